@@ -12,16 +12,14 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     info!("Takeoff!");
 
-    for i in 0..50 {
-        drone.take_off()?;
-    }
+    let status = drone.take_off()?;
+    info!("Takeoff: {:?}", status);
 
     info!("Wait 5 seconds and fly UP");
     std::thread::sleep(Duration::from_secs(5));
 
-    for i in 0..50 {
-        drone.landing()?;
-    }
+    let status = drone.landing()?;
+    info!("Landing: {:?}", status);
 
     Ok(())
 }
@@ -4,14 +4,14 @@ use arsdk_rs::{
 };
 
 pub use arsdk_rs::{
-    ardrone3::{ArDrone3, MediaStreaming, Piloting, PCMD},
+    ardrone3::{self, media_streaming, piloting, ArDrone3},
     prelude::*,
 };
 
 pub mod prelude {
     pub use crate::Anafi;
     pub use arsdk_rs::{
-        ardrone3::{ArDrone3, Piloting, PCMD},
+        ardrone3::{piloting, ArDrone3},
         prelude::*,
     };
 }
@@ -21,15 +21,18 @@ pub struct Anafi {
 }
 
 impl Anafi {
+    /// The `Product::Anafi` profile is used, unless the `config` already has one.
     pub fn connect(config: Config) -> Result<Self, ConnectionError> {
-        let drone = Drone::connect(config)?;
+        let drone = Drone::connect(config.or_product(Product::Anafi))?;
 
         Ok(Self { drone })
     }
 
     /// - Captain #Ferris 🦀 :Take off... 🛫
-    pub fn take_off(&self) -> Result<(), Error> {
-        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(Piloting::TakeOff)));
+    ///
+    /// Blocks until the drone acknowledges the command.
+    pub fn take_off(&self) -> Result<DeliveryStatus, Error> {
+        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::TakeOff)));
 
         let frame = Frame::for_drone(
             &self.drone,
@@ -38,19 +41,19 @@ impl Anafi {
             Some(feature),
         );
 
-        self.drone.send_frame(frame)
+        self.drone.send_frame_with_ack(frame)
     }
 
     pub fn up(&self, sequence_id: u8) -> Result<(), Error> {
-        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(Piloting::PCMD(PCMD {
-            flag: true,
+        let pcmd = piloting::Pcmd {
+            flag: 1,
             roll: 0,
             pitch: 0,
             yaw: 0,
             gaz: 100,
-            timestamp: Utc::now(),
-            sequence_id,
-        }))));
+            timestamp_and_seq_num: ardrone3::timestamp_and_seq_num(Utc::now(), sequence_id),
+        };
+        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::Pcmd(pcmd))));
 
         let frame = Frame::for_drone(&self.drone, Type::Data, BufferID::CDNonAck, Some(feature));
 
@@ -58,23 +61,24 @@ impl Anafi {
     }
 
     pub fn down(&self, sequence_id: u8) -> Result<(), Error> {
-        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(Piloting::PCMD(PCMD {
-            flag: true,
+        let pcmd = piloting::Pcmd {
+            flag: 1,
             roll: 0,
             pitch: 0,
             yaw: 0,
             gaz: -100,
-            timestamp: Utc::now(),
-            sequence_id,
-        }))));
+            timestamp_and_seq_num: ardrone3::timestamp_and_seq_num(Utc::now(), sequence_id),
+        };
+        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::Pcmd(pcmd))));
 
         let frame = Frame::for_drone(&self.drone, Type::Data, BufferID::CDNonAck, Some(feature));
 
         self.drone.send_frame(frame)
     }
 
-    pub fn landing(&self) -> Result<(), Error> {
-        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(Piloting::Landing)));
+    /// Blocks until the drone acknowledges the command.
+    pub fn landing(&self) -> Result<DeliveryStatus, Error> {
+        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::Landing)));
 
         let frame = Frame::for_drone(
             &self.drone,
@@ -83,6 +87,6 @@ impl Anafi {
             Some(feature),
         );
 
-        self.drone.send_frame(frame)
+        self.drone.send_frame_with_ack(frame)
     }
 }
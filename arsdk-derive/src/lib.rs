@@ -0,0 +1,614 @@
+//! `#[derive(FramePart)]` for the id-mapped Classes and Commands of `arsdk-rs`.
+//!
+//! On enums it generates the id conversions (`From<&Enum>` & `From<Enum>` for the id type)
+//! and the scroll `TryFromCtx` / `TryIntoCtx` impls: the id is followed by the fields of the variant, in order.
+//! On structs only the fields are (de)serialized.
+//!
+//! ```ignore
+//! #[derive(FramePart)]
+//! #[arsdk(id_type = "u16")]
+//! pub enum PilotingID {
+//!     #[arsdk(id = 0)]
+//!     Pilot(PilotState),
+//!     #[arsdk(id = 1)]
+//!     Posture,
+//! }
+//! ```
+//!
+//! Attributes:
+//! - `#[arsdk(id_type = "u8" | "u16" | "u32")]` on the enum - the type of the id on the wire
+//! - `#[arsdk(id = N)]` on each variant, `N` has to fit in the `id_type`
+//! - `#[arsdk(unknown)]` on a `Unknown { <id>: <id_type>, data: Vec<u8> }` variant - unknown ids are decoded as it
+//!   and the remaining bytes are kept in `data`, otherwise they fail with `Error::OutOfBound`
+//! - `#[arsdk(id = N, skip_decode)]` - the id is decoded as the `unknown` variant and encoding the variant
+//!   fails with `scroll::Error::BadInput` (for Classes & Commands whose arguments are not implemented yet)
+//!
+//! The fields are read & written with the `Endian` context, except `bool` which is a `u8` that must be `0` or `1`.
+//!
+//! The generated code uses `::arsdk_rs::frame::Error` and `::scroll`.
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DataEnum, DeriveInput, Error, Fields, Ident, Index, Lit,
+    LitInt, Meta, NestedMeta, Type, Variant,
+};
+
+#[proc_macro_derive(FramePart, attributes(arsdk))]
+pub fn derive_frame_part(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input)
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "FramePart can't be derived for generic types",
+        ));
+    }
+
+    match &input.data {
+        Data::Enum(data) => expand_enum(input, data),
+        Data::Struct(data) => {
+            let options = Options::parse(&input.attrs)?;
+            if options.id_type.is_some() {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "`id_type` is only supported on enums",
+                ));
+            }
+
+            Ok(expand_struct(&input.ident, &data.fields))
+        }
+        Data::Union(_) => Err(Error::new_spanned(
+            &input.ident,
+            "FramePart can't be derived for unions",
+        )),
+    }
+}
+
+#[derive(Default)]
+struct Options {
+    id_type: Option<Ident>,
+    id: Option<LitInt>,
+    unknown: bool,
+    skip_decode: bool,
+}
+
+impl Options {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("arsdk")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new_spanned(meta, "expected `#[arsdk(...)]`")),
+            };
+
+            for nested in list.nested {
+                match &nested {
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident("id_type") =>
+                    {
+                        options.id_type = match &name_value.lit {
+                            Lit::Str(id_type)
+                                if matches!(id_type.value().as_str(), "u8" | "u16" | "u32") =>
+                            {
+                                Some(Ident::new(&id_type.value(), id_type.span()))
+                            }
+                            lit => {
+                                return Err(Error::new_spanned(
+                                    lit,
+                                    "expected `\"u8\"`, `\"u16\"` or `\"u32\"`",
+                                ))
+                            }
+                        }
+                    }
+                    NestedMeta::Meta(Meta::NameValue(name_value))
+                        if name_value.path.is_ident("id") =>
+                    {
+                        options.id = match &name_value.lit {
+                            Lit::Int(id) => Some(id.clone()),
+                            lit => return Err(Error::new_spanned(lit, "expected an integer id")),
+                        }
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("unknown") => {
+                        options.unknown = true
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("skip_decode") => {
+                        options.skip_decode = true
+                    }
+                    nested => return Err(Error::new_spanned(nested, "unknown `arsdk` attribute")),
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// The largest id of the `id_type`
+fn max_id(id_type: &Ident) -> u64 {
+    if id_type == "u8" {
+        u8::MAX.into()
+    } else if id_type == "u16" {
+        u16::MAX.into()
+    } else {
+        u32::MAX.into()
+    }
+}
+
+/// The `#[arsdk(unknown)]` variant, e.g. `Unknown { class: u8, data: Vec<u8> }`
+struct Unknown<'a> {
+    variant: &'a Ident,
+    id: &'a Ident,
+    data: &'a Ident,
+}
+
+fn expand_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let param = name.to_string();
+    let id_type = Options::parse(&input.attrs)?.id_type.ok_or_else(|| {
+        Error::new_spanned(name, "missing `#[arsdk(id_type = \"...\")]` on the enum")
+    })?;
+
+    let mut unknown = None;
+    let mut ids = Vec::with_capacity(data.variants.len());
+    let mut id_arms = Vec::with_capacity(data.variants.len());
+    let mut decode_arms = Vec::with_capacity(data.variants.len());
+    let mut encode_arms = Vec::with_capacity(data.variants.len());
+
+    for variant in &data.variants {
+        let options = Options::parse(&variant.attrs)?;
+        let ident = &variant.ident;
+
+        if options.unknown {
+            if unknown.is_some() {
+                return Err(Error::new_spanned(ident, "duplicate `unknown` variant"));
+            }
+            let (id, data) = unknown_fields(variant)?;
+
+            id_arms.push(quote! { #name::#ident { #id, .. } => *#id, });
+            encode_arms.push(quote! {
+                Self::#ident { #data, .. } => {
+                    // scroll fails to write an empty slice at the end of the buffer
+                    if !#data.is_empty() {
+                        __this.gwrite_with(#data.as_slice(), &mut __offset, ())?;
+                    }
+                }
+            });
+            unknown = Some(Unknown {
+                variant: ident,
+                id,
+                data,
+            });
+
+            continue;
+        }
+
+        let id_lit = options
+            .id
+            .ok_or_else(|| Error::new_spanned(ident, "missing `#[arsdk(id = ...)]`"))?;
+        let id = id_lit.base10_parse::<u64>()?;
+        if id > max_id(&id_type) {
+            return Err(Error::new_spanned(
+                id_lit,
+                format!("id `{}` doesn't fit in `{}`", id, id_type),
+            ));
+        }
+        if ids.contains(&id) {
+            return Err(Error::new_spanned(ident, format!("duplicate id `{}`", id)));
+        }
+        ids.push(id);
+        let id = Literal::u64_unsuffixed(id);
+
+        let (pattern, bindings) = bind_fields(&variant.fields);
+        id_arms.push(match &variant.fields {
+            Fields::Unit => quote! { #name::#ident => #id, },
+            Fields::Unnamed(_) => quote! { #name::#ident(..) => #id, },
+            Fields::Named(_) => quote! { #name::#ident { .. } => #id, },
+        });
+
+        if options.skip_decode {
+            let unknown = data.variants.iter().find(|variant| is_unknown(variant));
+            let unknown = unknown.ok_or_else(|| {
+                Error::new_spanned(ident, "`skip_decode` requires an `unknown` variant")
+            })?;
+            // without its arguments the id alone would be an invalid Command
+            let msg = format!(
+                "`{}::{}` is not implemented, use `{}::{}`",
+                name, ident, name, unknown.ident
+            );
+            encode_arms.push(quote! {
+                Self::#ident { .. } => {
+                    return Err(::scroll::Error::BadInput { size: 0, msg: #msg }.into())
+                }
+            });
+        } else {
+            let writes = variant
+                .fields
+                .iter()
+                .zip(&bindings)
+                .map(|(field, binding)| write_field(&field.ty, quote! { #binding }));
+            encode_arms.push(quote! { Self::#ident #pattern => { #(#writes)* } });
+
+            let construct = construct(&variant.fields, &ident.to_string());
+            decode_arms.push(quote! { #id => Self::#ident #construct, });
+        }
+    }
+
+    let fallback = match unknown {
+        Some(Unknown { variant, id, data }) => quote! {
+            __id => {
+                let #data = __src[__offset..].to_vec();
+                __offset += #data.len();
+
+                Self::#variant { #id: __id, #data }
+            }
+        },
+        None => quote! {
+            __id => {
+                return Err(::arsdk_rs::frame::Error::OutOfBound {
+                    value: __id.into(),
+                    param: #param.to_string(),
+                })
+            }
+        },
+    };
+
+    Ok(quote! {
+        impl ::core::convert::From<&#name> for #id_type {
+            fn from(value: &#name) -> Self {
+                match value {
+                    #(#id_arms)*
+                }
+            }
+        }
+
+        impl ::core::convert::From<#name> for #id_type {
+            fn from(value: #name) -> Self {
+                Self::from(&value)
+            }
+        }
+
+        impl<'a> ::scroll::ctx::TryFromCtx<'a, ::scroll::Endian> for #name {
+            type Error = ::arsdk_rs::frame::Error;
+
+            fn try_from_ctx(
+                __src: &'a [u8],
+                __ctx: ::scroll::Endian,
+            ) -> ::core::result::Result<(Self, usize), Self::Error> {
+                use ::scroll::Pread;
+                let mut __offset = 0;
+
+                let __value = match __src.gread_with::<#id_type>(&mut __offset, __ctx)? {
+                    #(#decode_arms)*
+                    #fallback
+                };
+
+                Ok((__value, __offset))
+            }
+        }
+
+        impl ::scroll::ctx::TryIntoCtx<::scroll::Endian> for #name {
+            type Error = ::arsdk_rs::frame::Error;
+
+            fn try_into_ctx(
+                self,
+                __this: &mut [u8],
+                __ctx: ::scroll::Endian,
+            ) -> ::core::result::Result<usize, Self::Error> {
+                use ::scroll::Pwrite;
+                let mut __offset = 0;
+
+                __this.gwrite_with::<#id_type>((&self).into(), &mut __offset, __ctx)?;
+                match self {
+                    #(#encode_arms)*
+                }
+
+                Ok(__offset)
+            }
+        }
+    })
+}
+
+fn expand_struct(name: &Ident, fields: &Fields) -> TokenStream2 {
+    let construct = construct(fields, &name.to_string());
+    let writes = fields.iter().enumerate().map(|(index, field)| {
+        let member = match &field.ident {
+            Some(ident) => quote! { self.#ident },
+            None => {
+                let index = Index::from(index);
+                quote! { self.#index }
+            }
+        };
+
+        write_field(&field.ty, member)
+    });
+
+    quote! {
+        impl<'a> ::scroll::ctx::TryFromCtx<'a, ::scroll::Endian> for #name {
+            type Error = ::arsdk_rs::frame::Error;
+
+            fn try_from_ctx(
+                __src: &'a [u8],
+                __ctx: ::scroll::Endian,
+            ) -> ::core::result::Result<(Self, usize), Self::Error> {
+                use ::scroll::Pread;
+                let mut __offset = 0;
+
+                let __value = Self #construct;
+
+                Ok((__value, __offset))
+            }
+        }
+
+        impl ::scroll::ctx::TryIntoCtx<::scroll::Endian> for #name {
+            type Error = ::arsdk_rs::frame::Error;
+
+            fn try_into_ctx(
+                self,
+                __this: &mut [u8],
+                __ctx: ::scroll::Endian,
+            ) -> ::core::result::Result<usize, Self::Error> {
+                use ::scroll::Pwrite;
+                let mut __offset = 0;
+
+                #(#writes)*
+
+                Ok(__offset)
+            }
+        }
+    }
+}
+
+fn is_unknown(variant: &Variant) -> bool {
+    matches!(Options::parse(&variant.attrs), Ok(options) if options.unknown)
+}
+
+/// The id & data fields of the `unknown` variant
+fn unknown_fields(variant: &Variant) -> syn::Result<(&Ident, &Ident)> {
+    let idents = match &variant.fields {
+        Fields::Named(fields) if fields.named.len() == 2 => fields
+            .named
+            .iter()
+            .filter_map(|field| field.ident.as_ref())
+            .collect::<Vec<_>>(),
+        _ => {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                "the `unknown` variant should be `Unknown { <id>: <id_type>, data: Vec<u8> }`",
+            ))
+        }
+    };
+
+    Ok((idents[0], idents[1]))
+}
+
+/// The pattern binding the fields of a variant and the bindings, in order
+fn bind_fields(fields: &Fields) -> (TokenStream2, Vec<Ident>) {
+    match fields {
+        Fields::Unit => (quote! {}, vec![]),
+        Fields::Unnamed(unnamed) => {
+            let bindings = (0..unnamed.unnamed.len())
+                .map(|index| format_ident!("__field{}", index))
+                .collect::<Vec<_>>();
+
+            (quote! { ( #(#bindings),* ) }, bindings)
+        }
+        Fields::Named(named) => {
+            let bindings = named
+                .named
+                .iter()
+                .filter_map(|field| field.ident.clone())
+                .collect::<Vec<_>>();
+
+            (quote! { { #(#bindings),* } }, bindings)
+        }
+    }
+}
+
+/// Reads the fields, in order, e.g. `(field_0, field_1)` or `{ a: field_a }`
+///
+/// `param` is used in the errors of the unnamed `bool` fields.
+fn construct(fields: &Fields, param: &str) -> TokenStream2 {
+    match fields {
+        Fields::Unit => quote! {},
+        Fields::Unnamed(unnamed) => {
+            let reads = unnamed
+                .unnamed
+                .iter()
+                .map(|field| read_field(&field.ty, param));
+
+            quote! { ( #(#reads),* ) }
+        }
+        Fields::Named(named) => {
+            let reads = named.named.iter().map(|field| {
+                let ident = field.ident.as_ref().expect("Named fields have an ident");
+                let read = read_field(&field.ty, &ident.to_string());
+
+                quote! { #ident: #read }
+            });
+
+            quote! { { #(#reads),* } }
+        }
+    }
+}
+
+fn is_bool(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("bool"))
+}
+
+fn read_field(ty: &Type, param: &str) -> TokenStream2 {
+    if is_bool(ty) {
+        quote! {
+            match __src.gread_with::<u8>(&mut __offset, __ctx)? {
+                0 => false,
+                1 => true,
+                __value => {
+                    return Err(::arsdk_rs::frame::Error::OutOfBound {
+                        value: __value.into(),
+                        param: #param.to_string(),
+                    })
+                }
+            }
+        }
+    } else {
+        quote! { __src.gread_with::<#ty>(&mut __offset, __ctx)? }
+    }
+}
+
+fn write_field(ty: &Type, value: TokenStream2) -> TokenStream2 {
+    if is_bool(ty) {
+        quote! { __this.gwrite_with::<u8>(#value.into(), &mut __offset, __ctx)?; }
+    } else {
+        quote! { __this.gwrite_with(#value, &mut __offset, __ctx)?; }
+    }
+}
+
+#[cfg(test)]
+mod frame_part_tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn expand_error(input: DeriveInput) -> String {
+        expand(&input).expect_err("Should fail").to_string()
+    }
+
+    /// The `match` arms of the expanded code
+    #[derive(Default)]
+    struct Arms(Vec<syn::Arm>);
+
+    impl<'ast> syn::visit::Visit<'ast> for Arms {
+        fn visit_arm(&mut self, arm: &'ast syn::Arm) {
+            self.0.push(arm.clone());
+            syn::visit::visit_arm(self, arm);
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        let expanded = expand(&parse_quote! {
+            #[arsdk(id_type = "u16")]
+            enum PilotingID {
+                #[arsdk(id = 0)]
+                Pilot(PilotState),
+                #[arsdk(id = 1, skip_decode)]
+                Posture,
+                #[arsdk(unknown)]
+                Unknown { piloting: u16, data: Vec<u8> },
+            }
+        })
+        .expect("Should expand");
+        let file = syn::parse2::<syn::File>(expanded).expect("Should be valid items");
+
+        let from: syn::Path = parse_quote!(::core::convert::From<&PilotingID>);
+        let u16_type: Type = parse_quote!(u16);
+        assert!(file.items.iter().any(|item| matches!(
+            item,
+            syn::Item::Impl(item_impl)
+                if item_impl.trait_.as_ref().map(|(_, path, _)| path) == Some(&from)
+                    && *item_impl.self_ty == u16_type
+        )));
+
+        let mut arms = Arms::default();
+        syn::visit::visit_file(&mut arms, &file);
+        let arms = arms.0;
+
+        assert!(arms.contains(&parse_quote! {
+            PilotingID::Unknown { piloting, .. } => *piloting,
+        }));
+        // `Posture` has an id, but it's neither decoded nor encoded
+        assert!(arms.contains(&parse_quote! { PilotingID::Posture => 1, }));
+        let posture: syn::Expr = parse_quote!(Self::Posture);
+        assert!(!arms.iter().any(|arm| *arm.body == posture));
+        assert!(arms.contains(&parse_quote! {
+            Self::Posture { .. } => {
+                return Err(::scroll::Error::BadInput {
+                    size: 0,
+                    msg: "`PilotingID::Posture` is not implemented, use `PilotingID::Unknown`"
+                }
+                .into())
+            }
+        }));
+    }
+
+    #[test]
+    fn test_invalid_attributes() {
+        assert_eq!(
+            "missing `#[arsdk(id_type = \"...\")]` on the enum",
+            expand_error(parse_quote! {
+                enum Class {
+                    #[arsdk(id = 0)]
+                    Piloting,
+                }
+            })
+        );
+        assert_eq!(
+            "missing `#[arsdk(id = ...)]`",
+            expand_error(parse_quote! {
+                #[arsdk(id_type = "u8")]
+                enum Class {
+                    Piloting,
+                }
+            })
+        );
+        assert_eq!(
+            "duplicate id `0`",
+            expand_error(parse_quote! {
+                #[arsdk(id_type = "u8")]
+                enum Class {
+                    #[arsdk(id = 0)]
+                    Piloting,
+                    #[arsdk(id = 0)]
+                    PilotingState,
+                }
+            })
+        );
+        assert_eq!(
+            "id `256` doesn't fit in `u8`",
+            expand_error(parse_quote! {
+                #[arsdk(id_type = "u8")]
+                enum Class {
+                    #[arsdk(id = 256)]
+                    Piloting,
+                }
+            })
+        );
+        assert_eq!(
+            "id `65536` doesn't fit in `u16`",
+            expand_error(parse_quote! {
+                #[arsdk(id_type = "u16")]
+                enum Command {
+                    #[arsdk(id = 65535)]
+                    Last,
+                    #[arsdk(id = 65536)]
+                    Overflow,
+                }
+            })
+        );
+        assert_eq!(
+            "`skip_decode` requires an `unknown` variant",
+            expand_error(parse_quote! {
+                #[arsdk(id_type = "u8")]
+                enum Class {
+                    #[arsdk(id = 0, skip_decode)]
+                    Piloting,
+                }
+            })
+        );
+        assert_eq!(
+            "expected `\"u8\"`, `\"u16\"` or `\"u32\"`",
+            expand_error(parse_quote! {
+                #[arsdk(id_type = "u64")]
+                enum Class {}
+            })
+        );
+    }
+}
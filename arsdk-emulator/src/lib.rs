@@ -0,0 +1,669 @@
+//! A local Drone emulator speaking the ARSDK handshake and the ARNetwork protocol
+//!
+//! It allows testing crates built on `arsdk-rs` (e.g. `bebop2` & `jumpingsumo-rs`) without a Drone:
+//!
+//! * accepts the JSON handshake over TCP and replies with a [`Response`]
+//! * sends PINGs to the controller and answers its PINGs with PONGs
+//! * acknowledges every `Type::DataWithAck` frame
+//! * answers the `piloting::Command`s `TakeOff`, `Landing`, `Emergency` & `Pcmd`
+//!   with `piloting_state::Command::FlyingStateChanged` events
+//! * advertises its `_arsdk-XXXX._udp.local` service, see [`Config::mdns_addr`]
+//!
+//! ```
+//! use arsdk_emulator::{Config, Emulator};
+//! use arsdk_rs::Drone;
+//! use std::net::Ipv4Addr;
+//!
+//! let emulator = Emulator::start(Config::localhost()).expect("Should start the emulator");
+//!
+//! let config = arsdk_rs::Config::builder(Ipv4Addr::LOCALHOST)
+//!     .init_port(emulator.init_addr().port())
+//!     .d2c_port(0)
+//!     .local_addr(Ipv4Addr::LOCALHOST)
+//!     .build();
+//!
+//! let drone = Drone::connect(config).expect("Should connect to the emulator");
+//! ```
+use arsdk_rs::{
+    ardrone3::{
+        piloting::{Command as Piloting, Pcmd},
+        piloting_state::{self, FlyingStateChanged},
+        ArDrone3,
+    },
+    command::Feature,
+    frame::{BufferID, Type, ACK_BUFFER_OFFSET, HEADER_LENGTH},
+    handshake::{Request, Response, PROTOCOL_VERSION},
+    ArStream2, INIT_PORT,
+};
+use log::{debug, error, info, warn};
+use scroll::{Pread, Pwrite, LE};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    io::{self, Read, Write},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+mod mdns;
+
+/// The `c2d_port` of the real Drones
+pub const DEFAULT_C2D_PORT: u16 = 54321;
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_millis(500);
+/// The `arstream2_server_*` ports sent in the handshake
+pub const DEFAULT_ARSTREAM2: ArStream2 = ArStream2 {
+    stream_port: 5004,
+    control_port: 5005,
+};
+
+pub const DEFAULT_NAME: &str = "arsdk-emulator";
+/// The Bebop 2
+pub const DEFAULT_PRODUCT_ID: u16 = 0x090c;
+
+/// How often the threads check if the Emulator was dropped
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+/// The handshake request is a small JSON, anything bigger is refused
+const MAX_REQUEST_SIZE: usize = 4096;
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The TCP address for the handshake, port `0` picks an ephemeral port
+    pub init_addr: SocketAddr,
+    /// The UDP port we receive the frames on, sent as `c2d_port` in the handshake.
+    /// `0` picks an ephemeral port
+    pub c2d_port: u16,
+    pub ping_interval: Duration,
+    /// The server video streaming ports sent in the handshake
+    pub arstream2: Option<ArStream2>,
+    /// The UDP address of the mDNS responder, `None` to not advertise the Emulator
+    ///
+    /// Only the PTR queries asking for unicast answers are supported, see `arsdk_rs::discovery`.
+    pub mdns_addr: Option<SocketAddr>,
+    /// The advertised service instance name
+    pub name: String,
+    /// The advertised product id, e.g. `0x090c` for the Bebop 2
+    pub product_id: u16,
+}
+
+impl Default for Config {
+    /// The ports of a real Drone on all interfaces
+    fn default() -> Self {
+        Self {
+            init_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), INIT_PORT),
+            c2d_port: DEFAULT_C2D_PORT,
+            ping_interval: DEFAULT_PING_INTERVAL,
+            arstream2: Some(DEFAULT_ARSTREAM2),
+            mdns_addr: None,
+            name: DEFAULT_NAME.to_string(),
+            product_id: DEFAULT_PRODUCT_ID,
+        }
+    }
+}
+
+impl Config {
+    /// Ephemeral ports on `127.0.0.1`, so multiple Emulators can run at once, e.g. in tests
+    pub fn localhost() -> Self {
+        Self {
+            init_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+            c2d_port: 0,
+            mdns_addr: Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0)),
+            ..Self::default()
+        }
+    }
+}
+
+/// eARCOMMANDS_ARDRONE3_PILOTINGSTATE_FLYINGSTATECHANGED_STATE
+pub use piloting_state::FlyingStateChangedState as FlyingState;
+
+/// The states we go through when receiving the `piloting` command in the `state`
+fn transitions(state: FlyingState, piloting: &Piloting) -> &'static [FlyingState] {
+    use FlyingState::*;
+
+    match (piloting, state) {
+        (Piloting::TakeOff, Landed) => &[Takingoff, Hovering],
+        (Piloting::Landing, Takingoff)
+        | (Piloting::Landing, Hovering)
+        | (Piloting::Landing, Flying) => &[Landing, Landed],
+        (Piloting::Emergency, state) if state != Landed => &[Emergency, Landed],
+        (Piloting::Pcmd(pcmd), Hovering) if is_moving(pcmd) => &[Flying],
+        (Piloting::Pcmd(pcmd), Flying) if !is_moving(pcmd) => &[Hovering],
+        _ => &[],
+    }
+}
+
+/// `roll` & `pitch` are only taken into account when the `flag` is set
+fn is_moving(pcmd: &Pcmd) -> bool {
+    (pcmd.flag != 0 && (pcmd.roll != 0 || pcmd.pitch != 0)) || pcmd.yaw != 0 || pcmd.gaz != 0
+}
+
+#[derive(Debug)]
+struct Shared {
+    running: AtomicBool,
+    /// Where we send the frames to, the d2c address of the last handshake
+    controller: Mutex<Option<SocketAddr>>,
+    flying_state: Mutex<FlyingState>,
+    commands: Mutex<Vec<Feature>>,
+}
+
+/// Emulates a Drone until it's dropped
+#[derive(Debug)]
+pub struct Emulator {
+    init_addr: SocketAddr,
+    c2d_addr: SocketAddr,
+    mdns_addr: Option<SocketAddr>,
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Emulator {
+    /// Binds the handshake listener, the c2d socket & the mDNS responder and spawns their threads
+    pub fn start(config: Config) -> io::Result<Self> {
+        let listener = TcpListener::bind(config.init_addr)?;
+        // so the thread can notice when the Emulator is dropped
+        listener.set_nonblocking(true)?;
+        let init_addr = listener.local_addr()?;
+
+        let c2d = UdpSocket::bind(SocketAddr::new(init_addr.ip(), config.c2d_port))?;
+        c2d.set_read_timeout(Some(POLL_INTERVAL))?;
+        let c2d_addr = c2d.local_addr()?;
+
+        info!("{}: Waiting for the handshake", init_addr);
+        info!("{}: Receiving commands", c2d_addr);
+
+        let response = Response {
+            arstream_fragment_maximum_number: None,
+            arstream_fragment_size: None,
+            arstream_max_ack_interval: None,
+            arstream2: config.arstream2,
+            c2d_port: c2d_addr.port(),
+            c2d_update_port: 51,
+            c2d_user_port: 21,
+            status: 0,
+            qos_mode: 0,
+            proto_v: Some(PROTOCOL_VERSION),
+        };
+
+        let shared = Arc::new(Shared {
+            running: AtomicBool::new(true),
+            controller: Mutex::new(None),
+            flying_state: Mutex::new(FlyingState::Landed),
+            commands: Mutex::new(Vec::new()),
+        });
+
+        let mut threads = vec![
+            spawn_handshake(listener, response, shared.clone()),
+            spawn_network(c2d, config.ping_interval, shared.clone()),
+        ];
+
+        let mdns_addr = match config.mdns_addr {
+            Some(mdns_addr) => {
+                let socket = UdpSocket::bind(mdns_addr)?;
+                let mdns_addr = socket.local_addr()?;
+                let service = mdns::Service {
+                    name: config.name,
+                    product_id: config.product_id,
+                    addr: match init_addr.ip() {
+                        IpAddr::V4(ip) if !ip.is_unspecified() => Some(ip),
+                        _ => None,
+                    },
+                    init_port: init_addr.port(),
+                };
+
+                threads.push(mdns::spawn_responder(socket, service, shared.clone())?);
+
+                Some(mdns_addr)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            init_addr,
+            c2d_addr,
+            mdns_addr,
+            shared,
+            threads,
+        })
+    }
+
+    /// The actual address of the handshake listener, when the `Config::init_addr` port is `0`
+    pub fn init_addr(&self) -> SocketAddr {
+        self.init_addr
+    }
+
+    pub fn c2d_addr(&self) -> SocketAddr {
+        self.c2d_addr
+    }
+
+    /// The actual address of the mDNS responder, see `Config::mdns_addr`
+    pub fn mdns_addr(&self) -> Option<SocketAddr> {
+        self.mdns_addr
+    }
+
+    /// The d2c address of the controller, `None` until the handshake
+    pub fn controller_addr(&self) -> Option<SocketAddr> {
+        *lock(&self.shared.controller)
+    }
+
+    pub fn flying_state(&self) -> FlyingState {
+        *lock(&self.shared.flying_state)
+    }
+
+    /// All the commands received so far
+    pub fn commands(&self) -> Vec<Feature> {
+        lock(&self.shared.commands).clone()
+    }
+}
+
+impl Drop for Emulator {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::SeqCst);
+
+        for thread in self.threads.drain(..) {
+            if thread.join().is_err() {
+                error!("Emulator thread panicked");
+            }
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().expect("Emulator lock poisoned")
+}
+
+fn spawn_handshake(
+    listener: TcpListener,
+    response: Response,
+    shared: Arc<Shared>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        while shared.running.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, peer)) => match handshake(stream, &response) {
+                    Ok(request) => {
+                        let controller = SocketAddr::new(peer.ip(), request.d2c_port);
+                        info!(
+                            "{}: Controller {} connected",
+                            controller, request.controller_name
+                        );
+
+                        *lock(&shared.controller) = Some(controller);
+                    }
+                    Err(err) => error!("{}: Handshake failed - {}", peer, err),
+                },
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+                Err(err) => {
+                    error!("Accepting handshake connection: {}", err);
+                    thread::sleep(POLL_INTERVAL);
+                }
+            }
+        }
+    })
+}
+
+fn handshake(mut stream: TcpStream, response: &Response) -> io::Result<Request> {
+    // the accepted stream can inherit the non-blocking mode of the listener
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+
+    let request = read_request(&mut stream)?;
+    debug!("Request: {:?}", request);
+
+    let mut response_bytes = serde_json::to_vec(response)?;
+    // the Drones terminate the response with `\u{0}`
+    response_bytes.push(0);
+
+    stream.write_all(&response_bytes)?;
+
+    Ok(request)
+}
+
+/// The request is not terminated, so we read until it's a whole JSON
+fn read_request(stream: &mut impl Read) -> io::Result<Request> {
+    let mut request = Vec::new();
+    let mut buf = [0_u8; 256];
+
+    loop {
+        let read = stream.read(&mut buf)?;
+
+        if read == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Connection closed before the end of the request",
+            ));
+        }
+
+        request.extend_from_slice(&buf[..read]);
+
+        let json = request.split(|byte| *byte == 0).next().unwrap_or_default();
+
+        match serde_json::from_slice(json) {
+            Ok(request) => return Ok(request),
+            Err(err) if err.is_eof() && request.len() < MAX_REQUEST_SIZE => continue,
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+fn spawn_network(
+    socket: UdpSocket,
+    ping_interval: Duration,
+    shared: Arc<Shared>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut network = Network {
+            socket,
+            shared,
+            sequence_ids: HashMap::new(),
+            started: Instant::now(),
+        };
+        let mut last_ping = Instant::now();
+        let mut buf = [0_u8; 65_535];
+
+        while network.shared.running.load(Ordering::SeqCst) {
+            if last_ping.elapsed() >= ping_interval {
+                network.ping();
+                last_ping = Instant::now();
+            }
+
+            match network.socket.recv_from(&mut buf) {
+                Ok((read, origin)) => {
+                    debug!("Received: {} bytes from {}", read, origin);
+
+                    network.handle_datagram(&buf[..read]);
+                }
+                Err(err)
+                    if err.kind() == io::ErrorKind::WouldBlock
+                        || err.kind() == io::ErrorKind::TimedOut => {}
+                Err(err) => error!("Receiving frames: {}", err),
+            }
+        }
+    })
+}
+
+struct Network {
+    socket: UdpSocket,
+    shared: Arc<Shared>,
+    /// The last sequence id per buffer id of the frames we've sent
+    sequence_ids: HashMap<u8, u8>,
+    /// The PINGs carry the time since we've started
+    started: Instant,
+}
+
+impl Network {
+    fn sequence_id(&mut self, buffer_id: u8) -> u8 {
+        let sequence_id = self.sequence_ids.entry(buffer_id).or_insert(u8::MAX);
+        *sequence_id = sequence_id.wrapping_add(1);
+
+        *sequence_id
+    }
+
+    fn send(&mut self, frame_type: Type, buffer_id: u8, data: &[u8]) {
+        let controller = match *lock(&self.shared.controller) {
+            Some(controller) => controller,
+            // no handshake yet
+            None => return,
+        };
+
+        let length = HEADER_LENGTH + data.len();
+        let mut frame = Vec::with_capacity(length);
+        frame.push(frame_type.into());
+        frame.push(buffer_id);
+        frame.push(self.sequence_id(buffer_id));
+        frame.extend_from_slice(&(length as u32).to_le_bytes());
+        frame.extend_from_slice(data);
+
+        if let Err(err) = self.socket.send_to(&frame, controller) {
+            error!("{}: Sending frame - {}", controller, err);
+        }
+    }
+
+    /// The time since we've started as `struct timespec`
+    fn ping(&mut self) {
+        let elapsed = self.started.elapsed();
+
+        let mut data = Vec::with_capacity(16);
+        data.extend_from_slice(&elapsed.as_secs().to_le_bytes());
+        data.extend_from_slice(&u64::from(elapsed.subsec_nanos()).to_le_bytes());
+
+        self.send(Type::Data, BufferID::PING.into(), &data);
+    }
+
+    fn send_event(&mut self, feature: Feature) {
+        let mut data = [0_u8; 256];
+
+        match data.pwrite_with(feature, 0, LE) {
+            Ok(written) => self.send(
+                Type::DataWithAck,
+                BufferID::DCEvent.into(),
+                &data[..written],
+            ),
+            Err(err) => error!("Writing event: {}", err),
+        }
+    }
+
+    fn handle_datagram(&mut self, datagram: &[u8]) {
+        let mut offset = 0;
+
+        while offset < datagram.len() {
+            let frame = &datagram[offset..];
+
+            let length = match frame.pread_with::<u32>(3, LE) {
+                Ok(length) => length as usize,
+                Err(_) => {
+                    warn!("Ignoring {} bytes of incomplete frame header", frame.len());
+                    return;
+                }
+            };
+
+            if length < HEADER_LENGTH || length > frame.len() {
+                warn!(
+                    "Ignoring frame of length {} with {} bytes left",
+                    length,
+                    frame.len()
+                );
+                return;
+            }
+
+            self.handle_frame(frame[0], frame[1], frame[2], &frame[HEADER_LENGTH..length]);
+
+            offset += length;
+        }
+    }
+
+    fn handle_frame(&mut self, frame_type: u8, buffer_id: u8, sequence_id: u8, data: &[u8]) {
+        if let Ok(Type::DataWithAck) = Type::try_from(frame_type) {
+            self.send(
+                Type::Ack,
+                buffer_id.wrapping_add(ACK_BUFFER_OFFSET),
+                &[sequence_id],
+            );
+        }
+
+        match BufferID::try_from(buffer_id) {
+            Ok(BufferID::CDNonAck) | Ok(BufferID::CDAck) | Ok(BufferID::CDEmergency) => {
+                match data.pread_with::<Feature>(0, LE) {
+                    Ok(feature) => self.command(feature),
+                    Err(err) => warn!("Unknown command on buffer {}: {}", buffer_id, err),
+                }
+            }
+            // the same data is echoed back
+            Ok(BufferID::PING) => self.send(Type::Data, BufferID::PONG.into(), data),
+            // PONGs & Acks of our events
+            _ => {}
+        }
+    }
+
+    fn command(&mut self, feature: Feature) {
+        info!("Command: {:?}", feature);
+
+        lock(&self.shared.commands).push(feature.clone());
+
+        if let Feature::ArDrone3(Some(ArDrone3::Piloting(piloting))) = feature {
+            let transitions = transitions(self.flying_state(), &piloting);
+
+            for state in transitions {
+                *lock(&self.shared.flying_state) = *state;
+
+                self.send_event(flying_state_changed(*state));
+            }
+        }
+    }
+
+    fn flying_state(&self) -> FlyingState {
+        *lock(&self.shared.flying_state)
+    }
+}
+
+fn flying_state_changed(state: FlyingState) -> Feature {
+    Feature::ArDrone3(Some(ArDrone3::PilotingState(
+        piloting_state::Command::FlyingStateChanged(FlyingStateChanged { state }),
+    )))
+}
+
+#[cfg(test)]
+mod emulator_tests {
+    use super::*;
+    use arsdk_rs::{
+        discovery::{Device, Discovery},
+        event::{Event, Filter},
+        frame::{Frame, FrameType},
+        DeliveryStatus, Drone, Product,
+    };
+    use std::sync::mpsc::Receiver;
+
+    fn connect(emulator: &Emulator) -> Drone {
+        let config = arsdk_rs::Config::builder(Ipv4Addr::LOCALHOST)
+            .init_port(emulator.init_addr().port())
+            .d2c_port(0)
+            .local_addr(Ipv4Addr::LOCALHOST)
+            .build();
+
+        Drone::connect(config).expect("Should connect to the emulator")
+    }
+
+    fn recv_flying_state(events: &Receiver<Event>) -> FlyingState {
+        match events.recv_timeout(Duration::from_secs(2)) {
+            Ok(Event::Frame(FrameType::Known(Frame {
+                feature:
+                    Some(Feature::ArDrone3(Some(ArDrone3::PilotingState(
+                        piloting_state::Command::FlyingStateChanged(FlyingStateChanged { state }),
+                    )))),
+                ..
+            }))) => state,
+            other => panic!("Expected FlyingStateChanged, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_take_off() {
+        let emulator = Emulator::start(Config::localhost()).expect("Should start the emulator");
+        let drone = connect(&emulator);
+
+        let handshake = drone
+            .handshake_response()
+            .expect("Should have the handshake response");
+        assert_eq!(emulator.c2d_addr().port(), handshake.c2d_port);
+        assert_eq!(PROTOCOL_VERSION, handshake.protocol_version());
+
+        // ArDrone3 PilotingState
+        let events = drone.subscribe_with(Filter::feature(1).class(4));
+
+        let take_off = Frame::for_drone(
+            &drone,
+            Type::DataWithAck,
+            BufferID::CDAck,
+            Some(Feature::ArDrone3(Some(ArDrone3::Piloting(
+                Piloting::TakeOff,
+            )))),
+        );
+
+        assert_eq!(
+            DeliveryStatus::Acked,
+            drone
+                .send_frame_with_ack(take_off)
+                .expect("Should send TakeOff")
+        );
+
+        assert_eq!(FlyingState::Takingoff, recv_flying_state(&events));
+        assert_eq!(FlyingState::Hovering, recv_flying_state(&events));
+        assert_eq!(FlyingState::Hovering, emulator.flying_state());
+
+        let stats = drone.stats();
+        assert!(stats.ack_latency.is_some());
+        assert_eq!(0, stats.retransmits);
+        assert!(stats.sent.frames > 0);
+        assert_eq!(2, stats.buffers[&BufferID::DCEvent].received);
+    }
+
+    #[test]
+    fn test_discovery() {
+        let emulator = Emulator::start(Config::localhost()).expect("Should start the emulator");
+
+        let devices = Discovery::new()
+            .responder(emulator.mdns_addr().expect("Should advertise the emulator"))
+            .timeout(Duration::from_millis(500))
+            .browse()
+            .expect("Should browse");
+
+        assert_eq!(
+            vec![Device {
+                name: DEFAULT_NAME.to_string(),
+                product_id: DEFAULT_PRODUCT_ID,
+                product: Some(Product::Bebop2),
+                addr: Ipv4Addr::LOCALHOST.into(),
+                init_port: emulator.init_addr().port(),
+                device_id: Some(DEFAULT_NAME.to_string()),
+            }],
+            devices
+        );
+
+        let config = arsdk_rs::Config::from(devices[0].clone());
+        let config = arsdk_rs::Config {
+            d2c_port: 0,
+            local_addr: Some(Ipv4Addr::LOCALHOST.into()),
+            ..config
+        };
+        let drone = Drone::connect(config).expect("Should connect to the discovered emulator");
+        assert!(drone.is_connected());
+    }
+
+    #[test]
+    fn test_round_trip_time() {
+        let emulator = Emulator::start(Config::localhost()).expect("Should start the emulator");
+        let drone = connect(&emulator);
+
+        let deadline = Instant::now() + arsdk_rs::PING_INTERVAL * 3;
+        while drone.stats().rtt.is_none() {
+            assert!(Instant::now() < deadline, "Should get a PONG");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        assert!(drone.stats().received.frames > 0);
+    }
+
+    #[test]
+    fn test_read_request() {
+        let request =
+            br#"{"controller_name":"arsdk-rs","controller_type":"computer","d2c_port":43210}"#;
+
+        let request = read_request(&mut &request[..]).expect("Should read the Request");
+
+        assert_eq!(43210, request.d2c_port);
+        assert_eq!(None, request.arstream2);
+
+        let truncated = br#"{"controller_name":"arsdk-rs","#;
+
+        assert_eq!(
+            io::ErrorKind::UnexpectedEof,
+            read_request(&mut &truncated[..])
+                .expect_err("Should not read a truncated Request")
+                .kind()
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use arsdk_emulator::{Config, Emulator};
+use std::{env, error::Error, thread};
+
+/// Usage: `arsdk-emulator [INIT_ADDR] [C2D_PORT]`
+///
+/// e.g. `RUST_LOG=info arsdk-emulator 127.0.0.1:44444 54321`
+fn main() -> Result<(), Box<dyn Error>> {
+    env_logger::init();
+
+    let mut config = Config::default();
+    let mut args = env::args().skip(1);
+
+    if let Some(init_addr) = args.next() {
+        config.init_addr = init_addr.parse()?;
+    }
+
+    if let Some(c2d_port) = args.next() {
+        config.c2d_port = c2d_port.parse()?;
+    }
+
+    let emulator = Emulator::start(config)?;
+
+    println!(
+        "Emulating a Drone, handshake at {} & commands at {}",
+        emulator.init_addr(),
+        emulator.c2d_addr()
+    );
+
+    loop {
+        thread::park();
+    }
+}
@@ -0,0 +1,181 @@
+//! A minimal mDNS responder advertising the `_arsdk-XXXX._udp.local` service of the Emulator
+//!
+//! It only answers the PTR queries of its service type, with the SRV, TXT & A records
+//! in the additional section, which is what `arsdk_rs::discovery` browses for.
+use crate::{Shared, POLL_INTERVAL};
+use arsdk_rs::discovery::service_type;
+use dns_parser::{Packet, QueryType};
+use log::{debug, error, info};
+use std::{
+    io,
+    net::{Ipv4Addr, UdpSocket},
+    sync::{atomic::Ordering, Arc},
+    thread::{self, JoinHandle},
+};
+
+/// TTL of the records, the default of the DNS-SD host records
+const TTL: u32 = 120;
+const CLASS_IN: u16 = 1;
+const TYPE_A: u16 = 1;
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Service {
+    /// The instance name, e.g. `arsdk-emulator`
+    pub(crate) name: String,
+    pub(crate) product_id: u16,
+    /// `None` when listening on all the interfaces, the answer's origin is the address then
+    pub(crate) addr: Option<Ipv4Addr>,
+    pub(crate) init_port: u16,
+}
+
+impl Service {
+    fn service_type(&self) -> String {
+        service_type(self.product_id)
+    }
+
+    fn instance(&self) -> String {
+        format!("{}.{}", self.name, self.service_type())
+    }
+
+    fn host(&self) -> String {
+        format!("{}.local", self.name)
+    }
+
+    /// PTR answer, SRV, TXT & A additional records
+    fn response(&self, id: u16) -> Vec<u8> {
+        let mut packet = Vec::new();
+        let additional = if self.addr.is_some() { 3 } else { 2 };
+        // response & authoritative
+        for field in [id, 0x8400, 0, 1, 0, additional].iter() {
+            packet.extend_from_slice(&field.to_be_bytes());
+        }
+
+        let mut ptr = Vec::new();
+        write_name(&mut ptr, &self.instance());
+        write_record(&mut packet, &self.service_type(), TYPE_PTR, &ptr);
+
+        // priority & weight
+        let mut srv = vec![0, 0, 0, 0];
+        srv.extend_from_slice(&self.init_port.to_be_bytes());
+        write_name(&mut srv, &self.host());
+        write_record(&mut packet, &self.instance(), TYPE_SRV, &srv);
+
+        let device_id = format!(r#"{{"device_id":"{}"}}"#, self.name);
+        let mut txt = vec![device_id.len() as u8];
+        txt.extend_from_slice(device_id.as_bytes());
+        write_record(&mut packet, &self.instance(), TYPE_TXT, &txt);
+
+        if let Some(addr) = self.addr {
+            write_record(&mut packet, &self.host(), TYPE_A, &addr.octets());
+        }
+
+        packet
+    }
+
+    /// Whether or not the `packet` is a query for our service type
+    fn is_queried(&self, packet: &Packet) -> bool {
+        let service_type = self.service_type();
+
+        packet.questions.iter().any(|question| {
+            question.qtype == QueryType::PTR && question.qname.to_string() == service_type
+        })
+    }
+}
+
+/// The names are written without compression
+fn write_name(buf: &mut Vec<u8>, name: &str) {
+    for label in name.split('.') {
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+
+    buf.push(0);
+}
+
+fn write_record(buf: &mut Vec<u8>, name: &str, record_type: u16, data: &[u8]) {
+    write_name(buf, name);
+    buf.extend_from_slice(&record_type.to_be_bytes());
+    buf.extend_from_slice(&CLASS_IN.to_be_bytes());
+    buf.extend_from_slice(&TTL.to_be_bytes());
+    buf.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    buf.extend_from_slice(data);
+}
+
+pub(crate) fn spawn_responder(
+    socket: UdpSocket,
+    service: Service,
+    shared: Arc<Shared>,
+) -> io::Result<JoinHandle<()>> {
+    socket.set_read_timeout(Some(POLL_INTERVAL))?;
+    info!(
+        "{}: Advertising {}",
+        socket.local_addr()?,
+        service.instance()
+    );
+
+    Ok(thread::spawn(move || {
+        let mut buf = [0_u8; 9000];
+
+        while shared.running.load(Ordering::SeqCst) {
+            let (read, origin) = match socket.recv_from(&mut buf) {
+                Ok(received) => received,
+                Err(err)
+                    if err.kind() == io::ErrorKind::WouldBlock
+                        || err.kind() == io::ErrorKind::TimedOut =>
+                {
+                    continue
+                }
+                Err(err) => {
+                    error!("Receiving mDNS query: {}", err);
+                    thread::sleep(POLL_INTERVAL);
+                    continue;
+                }
+            };
+
+            let id = match Packet::parse(&buf[..read]) {
+                Ok(packet) if packet.header.query && service.is_queried(&packet) => {
+                    packet.header.id
+                }
+                Ok(_) => continue,
+                Err(err) => {
+                    debug!("{}: Invalid mDNS query - {}", origin, err);
+                    continue;
+                }
+            };
+
+            // a unicast answer to the port of the query
+            if let Err(err) = socket.send_to(&service.response(id), origin) {
+                error!("{}: Sending mDNS response failed - {}", origin, err);
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod mdns_tests {
+    use super::*;
+
+    #[test]
+    fn test_response_is_parsable() {
+        let service = Service {
+            name: "emulator".to_string(),
+            product_id: 0x090c,
+            addr: Some(Ipv4Addr::LOCALHOST),
+            init_port: 44444,
+        };
+
+        let response = service.response(0);
+        let packet = Packet::parse(&response).expect("Should parse the response");
+
+        assert!(!packet.header.query);
+        assert_eq!(1, packet.answers.len());
+        assert_eq!(3, packet.additional.len());
+        assert_eq!(
+            "emulator._arsdk-090c._udp.local",
+            packet.additional[0].name.to_string()
+        );
+    }
+}
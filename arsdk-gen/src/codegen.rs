@@ -0,0 +1,650 @@
+//! Emits the Rust modules of the generated Classes of `arsdk-rs` from the [`model`](crate::model).
+//!
+//! Every Class gets its own file with a `Command` enum, an argument struct per Command with arguments
+//! and an enum per `enum` argument.
+//! All of them implement scroll's `TryFromCtx` & `TryIntoCtx` and the Command ids are available
+//! with `Into<u16>`, like the hand-written commands in `arsdk-rs`.
+use crate::model::{Arg, ArgType, Class, Command, Feature};
+use std::fmt::Write;
+
+const HEADER: &str = "// @generated by `arsdk-gen`, do not edit by hand!";
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "do", "dyn", "else",
+    "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop", "macro",
+    "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self", "static",
+    "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// `motor_ramping` => `MotorRamping`, `moveBy` => `MoveBy`, `RS_TRAVIS` => `RsTravis`
+pub fn pascal_case(name: &str) -> String {
+    let pascal = name
+        .split(&['_', '-'][..])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            // all-caps parts are acronyms, e.g. `PCMD` or `GPS`
+            let part = if part.chars().any(char::is_lowercase) {
+                part.to_string()
+            } else {
+                part.to_lowercase()
+            };
+            let mut chars = part.chars();
+
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+
+    // e.g. `5GHz` is not an identifier
+    match pascal.chars().next() {
+        Some(first) if first.is_ascii_digit() => format!("N{}", pascal),
+        _ => pascal,
+    }
+}
+
+/// `timestampAndSeqNum` => `timestamp_and_seq_num`, `GPSState` => `gps_state`
+pub fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::with_capacity(name.len() + 4);
+
+    for (index, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !snake.is_empty() && !snake.ends_with('_') {
+                snake.push('_');
+            }
+            continue;
+        }
+
+        if c.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lowercase =
+                matches!(chars.get(index + 1), Some(next) if next.is_lowercase());
+            let word_start = previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lowercase);
+
+            if word_start && !snake.ends_with('_') {
+                snake.push('_');
+            }
+        }
+
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
+
+/// Escapes the keywords, e.g. `type` => `r#type`
+pub fn identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// The module of a Class, `source` is the definition of its `feature`
+pub fn class_module(feature: &Feature, class: &Class, source: &str) -> String {
+    let has_strings = class
+        .commands
+        .iter()
+        .flat_map(|command| &command.args)
+        .any(|arg| arg.ty == ArgType::String);
+    let parse = if has_strings {
+        "read_string, read_unknown, write_bytes, write_string"
+    } else {
+        "read_unknown, write_bytes"
+    };
+    let mut out = String::new();
+
+    writeln!(out, "{}", HEADER).unwrap();
+    writeln!(out, "//! `{}`: {}", class.id, class.doc).unwrap();
+    writeln!(out, "//!").unwrap();
+    writeln!(
+        out,
+        "//! Generated from the `{}` Class of `arsdk-gen/xml/{}` (the `{}` Feature),",
+        class.name, source, feature.name
+    )
+    .unwrap();
+    writeln!(out, "//! regenerate it with `cargo run -p arsdk-gen`.").unwrap();
+    writeln!(out, "use crate::{{").unwrap();
+    writeln!(out, "    frame::Error,").unwrap();
+    writeln!(out, "    parse::{{{}}},", parse).unwrap();
+    writeln!(out, "}};").unwrap();
+    writeln!(out, "use scroll::{{ctx, Endian, Pread, Pwrite}};").unwrap();
+    writeln!(out).unwrap();
+
+    let variants = class
+        .commands
+        .iter()
+        .map(|command| Variant {
+            name: pascal_case(&command.name),
+            doc: format!("`{}`: {}", command.id, command.doc),
+            payload: if command.args.is_empty() {
+                None
+            } else {
+                Some(pascal_case(&command.name))
+            },
+            id: command.id.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let id_enum = IdEnum {
+        name: "Command",
+        id_type: "u16",
+        unknown: "command",
+        unknown_doc: "A Command that is not in the definitions",
+        eq: class.commands.iter().all(is_eq),
+        variants,
+    };
+    out.push_str(&id_enum.emit());
+
+    for command in class
+        .commands
+        .iter()
+        .filter(|command| !command.args.is_empty())
+    {
+        out.push('\n');
+        out.push_str(&args_struct(command));
+
+        for arg in &command.args {
+            if let ArgType::Enum(_) = arg.ty {
+                out.push('\n');
+                out.push_str(&arg_enum(command, arg));
+            }
+        }
+    }
+
+    out
+}
+
+/// The floats are the only arguments which are not `Eq`
+fn is_eq(command: &Command) -> bool {
+    command
+        .args
+        .iter()
+        .all(|arg| !matches!(arg.ty, ArgType::Float | ArgType::Double))
+}
+
+/// The Rust type of the argument
+fn arg_type(command: &Command, arg: &Arg) -> String {
+    match &arg.ty {
+        ArgType::Enum(_) => arg_enum_name(command, arg),
+        ty => rust_type(ty).into(),
+    }
+}
+
+fn rust_type(ty: &ArgType) -> &'static str {
+    match ty {
+        ArgType::U8 => "u8",
+        ArgType::I8 => "i8",
+        ArgType::U16 => "u16",
+        ArgType::I16 => "i16",
+        ArgType::U32 => "u32",
+        ArgType::I32 => "i32",
+        ArgType::U64 => "u64",
+        ArgType::I64 => "i64",
+        ArgType::Float => "f32",
+        ArgType::Double => "f64",
+        ArgType::String => "String",
+        ArgType::Bitfield { ty, .. } => rust_type(ty),
+        ArgType::Multisetting => "Vec<u8>",
+        ArgType::Enum(_) => unreachable!("The enums are named after their argument"),
+    }
+}
+
+/// e.g. `FlyingStateChangedState` for the `state` of `FlyingStateChanged`
+fn arg_enum_name(command: &Command, arg: &Arg) -> String {
+    format!("{}{}", pascal_case(&command.name), pascal_case(&arg.name))
+}
+
+fn args_struct(command: &Command) -> String {
+    let name = pascal_case(&command.name);
+    let mut out = String::new();
+
+    writeln!(out, "#[derive({})]", derives(is_eq(command))).unwrap();
+    writeln!(out, "/// {}", command.doc).unwrap();
+    writeln!(out, "pub struct {} {{", name).unwrap();
+    for arg in &command.args {
+        if !arg.doc.is_empty() {
+            writeln!(out, "    /// {}", arg.doc).unwrap();
+        }
+        if let ArgType::Bitfield { of, .. } = &arg.ty {
+            if !arg.doc.is_empty() {
+                writeln!(out, "    ///").unwrap();
+            }
+            writeln!(out, "    /// A bitfield of the `{}` values", of).unwrap();
+        }
+        writeln!(
+            out,
+            "    pub {}: {},",
+            identifier(&snake_case(&arg.name)),
+            arg_type(command, arg)
+        )
+        .unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+
+    let mut reads = String::new();
+    let mut writes = String::new();
+    for arg in &command.args {
+        let field = identifier(&snake_case(&arg.name));
+
+        if arg.ty == ArgType::Multisetting {
+            writeln!(
+                reads,
+                "            {}: read_unknown(src, &mut offset)?,",
+                field
+            )
+            .unwrap();
+            writeln!(
+                writes,
+                "        write_bytes(this, &self.{}, &mut offset)?;",
+                field
+            )
+            .unwrap();
+        } else if arg.ty == ArgType::String {
+            writeln!(
+                reads,
+                "            {}: read_string(src, &mut offset)?,",
+                field
+            )
+            .unwrap();
+            writeln!(
+                writes,
+                "        write_string(this, &self.{}, &mut offset)?;",
+                field
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                reads,
+                "            {}: src.gread_with(&mut offset, ctx)?,",
+                field
+            )
+            .unwrap();
+            writeln!(
+                writes,
+                "        this.gwrite_with(self.{}, &mut offset, ctx)?;",
+                field
+            )
+            .unwrap();
+        }
+    }
+
+    // only the strings & the multisettings are written without the endianness
+    let ctx = if command
+        .args
+        .iter()
+        .all(|arg| matches!(arg.ty, ArgType::String | ArgType::Multisetting))
+    {
+        "_ctx"
+    } else {
+        "ctx"
+    };
+
+    write!(
+        out,
+        r#"impl<'a> ctx::TryFromCtx<'a, Endian> for {name} {{
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], {ctx}: Endian) -> Result<(Self, usize), Self::Error> {{
+        let mut offset = 0;
+
+        let args = Self {{
+{reads}        }};
+
+        Ok((args, offset))
+    }}
+}}
+
+impl ctx::TryIntoCtx<Endian> for {name} {{
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], {ctx}: Endian) -> Result<usize, Self::Error> {{
+        let mut offset = 0;
+
+{writes}
+        Ok(offset)
+    }}
+}}
+"#,
+        name = name,
+        ctx = ctx,
+        reads = reads,
+        writes = writes,
+    )
+    .unwrap();
+
+    out
+}
+
+fn arg_enum(command: &Command, arg: &Arg) -> String {
+    let values = match &arg.ty {
+        ArgType::Enum(values) => values,
+        _ => unreachable!("Only enum arguments have values"),
+    };
+    let name = arg_enum_name(command, arg);
+    let mut out = String::new();
+
+    writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]").unwrap();
+    if !arg.doc.is_empty() {
+        writeln!(out, "/// {}", arg.doc).unwrap();
+    }
+    writeln!(out, "pub enum {} {{", name).unwrap();
+    for (index, value) in values.iter().enumerate() {
+        if value.doc.is_empty() {
+            writeln!(out, "    /// `{}`", index).unwrap();
+        } else {
+            writeln!(out, "    /// `{}`: {}", index, value.doc).unwrap();
+        }
+        writeln!(out, "    {},", pascal_case(&value.name)).unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+
+    let mut into_arms = String::new();
+    let mut from_arms = String::new();
+    for (index, value) in values.iter().enumerate() {
+        let variant = pascal_case(&value.name);
+
+        writeln!(into_arms, "            {}::{} => {},", name, variant, index).unwrap();
+        writeln!(from_arms, "            {} => Self::{},", index, variant).unwrap();
+    }
+
+    write!(
+        out,
+        r#"impl From<{name}> for u32 {{
+    fn from(value: {name}) -> Self {{
+        match value {{
+{into_arms}        }}
+    }}
+}}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for {name} {{
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {{
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {{
+{from_arms}            value => {{
+                return Err(Error::OutOfBound {{
+                    value: value.into(),
+                    param: "{name}".to_string(),
+                }})
+            }}
+        }};
+
+        Ok((value, offset))
+    }}
+}}
+
+impl ctx::TryIntoCtx<Endian> for {name} {{
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {{
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }}
+}}
+"#,
+        name = name,
+        into_arms = into_arms,
+        from_arms = from_arms,
+    )
+    .unwrap();
+
+    out
+}
+
+fn derives(eq: bool) -> &'static str {
+    if eq {
+        "Debug, Clone, PartialEq, Eq"
+    } else {
+        "Debug, Clone, PartialEq"
+    }
+}
+
+struct Variant {
+    name: String,
+    doc: String,
+    /// The type the variant holds, if any
+    payload: Option<String>,
+    /// The expression of the variant's id
+    id: String,
+}
+
+/// An enum of Commands, each with its own id and an `Unknown` variant for the rest
+struct IdEnum {
+    name: &'static str,
+    id_type: &'static str,
+    /// The field of `Unknown` with the id
+    unknown: &'static str,
+    unknown_doc: &'static str,
+    /// Whether or not all the payloads are `Eq`
+    eq: bool,
+    variants: Vec<Variant>,
+}
+
+impl IdEnum {
+    fn emit(&self) -> String {
+        let name = self.name;
+        let unknown = self.unknown;
+        let mut out = String::new();
+
+        writeln!(out, "#[derive({})]", derives(self.eq)).unwrap();
+        writeln!(out, "/// {}", self.id_type).unwrap();
+        writeln!(out, "pub enum {} {{", name).unwrap();
+        for variant in &self.variants {
+            writeln!(out, "    /// {}", variant.doc).unwrap();
+            match &variant.payload {
+                Some(payload) => writeln!(out, "    {}({}),", variant.name, payload).unwrap(),
+                None => writeln!(out, "    {},", variant.name).unwrap(),
+            }
+        }
+        writeln!(out, "    /// {}", self.unknown_doc).unwrap();
+        writeln!(
+            out,
+            "    Unknown {{ {}: {}, data: Vec<u8> }},",
+            unknown, self.id_type
+        )
+        .unwrap();
+        writeln!(out, "}}").unwrap();
+        writeln!(out).unwrap();
+
+        let variable = name.to_lowercase();
+        let mut id_arms = String::new();
+        let mut read_arms = String::new();
+        let mut write_arms = String::new();
+        for variant in &self.variants {
+            match &variant.payload {
+                Some(_) => {
+                    writeln!(
+                        id_arms,
+                        "            {}::{}(_) => {},",
+                        name, variant.name, variant.id
+                    )
+                    .unwrap();
+                    write!(
+                        read_arms,
+                        r#"            {id} => {{
+                let {payload} = src.gread_with(&mut offset, ctx)?;
+
+                Self::{variant}({payload})
+            }}
+"#,
+                        id = variant.id,
+                        payload = "args",
+                        variant = variant.name,
+                    )
+                    .unwrap();
+                    write!(
+                        write_arms,
+                        r#"            Self::{variant}({payload}) => {{
+                this.gwrite_with({payload}, &mut offset, ctx)?;
+            }}
+"#,
+                        payload = "args",
+                        variant = variant.name,
+                    )
+                    .unwrap();
+                }
+                None => {
+                    writeln!(
+                        id_arms,
+                        "            {}::{} => {},",
+                        name, variant.name, variant.id
+                    )
+                    .unwrap();
+                    writeln!(
+                        read_arms,
+                        "            {} => Self::{},",
+                        variant.id, variant.name
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        let has_unit_variants = self
+            .variants
+            .iter()
+            .any(|variant| variant.payload.is_none());
+        let write_payload = if write_arms.is_empty() {
+            // only the `Unknown` variant has a payload
+            r#"        if let Self::Unknown { data, .. } = self {
+            write_bytes(this, &data, &mut offset)?;
+        }
+"#
+            .to_string()
+        } else {
+            format!(
+                r#"        match self {{
+{write_arms}            Self::Unknown {{ data, .. }} => {{
+                write_bytes(this, &data, &mut offset)?;
+            }}
+{rest}        }}
+"#,
+                write_arms = write_arms,
+                rest = if has_unit_variants {
+                    "            // no arguments\n            _ => {}\n"
+                } else {
+                    ""
+                },
+            )
+        };
+
+        write!(
+            out,
+            r#"impl From<&{name}> for {id_type} {{
+    fn from({variable}: &{name}) -> Self {{
+        match {variable} {{
+{id_arms}            {name}::Unknown {{ {unknown}, .. }} => *{unknown},
+        }}
+    }}
+}}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for {name} {{
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {{
+        let mut offset = 0;
+
+        let {variable} = match src.gread_with::<{id_type}>(&mut offset, ctx)? {{
+{read_arms}            unknown => Self::Unknown {{
+                {unknown}: unknown,
+                data: read_unknown(src, &mut offset)?,
+            }},
+        }};
+
+        Ok(({variable}, offset))
+    }}
+}}
+
+impl ctx::TryIntoCtx<Endian> for {name} {{
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {{
+        let mut offset = 0;
+
+        this.gwrite_with::<{id_type}>((&self).into(), &mut offset, ctx)?;
+
+{write_payload}
+        Ok(offset)
+    }}
+}}
+"#,
+            name = name,
+            id_type = self.id_type,
+            variable = variable,
+            unknown = unknown,
+            id_arms = id_arms,
+            read_arms = read_arms,
+            write_payload = write_payload,
+        )
+        .unwrap();
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod codegen_tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        let pascal = [
+            "motor_ramping",
+            "moveBy",
+            "RS_TRAVIS",
+            "PCMD",
+            "GPSState",
+            "5GHz",
+        ]
+        .iter()
+        .map(|name| pascal_case(name))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "MotorRamping",
+                "MoveBy",
+                "RsTravis",
+                "Pcmd",
+                "GPSState",
+                "N5GHz"
+            ],
+            pascal
+        );
+
+        let snake = [
+            "timestampAndSeqNum",
+            "GPSState",
+            "mass_storage_id",
+            "dX",
+            "ardrone3",
+            "PCMD",
+        ]
+        .iter()
+        .map(|name| snake_case(name))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "timestamp_and_seq_num",
+                "gps_state",
+                "mass_storage_id",
+                "d_x",
+                "ardrone3",
+                "pcmd"
+            ],
+            snake
+        );
+
+        assert_eq!("r#type", identifier("type"));
+        assert_eq!("state", identifier("state"));
+    }
+}
@@ -0,0 +1,330 @@
+//! Generates Classes of `arsdk-rs` from Parrot's
+//! [arsdk-xml](https://github.com/Parrot-Developers/arsdk-xml) definitions.
+//!
+//! The definitions are vendored in `arsdk-gen/xml`. They are a subset of the upstream files
+//! (the Classes the crates use so far), upstream files in the `<project>` format can be dropped in as they are.
+//! All the definitions are validated and every Class of the [`TARGETS`] is generated: each of them is a module
+//! of the hand-written Feature it belongs to, e.g. `common::Class::Settings(settings::Command)`.
+//!
+//! Regenerate the code after changing the definitions or the targets with:
+//!
+//! ```bash
+//! cargo run -p arsdk-gen
+//! ```
+use std::{
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+pub mod codegen;
+pub mod model;
+pub mod xml;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("{path}: {error}")]
+    Xml { path: PathBuf, error: xml::Error },
+    #[error("Invalid definition: {0}")]
+    Definition(String),
+    #[error("IO error")]
+    Io(#[from] std::io::Error),
+}
+
+/// A Feature generated into `arsdk-rs`, all of its Classes are generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target {
+    /// The `<project>` name, e.g. `jpsumo`
+    pub feature: &'static str,
+    /// The module of the Feature, relative to `arsdk-rs/src`, e.g. `jumping_sumo`
+    ///
+    /// Every Class is generated in its own file of the module, e.g. `jumping_sumo/piloting_state.rs`.
+    pub module: &'static str,
+}
+
+/// The generated Features, the hand-written Feature modules declare their Classes
+pub const TARGETS: &[Target] = &[
+    Target {
+        feature: "common",
+        module: "common",
+    },
+    Target {
+        feature: "ardrone3",
+        module: "ardrone3",
+    },
+    Target {
+        feature: "jpsumo",
+        module: "jumping_sumo",
+    },
+];
+
+/// A generated file, relative to the output directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    pub name: String,
+    pub contents: String,
+}
+
+/// Generates the `targets` from the `*.xml` definitions in `xml_dir`
+pub fn generate(xml_dir: &Path, targets: &[Target]) -> Result<Vec<File>, Error> {
+    let mut paths = fs::read_dir(xml_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.extension() == Some(OsStr::new("xml")));
+    paths.sort();
+
+    // the Features and the definition they come from
+    let mut features = Vec::with_capacity(paths.len());
+
+    for path in paths {
+        let xml = fs::read_to_string(&path)?;
+        let project = xml::parse(&xml).map_err(|error| Error::Xml {
+            path: path.clone(),
+            error,
+        })?;
+        let feature = model::Feature::from_xml(&project).map_err(|error| match error {
+            Error::Definition(message) => {
+                Error::Definition(format!("{}: {}", path.display(), message))
+            }
+            error => error,
+        })?;
+
+        let source = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        features.push((feature, source));
+    }
+
+    let mut files = Vec::new();
+
+    for target in targets {
+        let (feature, source) = features
+            .iter()
+            .find(|(feature, _)| feature.name == target.feature)
+            .ok_or_else(|| Error::Definition(format!("no `{}` Feature", target.feature)))?;
+
+        files.extend(feature.classes.iter().map(|class| File {
+            name: format!("{}/{}.rs", target.module, codegen::snake_case(&class.name)),
+            contents: codegen::class_module(feature, class, source),
+        }));
+    }
+
+    Ok(files)
+}
+
+/// Writes the `files` to `out_dir`, creating the directories if needed
+pub fn write(out_dir: &Path, files: &[File]) -> Result<(), Error> {
+    for file in files {
+        let path = out_dir.join(&file.name);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, &file.contents)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod generated_tests {
+    use super::*;
+    use arsdk_rs::{
+        ardrone3::{gps_state, media_streaming, piloting_state, ArDrone3},
+        command::Feature,
+        common::{self, network_event, settings, settings_state},
+        frame::Error as FrameError,
+        jumping_sumo::{self, animations, piloting},
+    };
+    use scroll::{Pread, Pwrite, LE};
+
+    fn manifest_dir() -> &'static Path {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn test_generated_code_is_up_to_date() {
+        let files = generate(&manifest_dir().join("xml"), TARGETS).expect("Should generate");
+        let out_dir = manifest_dir().join("../arsdk-rs/src");
+
+        for file in files {
+            let path = out_dir.join(&file.name);
+            let committed = fs::read_to_string(&path).unwrap_or_default();
+
+            assert!(
+                committed == file.contents,
+                "`{}` is out of date, regenerate it with `cargo run -p arsdk-gen`",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_class_is_generated() {
+        let files = generate(&manifest_dir().join("xml"), TARGETS).expect("Should generate");
+        let names = files
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                "common/network.rs",
+                "common/network_event.rs",
+                "common/settings.rs",
+                "common/settings_state.rs",
+                "common/common.rs",
+                "common/common_state.rs",
+                "ardrone3/piloting.rs",
+                "ardrone3/piloting_state.rs",
+                "ardrone3/media_streaming.rs",
+                "ardrone3/gps_state.rs",
+                "jumping_sumo/piloting.rs",
+                "jumping_sumo/piloting_state.rs",
+                "jumping_sumo/animations.rs",
+                "jumping_sumo/media_streaming.rs",
+            ],
+            names
+        );
+    }
+
+    #[test]
+    fn test_unknown_targets() {
+        let xml_dir = manifest_dir().join("xml");
+        let unknown_feature = Target {
+            feature: "nope",
+            module: "nope",
+        };
+
+        assert!(matches!(
+            generate(&xml_dir, &[unknown_feature]),
+            Err(Error::Definition(ref message)) if message == "no `nope` Feature"
+        ));
+    }
+
+    fn assert_round_trip(feature: Feature, bytes: &[u8]) {
+        let decoded = bytes.pread_with::<Feature>(0, LE).expect("Should decode");
+        assert_eq!(feature, decoded);
+
+        let mut buf = [0_u8; 64];
+        let written = buf.pwrite_with(feature, 0, LE).expect("Should encode");
+        assert_eq!(bytes, &buf[..written]);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        assert_round_trip(
+            Feature::Common(Some(common::Class::Settings(
+                settings::Command::AllSettings,
+            ))),
+            &[0, 2, 0, 0],
+        );
+
+        assert_round_trip(
+            Feature::Common(Some(common::Class::SettingsState(
+                settings_state::Command::ProductVersionChanged(
+                    settings_state::ProductVersionChanged {
+                        software: "4.7.1".into(),
+                        hardware: String::new(),
+                    },
+                ),
+            ))),
+            &[0, 3, 3, 0, b'4', b'.', b'7', b'.', b'1', 0, 0],
+        );
+
+        assert_round_trip(
+            Feature::Common(Some(common::Class::NetworkEvent(
+                network_event::Command::Disconnection(network_event::Disconnection {
+                    cause: network_event::DisconnectionCause::OffButton,
+                }),
+            ))),
+            &[0, 1, 0, 0, 0, 0, 0, 0],
+        );
+
+        assert_round_trip(
+            Feature::Common(Some(common::Class::Common(
+                common::common::Command::CurrentDate(common::common::CurrentDate {
+                    date: "2020-07-04".into(),
+                }),
+            ))),
+            &[
+                0, 4, 1, 0, b'2', b'0', b'2', b'0', b'-', b'0', b'7', b'-', b'0', b'4', 0,
+            ],
+        );
+
+        assert_round_trip(
+            Feature::ArDrone3(Some(ArDrone3::PilotingState(
+                piloting_state::Command::FlyingStateChanged(piloting_state::FlyingStateChanged {
+                    state: piloting_state::FlyingStateChangedState::Hovering,
+                }),
+            ))),
+            &[1, 4, 1, 0, 2, 0, 0, 0],
+        );
+
+        assert_round_trip(
+            Feature::ArDrone3(Some(ArDrone3::MediaStreaming(
+                media_streaming::Command::VideoEnable(media_streaming::VideoEnable { enable: 1 }),
+            ))),
+            &[1, 21, 0, 0, 1],
+        );
+
+        assert_round_trip(
+            Feature::ArDrone3(Some(ArDrone3::GPSState(
+                gps_state::Command::NumberOfSatelliteChanged(gps_state::NumberOfSatelliteChanged {
+                    number_of_satellite: 7,
+                }),
+            ))),
+            &[1, 31, 0, 0, 7],
+        );
+
+        assert_round_trip(
+            Feature::JumpingSumo(jumping_sumo::Class::Piloting(piloting::Command::Pcmd(
+                piloting::Pcmd {
+                    flag: 1,
+                    speed: -100,
+                    turn: 30,
+                },
+            ))),
+            &[3, 0, 0, 0, 1, 156, 30],
+        );
+
+        assert_round_trip(
+            Feature::JumpingSumo(jumping_sumo::Class::Animations(animations::Command::Jump(
+                animations::Jump {
+                    r#type: animations::JumpType::High,
+                },
+            ))),
+            &[3, 2, 3, 0, 1, 0, 0, 0],
+        );
+
+        // Unknown Commands are passed through
+        assert_round_trip(
+            Feature::Common(Some(common::Class::SettingsState(
+                settings_state::Command::Unknown {
+                    command: 99,
+                    data: vec![1, 2],
+                },
+            ))),
+            &[0, 3, 99, 0, 1, 2],
+        );
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        // ProductNameChanged without the NUL terminator
+        let unterminated = [0_u8, 3, 2, 0, b'a', b'b']
+            .pread_with::<Feature>(0, LE)
+            .expect_err("Should fail");
+        assert!(matches!(unterminated, FrameError::Scroll(_)));
+
+        let nul = Feature::Common(Some(common::Class::Settings(
+            settings::Command::ProductName(settings::ProductName {
+                name: "a\0b".into(),
+            }),
+        )));
+        let mut buf = [0_u8; 16];
+        assert!(buf.pwrite_with(nul, 0, LE).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use std::{
+    env,
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+/// `arsdk-gen [XML_DIR] [OUT_DIR]`
+///
+/// Defaults to the vendored definitions and `arsdk-rs/src`.
+fn main() -> Result<(), Box<dyn Error>> {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut args = env::args_os().skip(1);
+
+    let xml_dir = args
+        .next()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| manifest_dir.join("xml"));
+    let out_dir = args
+        .next()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| manifest_dir.join("../arsdk-rs/src"));
+
+    let files = arsdk_gen::generate(&xml_dir, arsdk_gen::TARGETS)?;
+    arsdk_gen::write(&out_dir, &files)?;
+
+    for file in &files {
+        println!("Generated {}", out_dir.join(&file.name).display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,450 @@
+//! The Features, Classes, Commands and arguments of an arsdk-xml definition.
+//!
+//! Only the `<project>` format (used by `common.xml`, `ardrone3.xml`, `jpsumo.xml`, ...) is supported:
+//!
+//! ```xml
+//! <project name="common" id="0">
+//!     All common commands shared between all projects
+//!     <class name="CommonState" id="5">
+//!         Common state from product
+//!         <cmd name="BatteryStateChanged" id="1">
+//!             <comment title="Battery state" desc="Battery state."/>
+//!             <arg name="percent" type="u8">
+//!                 Battery percentage
+//!             </arg>
+//!         </cmd>
+//!     </class>
+//! </project>
+//! ```
+use crate::{xml::Element, Error};
+use std::{convert::TryFrom, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feature {
+    pub name: String,
+    pub id: u8,
+    pub doc: String,
+    pub classes: Vec<Class>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Class {
+    pub name: String,
+    pub id: u8,
+    pub doc: String,
+    pub commands: Vec<Command>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    pub name: String,
+    pub id: u16,
+    pub doc: String,
+    pub args: Vec<Arg>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arg {
+    pub name: String,
+    pub doc: String,
+    pub ty: ArgType,
+}
+
+/// The wire format of an argument, see `libARCommands/ARCOMMANDS_ReadWrite.c`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    /// `f32`
+    Float,
+    /// `f64`
+    Double,
+    /// NUL terminated string
+    String,
+    /// Encoded as a `u32`, the value is the index of the variant
+    Enum(Vec<EnumValue>),
+    /// `bitfield:<integer>:<enum>`, the integer has a bit per value of the `enum`
+    /// (which can be defined in another Feature), so it's kept as the raw integer
+    Bitfield {
+        ty: Box<ArgType>,
+        of: String,
+    },
+    /// `multisetting:<name>`, several settings in one argument, kept as the raw bytes.
+    /// It has no length, so it has to be the last argument
+    Multisetting,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumValue {
+    pub name: String,
+    pub doc: String,
+}
+
+impl Feature {
+    pub fn from_xml(project: &Element) -> Result<Self, Error> {
+        if project.name != "project" {
+            return Err(definition(format!(
+                "expected a `<project>`, found `<{}>`",
+                project.name
+            )));
+        }
+        let name = required(project, "name")?;
+
+        let classes = project
+            .elements()
+            .filter(|element| element.name == "class")
+            .map(Class::from_xml)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| in_context(error, &name))?;
+
+        unique(classes.iter().map(|class| class.id), "class", &name)?;
+
+        Ok(Self {
+            id: parse_id(project)?,
+            doc: doc(&project.text()),
+            name,
+            classes,
+        })
+    }
+}
+
+impl Class {
+    fn from_xml(class: &Element) -> Result<Self, Error> {
+        let name = required(class, "name")?;
+
+        let commands = class
+            .elements()
+            .filter(|element| element.name == "cmd")
+            .enumerate()
+            .map(|(index, command)| Command::from_xml(command, index))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| in_context(error, &name))?;
+
+        unique(commands.iter().map(|command| command.id), "cmd", &name)?;
+
+        Ok(Self {
+            id: parse_id(class)?,
+            doc: doc(&class.text()),
+            name,
+            commands,
+        })
+    }
+}
+
+impl Command {
+    /// Commands without an `id` are numbered in the order they are defined, like the C SDK does
+    fn from_xml(command: &Element, index: usize) -> Result<Self, Error> {
+        let name = required(command, "name")?;
+
+        let id = match command.attribute("id") {
+            Some(_) => parse_id(command)?,
+            None => u16::try_from(index).map_err(|_| definition("too many commands".into()))?,
+        };
+
+        // the title of the `<comment>`, or the text of the `<cmd>` in older definitions
+        let comment = command
+            .elements()
+            .find(|element| element.name == "comment")
+            .and_then(|comment| comment.attribute("title"))
+            .map(doc);
+
+        let args = command
+            .elements()
+            .filter(|element| element.name == "arg")
+            .map(Arg::from_xml)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| in_context(error, &name))?;
+
+        let last = args.len().saturating_sub(1);
+        if let Some(arg) = args[..last]
+            .iter()
+            .find(|arg| arg.ty == ArgType::Multisetting)
+        {
+            return Err(definition(format!(
+                "{}: the `multisetting` `{}` has to be the last argument",
+                name, arg.name
+            )));
+        }
+
+        Ok(Self {
+            id,
+            doc: comment.unwrap_or_else(|| doc(&command.text())),
+            name,
+            args,
+        })
+    }
+}
+
+impl Arg {
+    fn from_xml(arg: &Element) -> Result<Self, Error> {
+        let name = required(arg, "name")?;
+
+        let ty = match required(arg, "type")?.as_str() {
+            "float" => ArgType::Float,
+            "double" => ArgType::Double,
+            "string" => ArgType::String,
+            "enum" => {
+                let values = arg
+                    .elements()
+                    .filter(|element| element.name == "enum")
+                    .map(|value| {
+                        Ok(EnumValue {
+                            name: required(value, "name")?,
+                            doc: doc(&value.text()),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                if values.is_empty() {
+                    return Err(definition(format!("enum `{}` has no values", name)));
+                }
+
+                ArgType::Enum(values)
+            }
+            "multisetting" => ArgType::Multisetting,
+            ty if ty.starts_with("multisetting:") => ArgType::Multisetting,
+            ty => match (integer(ty), ty.strip_prefix("bitfield:")) {
+                (Some(integer), _) => integer,
+                // e.g. `bitfield:u8:list_flags`
+                (None, Some(bitfield)) => {
+                    let mut parts = bitfield.splitn(2, ':');
+
+                    match (parts.next().and_then(integer), parts.next()) {
+                        (Some(integer), Some(of)) if !of.is_empty() => ArgType::Bitfield {
+                            ty: Box::new(integer),
+                            of: of.to_string(),
+                        },
+                        _ => {
+                            return Err(definition(format!(
+                                "invalid bitfield `{}` of `{}`, {}",
+                                ty, name, "expected `bitfield:<integer>:<enum>`"
+                            )))
+                        }
+                    }
+                }
+                (None, None) => {
+                    return Err(definition(format!(
+                        "unsupported type `{}` of `{}`",
+                        ty, name
+                    )))
+                }
+            },
+        };
+
+        Ok(Self {
+            doc: doc(&arg.text()),
+            name,
+            ty,
+        })
+    }
+}
+
+/// The integer `ArgType`s
+fn integer(ty: &str) -> Option<ArgType> {
+    let integer = match ty {
+        "u8" => ArgType::U8,
+        "i8" => ArgType::I8,
+        "u16" => ArgType::U16,
+        "i16" => ArgType::I16,
+        "u32" => ArgType::U32,
+        "i32" => ArgType::I32,
+        "u64" => ArgType::U64,
+        "i64" => ArgType::I64,
+        _ => return None,
+    };
+
+    Some(integer)
+}
+
+fn definition(message: String) -> Error {
+    Error::Definition(message)
+}
+
+/// Prefixes the error with the name of the Feature / Class / Command it comes from
+fn in_context(error: Error, name: &str) -> Error {
+    match error {
+        Error::Definition(message) => definition(format!("{}: {}", name, message)),
+        error => error,
+    }
+}
+
+fn required(element: &Element, attribute: &str) -> Result<String, Error> {
+    element
+        .attribute(attribute)
+        .map(String::from)
+        .ok_or_else(|| {
+            definition(format!(
+                "`<{}>` is missing the `{}` attribute",
+                element.name, attribute
+            ))
+        })
+}
+
+fn parse_id<T: FromStr>(element: &Element) -> Result<T, Error> {
+    let id = required(element, "id")?;
+
+    id.parse()
+        .map_err(|_| definition(format!("invalid id `{}` of `<{}>`", id, element.name)))
+}
+
+fn unique(
+    ids: impl Iterator<Item = impl Into<u16>>,
+    kind: &str,
+    parent: &str,
+) -> Result<(), Error> {
+    let mut ids = ids.map(Into::into).collect::<Vec<u16>>();
+    let count = ids.len();
+    ids.sort_unstable();
+    ids.dedup();
+
+    if ids.len() != count {
+        return Err(definition(format!("{}: duplicate `<{}>` id", parent, kind)));
+    }
+
+    Ok(())
+}
+
+/// Collapses the whitespace of the descriptions into a single line
+fn doc(text: &str) -> String {
+    text.replace("\\n", " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod model_tests {
+    use super::*;
+    use crate::xml::parse;
+
+    #[test]
+    fn test_feature_from_xml() {
+        let project = parse(
+            r#"<project name="jpsumo" id="3">
+                All commands specific to the Jumping Sumo.
+                <class name="Piloting" id="0">
+                    <cmd name="PCMD" buffer="NON_ACK">
+                        <comment title="Move the robot" desc="Move the robot.\n
+                            The libARController is sending the command each 50ms."/>
+                        <arg name="flag" type="u8">Boolean for "touch screen".</arg>
+                    </cmd>
+                    <cmd name="Posture">
+                        Set posture
+                        <arg name="type" type="enum">
+                            Type of Posture
+                            <enum name="standing">Standing type</enum>
+                            <enum name="jumper">Jumper type</enum>
+                        </arg>
+                    </cmd>
+                </class>
+            </project>"#,
+        )
+        .expect("Should parse");
+
+        let feature = Feature::from_xml(&project).expect("Should be valid");
+        assert_eq!("jpsumo", feature.name);
+        assert_eq!(3, feature.id);
+        assert_eq!("All commands specific to the Jumping Sumo.", feature.doc);
+
+        let commands = &feature.classes[0].commands;
+        assert_eq!(
+            Command {
+                name: "PCMD".into(),
+                id: 0,
+                doc: "Move the robot".into(),
+                args: vec![Arg {
+                    name: "flag".into(),
+                    doc: "Boolean for \"touch screen\".".into(),
+                    ty: ArgType::U8,
+                }],
+            },
+            commands[0]
+        );
+        assert_eq!(1, commands[1].id);
+        assert_eq!("Set posture", commands[1].doc);
+        assert_eq!(
+            ArgType::Enum(vec![
+                EnumValue {
+                    name: "standing".into(),
+                    doc: "Standing type".into(),
+                },
+                EnumValue {
+                    name: "jumper".into(),
+                    doc: "Jumper type".into(),
+                },
+            ]),
+            commands[1].args[0].ty
+        );
+    }
+
+    #[test]
+    fn test_bitfield_and_multisetting() {
+        let project = parse(
+            r#"<project name="x" id="1"><class name="A" id="0">
+                <cmd name="B">
+                    <arg name="listFlags" type="bitfield:u8:list_flags"/>
+                    <arg name="settings" type="multisetting:MaxAltitude"/>
+                </cmd>
+            </class></project>"#,
+        )
+        .expect("Should parse");
+
+        let feature = Feature::from_xml(&project).expect("Should be valid");
+        let args = &feature.classes[0].commands[0].args;
+        assert_eq!(
+            ArgType::Bitfield {
+                ty: Box::new(ArgType::U8),
+                of: "list_flags".into(),
+            },
+            args[0].ty
+        );
+        assert_eq!(ArgType::Multisetting, args[1].ty);
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        let unsupported = r#"<project name="x" id="1"><class name="A" id="0">
+            <cmd name="B"><arg name="c" type="list"/></cmd>
+        </class></project>"#;
+        let bitfield = r#"<project name="x" id="1"><class name="A" id="0">
+            <cmd name="B"><arg name="c" type="bitfield:float:d"/></cmd>
+        </class></project>"#;
+        let multisetting = r#"<project name="x" id="1"><class name="A" id="0">
+            <cmd name="B">
+                <arg name="c" type="multisetting:D"/><arg name="e" type="u8"/>
+            </cmd>
+        </class></project>"#;
+        let duplicate = r#"<project name="x" id="1">
+            <class name="A" id="0"/><class name="B" id="0"/>
+        </project>"#;
+        let out_of_range = r#"<project name="x" id="256"/>"#;
+
+        let errors = [unsupported, bitfield, multisetting, duplicate, out_of_range]
+            .iter()
+            .map(|xml| {
+                let project = parse(xml).expect("Should parse");
+
+                Feature::from_xml(&project)
+                    .expect_err("Should be invalid")
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                "Invalid definition: x: A: B: unsupported type `list` of `c`",
+                "Invalid definition: x: A: B: invalid bitfield `bitfield:float:d` of `c`, expected `bitfield:<integer>:<enum>`",
+                "Invalid definition: x: A: B: the `multisetting` `c` has to be the last argument",
+                "Invalid definition: x: duplicate `<class>` id",
+                "Invalid definition: invalid id `256` of `<project>`",
+            ],
+            errors
+        );
+    }
+}
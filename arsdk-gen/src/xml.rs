@@ -0,0 +1,310 @@
+//! A minimal XML reader, enough for the arsdk-xml definitions.
+//!
+//! It supports elements, attributes, text, comments, the XML declaration and the predefined / numeric entities.
+//! DTDs and `CDATA` sections are not used by the definitions and are rejected.
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("XML error at byte {position}: {message}")]
+pub struct Error {
+    pub position: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The child elements, in document order
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// The direct text children joined together (the text of the child elements is not included)
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Text(text) => Some(text.as_str()),
+                Node::Element(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Parses a document and returns its root element
+pub fn parse(input: &str) -> Result<Element, Error> {
+    let mut reader = Reader { input, position: 0 };
+
+    reader.skip_misc()?;
+    let root = reader.element()?;
+    reader.skip_misc()?;
+
+    if reader.position < input.len() {
+        return Err(reader.error("content after the root element"));
+    }
+
+    Ok(root)
+}
+
+struct Reader<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn error(&self, message: &str) -> Error {
+        Error {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), Error> {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", token)))
+        }
+    }
+
+    /// Skips until after `end`
+    fn skip_past(&mut self, end: &str) -> Result<(), Error> {
+        match self.rest().find(end) {
+            Some(index) => {
+                self.position += index + end.len();
+                Ok(())
+            }
+            None => Err(self.error(&format!("missing `{}`", end))),
+        }
+    }
+
+    /// Skips whitespace, comments and processing instructions (incl. the XML declaration)
+    fn skip_misc(&mut self) -> Result<(), Error> {
+        loop {
+            self.skip_whitespace();
+
+            if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!") {
+                return Err(self.error("DTDs and CDATA sections are not supported"));
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, Error> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')))
+            .unwrap_or(rest.len());
+
+        if length == 0 {
+            return Err(self.error("expected a name"));
+        }
+        self.position += length;
+
+        Ok(&rest[..length])
+    }
+
+    fn element(&mut self) -> Result<Element, Error> {
+        self.expect("<")?;
+        let mut element = Element {
+            name: self.name()?.to_string(),
+            ..Element::default()
+        };
+
+        loop {
+            self.skip_whitespace();
+
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+
+                return Ok(element);
+            } else if self.rest().starts_with('>') {
+                self.position += 1;
+                break;
+            }
+
+            let name = self.name()?.to_string();
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let value = self.quoted()?;
+
+            element.attributes.push((name, value));
+        }
+
+        loop {
+            let rest = self.rest();
+
+            if rest.starts_with("</") {
+                self.position += 2;
+                let start = self.position;
+                if self.name()? != element.name {
+                    self.position = start;
+
+                    return Err(self.error(&format!("expected `</{}>`", element.name)));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+
+                return Ok(element);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!") {
+                return Err(self.error("DTDs and CDATA sections are not supported"));
+            } else if rest.starts_with('<') {
+                element.children.push(Node::Element(self.element()?));
+            } else if rest.is_empty() {
+                return Err(self.error(&format!("unclosed `<{}>`", element.name)));
+            } else {
+                let length = rest.find('<').unwrap_or(rest.len());
+                let text = self.unescape(&rest[..length])?;
+                self.position += length;
+
+                element.children.push(Node::Text(text));
+            }
+        }
+    }
+
+    fn quoted(&mut self) -> Result<String, Error> {
+        let quote = match self.rest().chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => quote,
+            _ => return Err(self.error("expected a quoted value")),
+        };
+        self.position += 1;
+
+        let rest = self.rest();
+        let length = rest
+            .find(quote)
+            .ok_or_else(|| self.error("unterminated attribute value"))?;
+        let value = self.unescape(&rest[..length])?;
+        self.position += length + 1;
+
+        Ok(value)
+    }
+
+    fn unescape(&self, raw: &str) -> Result<String, Error> {
+        let mut unescaped = String::with_capacity(raw.len());
+        let mut rest = raw;
+
+        while let Some(start) = rest.find('&') {
+            unescaped.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let end = rest
+                .find(';')
+                .ok_or_else(|| self.error("unterminated entity"))?;
+            let character = match &rest[1..end] {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                entity => {
+                    let code = if let Some(hex) = entity.strip_prefix("#x") {
+                        u32::from_str_radix(hex, 16).ok()
+                    } else if let Some(decimal) = entity.strip_prefix('#') {
+                        decimal.parse().ok()
+                    } else {
+                        None
+                    };
+
+                    code.and_then(std::char::from_u32)
+                        .ok_or_else(|| self.error(&format!("unknown entity `&{};`", entity)))?
+                }
+            };
+
+            unescaped.push(character);
+            rest = &rest[end + 1..];
+        }
+        unescaped.push_str(rest);
+
+        Ok(unescaped)
+    }
+}
+
+#[cfg(test)]
+mod xml_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let root = parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- a comment -->
+            <project name="common" id='0'>
+                All common &quot;commands&quot; &#x26; more
+                <class name="Network" id="0">
+                    <!-- <cmd name="Commented"/> -->
+                    <cmd name="Disconnect"/>
+                </class>
+            </project>
+            "#,
+        )
+        .expect("Should parse");
+
+        assert_eq!("project", root.name);
+        assert_eq!(Some("common"), root.attribute("name"));
+        assert_eq!(Some("0"), root.attribute("id"));
+        assert_eq!(None, root.attribute("missing"));
+        assert_eq!("All common \"commands\" & more", root.text().trim());
+
+        let class = root.elements().next().expect("Should have a class");
+        let commands = class.elements().collect::<Vec<_>>();
+        assert_eq!(1, commands.len());
+        assert_eq!(Some("Disconnect"), commands[0].attribute("name"));
+        assert!(commands[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_documents() {
+        let mismatched = parse("<project><class></project>").expect_err("Should fail");
+        assert_eq!("expected `</class>`", mismatched.message);
+
+        let unclosed = parse("<project>text").expect_err("Should fail");
+        assert_eq!("unclosed `<project>`", unclosed.message);
+
+        let entity = parse("<project>&nbsp;</project>").expect_err("Should fail");
+        assert_eq!("unknown entity `&nbsp;`", entity.message);
+
+        assert!(parse("<project/><project/>").is_err());
+        assert!(parse("<project><![CDATA[x]]></project>").is_err());
+    }
+}
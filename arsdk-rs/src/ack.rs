@@ -0,0 +1,251 @@
+//! Reliable delivery of `Type::DataWithAck` frames
+//!
+//! Mirrors the ARNetwork semantics of the C SDK:
+//! every `Type::DataWithAck` frame we send is kept until the Drone acknowledges
+//! its sequence id, or it's retransmitted after `ack_timeout` up to `ack_retries` times.
+use crate::{
+    frame::{BufferID, Type},
+    Error,
+};
+use dashmap::DashMap;
+use std::{
+    convert::TryFrom,
+    sync::mpsc::SyncSender,
+    time::{Duration, Instant},
+};
+
+/// C SDK: `ackTimeoutMs` of the `BD_NET_CD_ACK_ID` buffer
+pub const DEFAULT_ACK_TIMEOUT: Duration = Duration::from_millis(500);
+/// C SDK: `numberOfRetry` of the `BD_NET_CD_ACK_ID` buffer
+pub const DEFAULT_ACK_RETRIES: usize = 3;
+
+/// How often the pending frames are checked for retransmission
+pub(crate) const RETRANSMIT_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The outcome of sending a `Type::DataWithAck` frame
+pub enum DeliveryStatus {
+    /// The Drone acknowledged the frame
+    Acked,
+    /// No acknowledgement was received after all the retries
+    Timeout,
+}
+
+/// The frames which couldn't be sent at all are notified with `Error::Send`
+pub(crate) type Delivery = Result<DeliveryStatus, Error>;
+
+#[derive(Debug)]
+pub(crate) enum Notifier {
+    Sync(SyncSender<Delivery>),
+    #[cfg(feature = "tokio")]
+    Async(tokio::sync::oneshot::Sender<Delivery>),
+}
+
+impl Notifier {
+    fn notify(self, status: Delivery) {
+        // the receiving side might have been dropped, which is fine
+        match self {
+            Notifier::Sync(sender) => {
+                let _ = sender.try_send(status);
+            }
+            #[cfg(feature = "tokio")]
+            Notifier::Async(sender) => {
+                let _ = sender.send(status);
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Pending {
+    raw_frame: Vec<u8>,
+    /// For the ack latency, retransmissions included
+    first_sent_at: Instant,
+    sent_at: Instant,
+    retries_left: usize,
+    notifier: Option<Notifier>,
+}
+
+#[derive(Debug)]
+/// Outstanding sequence ids per `BufferID`
+pub(crate) struct AckTracker {
+    ack_timeout: Duration,
+    ack_retries: usize,
+    pending: DashMap<(BufferID, u8), Pending>,
+}
+
+impl AckTracker {
+    pub(crate) fn new(ack_timeout: Duration, ack_retries: usize) -> Self {
+        Self {
+            ack_timeout,
+            ack_retries,
+            pending: DashMap::new(),
+        }
+    }
+
+    /// Starts waiting for the acknowledgement of the frame.
+    ///
+    /// If a frame with the same `BufferID` and `sequence_id` is still pending,
+    /// it gets replaced and its notifier is dropped.
+    pub(crate) fn track(
+        &self,
+        buffer_id: BufferID,
+        sequence_id: u8,
+        raw_frame: Vec<u8>,
+        notifier: Option<Notifier>,
+    ) {
+        let now = Instant::now();
+        let pending = Pending {
+            raw_frame,
+            first_sent_at: now,
+            sent_at: now,
+            retries_left: self.ack_retries,
+            notifier,
+        };
+
+        self.pending.insert((buffer_id, sequence_id), pending);
+    }
+
+    /// Returns how long it took for the frame to be acknowledged, `None` if it wasn't pending
+    pub(crate) fn acknowledge(&self, buffer_id: BufferID, sequence_id: u8) -> Option<Duration> {
+        let (_, pending) = self.pending.remove(&(buffer_id, sequence_id))?;
+
+        if let Some(notifier) = pending.notifier {
+            notifier.notify(Ok(DeliveryStatus::Acked));
+        }
+
+        Some(pending.first_sent_at.elapsed())
+    }
+
+    /// Stops tracking the frame the Command sender gave up on, its notifier gets the `error`.
+    ///
+    /// Does nothing for the `raw_frame`s which aren't `Type::DataWithAck`.
+    pub(crate) fn fail(&self, raw_frame: &[u8], error: Error) {
+        let (frame_type, buffer_id, sequence_id) = match raw_frame {
+            [frame_type, buffer_id, sequence_id, ..] => (*frame_type, *buffer_id, *sequence_id),
+            _ => return,
+        };
+
+        if !matches!(Type::try_from(frame_type), Ok(Type::DataWithAck)) {
+            return;
+        }
+
+        let buffer_id = match BufferID::try_from(buffer_id) {
+            Ok(buffer_id) => buffer_id,
+            Err(_) => return,
+        };
+
+        if let Some((_, pending)) = self.pending.remove(&(buffer_id, sequence_id)) {
+            if let Some(notifier) = pending.notifier {
+                notifier.notify(Err(error));
+            }
+        }
+    }
+
+    /// Stops tracking all the pending frames.
+    ///
+    /// Their notifiers are dropped, which is reported as `DeliveryStatus::Timeout`.
+    pub(crate) fn clear(&self) {
+        self.pending.clear();
+    }
+
+    /// Returns the raw frames that need to be retransmitted
+    /// and notifies the ones that ran out of retries with `DeliveryStatus::Timeout`.
+    pub(crate) fn due(&self, now: Instant) -> Vec<Vec<u8>> {
+        let mut retransmit = Vec::new();
+        let ack_timeout = self.ack_timeout;
+
+        self.pending.retain(|_, pending| {
+            if now.duration_since(pending.sent_at) < ack_timeout {
+                return true;
+            }
+
+            if pending.retries_left > 0 {
+                pending.retries_left -= 1;
+                pending.sent_at = now;
+                retransmit.push(pending.raw_frame.clone());
+
+                true
+            } else {
+                if let Some(notifier) = pending.notifier.take() {
+                    notifier.notify(Ok(DeliveryStatus::Timeout));
+                }
+
+                false
+            }
+        });
+
+        retransmit
+    }
+}
+
+#[cfg(test)]
+mod ack_tests {
+    use super::*;
+    use std::sync::mpsc::sync_channel;
+
+    #[test]
+    fn test_acked_frame() {
+        let tracker = AckTracker::new(DEFAULT_ACK_TIMEOUT, DEFAULT_ACK_RETRIES);
+        let (tx, rx) = sync_channel(1);
+
+        tracker.track(BufferID::CDAck, 4, vec![4, 11, 4], Some(Notifier::Sync(tx)));
+
+        assert_eq!(None, tracker.acknowledge(BufferID::CDAck, 5));
+        assert!(tracker.acknowledge(BufferID::CDAck, 4).is_some());
+        assert!(matches!(rx.try_recv(), Ok(Ok(DeliveryStatus::Acked))));
+        // already acknowledged
+        assert_eq!(None, tracker.acknowledge(BufferID::CDAck, 4));
+    }
+
+    #[test]
+    fn test_retransmit_and_timeout() {
+        let ack_timeout = Duration::from_millis(100);
+        let tracker = AckTracker::new(ack_timeout, 2);
+        let (tx, rx) = sync_channel(1);
+
+        tracker.track(BufferID::CDAck, 0, vec![4, 11, 0], Some(Notifier::Sync(tx)));
+        let sent_at = Instant::now();
+
+        assert!(tracker.due(sent_at).is_empty());
+
+        let first_retry = sent_at + ack_timeout;
+        assert_eq!(vec![vec![4, 11, 0]], tracker.due(first_retry));
+        // the timeout restarts after each retransmission
+        assert!(tracker.due(first_retry).is_empty());
+
+        let second_retry = first_retry + ack_timeout;
+        assert_eq!(vec![vec![4, 11, 0]], tracker.due(second_retry));
+        assert!(rx.try_recv().is_err());
+
+        assert!(tracker.due(second_retry + ack_timeout).is_empty());
+        assert!(matches!(rx.try_recv(), Ok(Ok(DeliveryStatus::Timeout))));
+        assert_eq!(None, tracker.acknowledge(BufferID::CDAck, 0));
+    }
+
+    #[test]
+    fn test_failed_frame() {
+        let tracker = AckTracker::new(DEFAULT_ACK_TIMEOUT, DEFAULT_ACK_RETRIES);
+        let (tx, rx) = sync_channel(1);
+
+        tracker.track(BufferID::CDAck, 2, vec![4, 11, 2], Some(Notifier::Sync(tx)));
+        // not a `Type::DataWithAck` frame
+        tracker.fail(&[2, 11, 2], Error::Disconnected);
+        assert!(rx.try_recv().is_err());
+
+        let error = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        tracker.fail(
+            &[4, 11, 2],
+            Error::Send {
+                buffer_id: 11,
+                error,
+            },
+        );
+
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(Err(Error::Send { buffer_id: 11, .. }))
+        ));
+        assert!(tracker.due(Instant::now() + DEFAULT_ACK_TIMEOUT).is_empty());
+    }
+}
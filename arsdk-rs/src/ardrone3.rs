@@ -1,52 +1,86 @@
-mod gps_state;
-mod piloting;
-mod piloting_state;
+pub mod gps_state;
+pub mod media_streaming;
+pub mod piloting;
+pub mod piloting_state;
 
-pub use gps_state::GPSState;
-pub use piloting::{pcmd::PCMD, Piloting};
-pub use piloting_state::PilotingState;
+use arsdk_derive::FramePart;
+use chrono::{DateTime, Utc};
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+/// The `timestampAndSeqNum` of the `piloting::Pcmd`:
+/// the timestamp in milliseconds (low 24 bits) and the command sequence number (high 8 bits)
+pub fn timestamp_and_seq_num(timestamp: DateTime<Utc>, sequence_id: u8) -> u32 {
+    let milliseconds = timestamp.timestamp_millis() as u32 & 0x00FF_FFFF;
+
+    milliseconds | u32::from(sequence_id) << 24
+}
+
+#[derive(Debug, Clone, PartialEq, FramePart)]
+#[arsdk(id_type = "u8")]
 /// u8
 pub enum ArDrone3 {
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_PILOTING = 0
-    Piloting(Piloting),
+    ///
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 0)]
+    Piloting(piloting::Command),
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_CAMERA = 1
+    #[arsdk(id = 1, skip_decode)]
     Camera,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_PILOTINGSETTINGS = 2
+    #[arsdk(id = 2, skip_decode)]
     PilotingSettings,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_MEDIARECORDEVENT = 3
+    #[arsdk(id = 3, skip_decode)]
     MediaRecordEvent,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_PILOTINGSTATE = 4
-    PilotingState(PilotingState),
+    ///
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 4)]
+    PilotingState(piloting_state::Command),
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_ANIMATIONS = 5
+    #[arsdk(id = 5, skip_decode)]
     Animations,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_PILOTINGSETTINGSSTATE = 6
+    #[arsdk(id = 6, skip_decode)]
     PilotingSettingsState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_MEDIARECORD = 7
+    #[arsdk(id = 7, skip_decode)]
     MediaRecord,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_MEDIARECORDSTATE = 8
+    #[arsdk(id = 8, skip_decode)]
     MediaRecordState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_NETWORKSETTINGS = 9
+    #[arsdk(id = 9, skip_decode)]
     NetworkSettings,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_NETWORKSETTINGSSTATE = 10
+    #[arsdk(id = 10, skip_decode)]
     NetworkSettingsState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_SPEEDSETTINGS = 11
+    #[arsdk(id = 11, skip_decode)]
     SpeedSettings,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_SPEEDSETTINGSSTATE = 12
+    #[arsdk(id = 12, skip_decode)]
     SpeedSettingsState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_NETWORK = 13
+    #[arsdk(id = 13, skip_decode)]
     Network,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_NETWORKSTATE = 14
+    #[arsdk(id = 14, skip_decode)]
     NetworkState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_SETTINGSSTATE = 16
+    #[arsdk(id = 16, skip_decode)]
     SettingsState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_PICTURESETTINGS = 19
+    #[arsdk(id = 19, skip_decode)]
     PictureSettings,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_PICTURESETTINGSSTATE = 20
+    #[arsdk(id = 20, skip_decode)]
     PictureSettingsState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_MEDIASTREAMING = 21
-    MediaStreaming(MediaStreaming),
+    ///
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 21)]
+    MediaStreaming(media_streaming::Command),
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_MEDIASTREAMINGSTATE = 22
     ///
     /// TODO: More info on this command
@@ -70,10 +104,13 @@ pub enum ArDrone3 {
     ///     ARCOMMANDS_ARDRONE3_MEDIASTREAMINGSTATE_VIDEOENABLECHANGED_ENABLED_MAX
     /// } eARCOMMANDS_ARDRONE3_MEDIASTREAMINGSTATE_VIDEOENABLECHANGED_ENABLED;
     /// ```
+    #[arsdk(id = 22, skip_decode)]
     MediaStreamingState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_GPSSETTINGS = 23
+    #[arsdk(id = 23, skip_decode)]
     GPSSettings,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_GPSSETTINGSSTATE = 24
+    #[arsdk(id = 24, skip_decode)]
     GPSSettingsState,
     /// Frame { frame_type: Data, buffer_id: DCNavdata, sequence_id: 69,
     /// feature: Some(Unknown { feature: 1, data: [25, 0, 0, 243, 0] }) }
@@ -103,171 +140,59 @@ pub enum ArDrone3 {
     ///
     /// 2. _tilt - u8
     /// 3. _pan - u8
+    #[arsdk(id = 25, skip_decode)]
     CameraState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_ANTIFLICKERING = 29
+    #[arsdk(id = 29, skip_decode)]
     AntiFlickering,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_ANTIFLICKERINGSTATE = 30
+    #[arsdk(id = 30, skip_decode)]
     AntiFlickeringState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_GPSSTATE = 31
-    /// TODO: use the GPSState struct
-    GPSState,
+    ///
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 31)]
+    GPSState(gps_state::Command),
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_PROSTATE = 32
+    #[arsdk(id = 32, skip_decode)]
     ProState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_ACCESSORYSTATE = 33
+    #[arsdk(id = 33, skip_decode)]
     AccessoryState,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_PILOTINGEVENT = 34
+    #[arsdk(id = 34, skip_decode)]
     PilotingEvent,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_SOUND = 35
+    #[arsdk(id = 35, skip_decode)]
     Sound,
     /// ARCOMMANDS_ID_ARDRONE3_CLASS_SOUNDSTATE = 36
+    #[arsdk(id = 36, skip_decode)]
     SoundState,
-    Unknown {
-        ardrone3: u8,
-        data: Vec<u8>,
-    },
-}
-
-impl Into<u8> for &ArDrone3 {
-    fn into(self) -> u8 {
-        match self {
-            ArDrone3::Piloting(_) => 0,
-            ArDrone3::Camera => 1,
-            ArDrone3::PilotingSettings => 2,
-            ArDrone3::MediaRecordEvent => 3,
-            ArDrone3::PilotingState { .. } => 4,
-            ArDrone3::Animations => 5,
-            ArDrone3::PilotingSettingsState => 6,
-            ArDrone3::MediaRecord => 7,
-            ArDrone3::MediaRecordState => 8,
-            ArDrone3::NetworkSettings => 9,
-            ArDrone3::NetworkSettingsState => 10,
-            ArDrone3::SpeedSettings => 11,
-            ArDrone3::SpeedSettingsState => 12,
-            ArDrone3::Network => 13,
-            ArDrone3::NetworkState => 14,
-            ArDrone3::SettingsState => 16,
-            ArDrone3::PictureSettings => 19,
-            ArDrone3::PictureSettingsState => 20,
-            ArDrone3::MediaStreaming(_) => 21,
-            ArDrone3::MediaStreamingState => 22,
-            ArDrone3::GPSSettings => 23,
-            ArDrone3::GPSSettingsState => 24,
-            ArDrone3::CameraState => 28,
-            ArDrone3::AntiFlickering => 29,
-            ArDrone3::AntiFlickeringState => 30,
-            ArDrone3::GPSState => 31,
-            ArDrone3::ProState => 32,
-            ArDrone3::AccessoryState => 33,
-            ArDrone3::PilotingEvent => 34,
-            ArDrone3::Sound => 35,
-            ArDrone3::SoundState => 36,
-            ArDrone3::Unknown { ardrone3, .. } => *ardrone3,
-        }
-    }
+    #[arsdk(unknown)]
+    Unknown { ardrone3: u8, data: Vec<u8> },
 }
 
-/// From pyparrot:
-/// For commands reference see (pyparrot/commandsandsensors/ardrone3.xml#L2965-L3008)[https://github.com/amymcgovern/pyparrot/blob/8b7091cdf9a411938566abd7962b05ef7df7adb3/pyparrot/commandsandsensors/ardrone3.xml#L2965-L3008]
-///
-/// For EnableVideo see (pyparrot/Bebop.py#L448-L461)[https://github.com/amymcgovern/pyparrot/blob/bf4775ec1199b282e4edde1e4a8e018dcc8725e0/pyparrot/Bebop.py#L448-L461]
-///
-/// ```python
-/// command_tuple = self.command_parser.get_command_tuple("ardrone3", "MediaStreaming", "VideoEnable")
-/// param_tuple = [1] # Enable
-/// param_type_tuple = ['u8']
-/// self.drone_connection.send_param_command_packet(command_tuple,param_tuple,param_type_tuple)
-/// ```
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum MediaStreaming {
-    /// EnableVideo = 0
-    /// bool: u8
-    EnableVideo(bool),
-    // TODO: VideoStreamMode
-}
+// --------------------- Tests --------------------- //
 
-pub mod scroll_impl {
+#[cfg(test)]
+mod ardrone3_tests {
     use super::*;
-    use crate::{frame::Error, parse::read_unknown};
-    use scroll::{ctx, Endian, Pread, Pwrite};
-
-    impl<'a> ctx::TryFromCtx<'a, Endian> for ArDrone3 {
-        type Error = Error;
-
-        // and the lifetime annotation on `&'a [u8]` here
-        fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
-            let mut offset = 0;
-            let ardrone3 = match src.gread_with::<u8>(&mut offset, ctx)? {
-                0 => Self::Piloting(src.gread_with::<Piloting>(&mut offset, ctx)?),
-                // 1 => Self::Camera,
-                // 2 => Self::PilotingSettings,
-                // 3 => Self::MediaRecordEvent,
-                4 => Self::PilotingState(src.gread_with::<PilotingState>(&mut offset, ctx)?),
-                // 5 => Self::Animations,
-                // 6 => Self::PilotingSettingsState,
-                // 7 => Self::MediaRecord,
-                // 8 => Self::MediaRecordState,
-                // 9 => Self::NetworkSettings,
-                // 10 => Self::NetworkSettingsState,
-                // 11 => Self::SpeedSettings,
-                // 12 => Self::SpeedSettingsState,
-                // 13 => Self::Network,
-                // 14 => Self::NetworkState,
-                // 16 => Self::SettingsState,
-                // 19 => Self::PictureSettings,
-                // 20 => Self::PictureSettingsState,
-                // 21 => Self::MediaStreaming,
-                // 22 => Self::MediaStreamingState,
-                // 23 => Self::GPSSettings,
-                // 24 => Self::GPSSettingsState,
-                // 25 => Self::CameraState,
-                // 29 => Self::AntiFlickering,
-                // 30 => Self::AntiFlickeringState,
-                // 31 => Self::GPSState,
-                // 32 => Self::ProState,
-                // 33 => Self::AccessoryState,
-                // 34 => Self::PilotingEvent,
-                // 35 => Self::Sound,
-                // 36 => Self::SoundState,
-                // value => {
-                //     return Err(MessageError::OutOfBound {
-                //         value: value.into(),
-                //         param: "ArDrone3".to_string(),
-                //     })
-                // }
-                unknown_ardrone3 => Self::Unknown {
-                    ardrone3: unknown_ardrone3,
-                    data: read_unknown(src, &mut offset)?,
-                },
-            };
-
-            Ok((ardrone3, offset))
-        }
-    }
-
-    impl<'a> ctx::TryIntoCtx<Endian> for ArDrone3 {
-        type Error = Error;
-
-        fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
-            let mut offset = 0;
-
-            this.gwrite_with::<u8>((&self).into(), &mut offset, ctx)?;
 
-            match self {
-                Self::Piloting(piloting) => {
-                    this.gwrite_with(piloting, &mut offset, ctx)?;
-                }
-                Self::MediaStreaming(streaming) => match streaming {
-                    MediaStreaming::EnableVideo(enabled) => {
-                        this.gwrite_with::<u8>(enabled.into(), &mut offset, ctx)?;
-                    } // _ => unimplemented!("Not all MediaStreaming options are impled!"),
-                },
-                Self::PilotingState(piloting_state) => {
-                    this.gwrite_with(piloting_state, &mut offset, ctx)?;
-                }
-                _ => unimplemented!("Not all ArDrone3 Classes are impled!"),
-            }
+    #[test]
+    fn test_timestamp_and_seq_num() {
+        // 1_234_567 ms, the higher bits of the milliseconds are dropped
+        let timestamp = "2020-04-26T00:20:34.567Z"
+            .parse::<DateTime<Utc>>()
+            .expect("Should be a valid date time");
+        let lowest_24_bits = timestamp.timestamp_millis() as u32 & 0x00FF_FFFF;
 
-            Ok(offset)
-        }
+        assert_eq!(
+            0x0900_0000 | lowest_24_bits,
+            timestamp_and_seq_num(timestamp, 9)
+        );
+        assert_eq!(
+            0x0912_D687,
+            timestamp_and_seq_num("1970-01-01T00:20:34.567Z".parse().unwrap(), 9)
+        );
     }
 }
@@ -1,37 +1,306 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `31`: GPS state from product
+//!
+//! Generated from the `GPSState` Class of `arsdk-gen/xml/ardrone3.xml` (the `ardrone3` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_unknown, write_bytes},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 /// u16
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub enum GPSState {
-    /// ARCOMMANDS_ID_ARDRONE3_GPSSTATE_CMD_NUMBEROFSATELLITECHANGED = 0
-    ///
-    /// > Frame { frame_type: DataWithAck, buffer_id: DCEvent, sequence_id: 2,
-    /// > feature: Some(Unknown { feature: 1, data: [31, 0, 0, 12] }) }
-    /// u16 => ARCOMMANDS_ID_ARDRONE3_GPSSTATE_CMD_NUMBEROFSATELLITECHANGED = [0, 0]
-    /// u8 => _numberOfSatellite = 12
-    NumberOfSatelliteChanged(u8),
-    /// ARCOMMANDS_ID_ARDRONE3_GPSSTATE_CMD_HOMETYPEAVAILABILITYCHANGED = 1
-    ///
-    /// 1. Type (u32):
-    /// - ARCOMMANDS_ARDRONE3_GPSSTATE_HOMETYPEAVAILABILITYCHANGED_TYPE_TAKEOFF = 0
-    ///     > The drone has enough information to return to the take off position
-    /// - ARCOMMANDS_ARDRONE3_GPSSTATE_HOMETYPEAVAILABILITYCHANGED_TYPE_PILOT = 1
-    ///     > The drone has enough information to return to the pilot position
-    /// - ARCOMMANDS_ARDRONE3_GPSSTATE_HOMETYPEAVAILABILITYCHANGED_TYPE_FIRST_FIX = 2
-    ///     > The drone has not enough information, it will return to the first GPS fix
-    /// - ARCOMMANDS_ARDRONE3_GPSSTATE_HOMETYPEAVAILABILITYCHANGED_TYPE_FOLLOWEE = 3
-    ///     > The drone has enough information to return to the target of the current (or last) follow me
-    /// - ARCOMMANDS_ARDRONE3_GPSSTATE_HOMETYPEAVAILABILITYCHANGED_TYPE_MAX
-    ///  TODO: Check what the `MAX` does!
-    ///
-    /// Last argument is:
-    /// - uint8_t *_available
-    ///     > 1 if this type is available, 0 otherwise
-    HomeTypeAvailabilityChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_GPSSTATE_CMD_HOMETYPECHOSENCHANGED = 2,
-    HomeTypeChosenChanged,
-}
-
-mod scroll_impl {
-    // TODO: impl TryFromCtx
-
-    // TODO: impl TryIntoCtx
+pub enum Command {
+    /// `0`: Number of GPS satellites
+    NumberOfSatelliteChanged(NumberOfSatelliteChanged),
+    /// `1`: Home type availability
+    HomeTypeAvailabilityChanged(HomeTypeAvailabilityChanged),
+    /// `2`: Home type
+    HomeTypeChosenChanged(HomeTypeChosenChanged),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::NumberOfSatelliteChanged(_) => 0,
+            Command::HomeTypeAvailabilityChanged(_) => 1,
+            Command::HomeTypeChosenChanged(_) => 2,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::NumberOfSatelliteChanged(args)
+            }
+            1 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::HomeTypeAvailabilityChanged(args)
+            }
+            2 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::HomeTypeChosenChanged(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::NumberOfSatelliteChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::HomeTypeAvailabilityChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::HomeTypeChosenChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Number of GPS satellites
+pub struct NumberOfSatelliteChanged {
+    /// The number of satellite
+    pub number_of_satellite: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for NumberOfSatelliteChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            number_of_satellite: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for NumberOfSatelliteChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.number_of_satellite, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Home type availability
+pub struct HomeTypeAvailabilityChanged {
+    /// The type of the return home
+    pub r#type: HomeTypeAvailabilityChangedType,
+    /// 1 if this type is available, 0 otherwise
+    pub available: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for HomeTypeAvailabilityChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            r#type: src.gread_with(&mut offset, ctx)?,
+            available: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for HomeTypeAvailabilityChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.r#type, &mut offset, ctx)?;
+        this.gwrite_with(self.available, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The type of the return home
+pub enum HomeTypeAvailabilityChangedType {
+    /// `0`: The drone will try to return to the take off position
+    Takeoff,
+    /// `1`: The drone will try to return to the pilot position
+    Pilot,
+    /// `2`: The drone has enough information to return to the first GPS fix
+    FirstFix,
+    /// `3`: The drone will try to return to the target of the current (or last) follow me
+    Followee,
+}
+
+impl From<HomeTypeAvailabilityChangedType> for u32 {
+    fn from(value: HomeTypeAvailabilityChangedType) -> Self {
+        match value {
+            HomeTypeAvailabilityChangedType::Takeoff => 0,
+            HomeTypeAvailabilityChangedType::Pilot => 1,
+            HomeTypeAvailabilityChangedType::FirstFix => 2,
+            HomeTypeAvailabilityChangedType::Followee => 3,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for HomeTypeAvailabilityChangedType {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Takeoff,
+            1 => Self::Pilot,
+            2 => Self::FirstFix,
+            3 => Self::Followee,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "HomeTypeAvailabilityChangedType".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for HomeTypeAvailabilityChangedType {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Home type
+pub struct HomeTypeChosenChanged {
+    /// The type of the return home chosen
+    pub r#type: HomeTypeChosenChangedType,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for HomeTypeChosenChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            r#type: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for HomeTypeChosenChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.r#type, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The type of the return home chosen
+pub enum HomeTypeChosenChangedType {
+    /// `0`: The drone will return to the take off position
+    Takeoff,
+    /// `1`: The drone will return to the pilot position
+    Pilot,
+    /// `2`: The drone has not enough information, it will return to the first GPS fix
+    FirstFix,
+    /// `3`: The drone will return to the target of the current (or last) follow me
+    Followee,
+}
+
+impl From<HomeTypeChosenChangedType> for u32 {
+    fn from(value: HomeTypeChosenChangedType) -> Self {
+        match value {
+            HomeTypeChosenChangedType::Takeoff => 0,
+            HomeTypeChosenChangedType::Pilot => 1,
+            HomeTypeChosenChangedType::FirstFix => 2,
+            HomeTypeChosenChangedType::Followee => 3,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for HomeTypeChosenChangedType {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Takeoff,
+            1 => Self::Pilot,
+            2 => Self::FirstFix,
+            3 => Self::Followee,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "HomeTypeChosenChangedType".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for HomeTypeChosenChangedType {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
 }
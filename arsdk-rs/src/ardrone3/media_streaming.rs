@@ -0,0 +1,199 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `21`: Control media streaming behavior.
+//!
+//! Generated from the `MediaStreaming` Class of `arsdk-gen/xml/ardrone3.xml` (the `ardrone3` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_unknown, write_bytes},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// u16
+pub enum Command {
+    /// `0`: Enable/disable video streaming
+    VideoEnable(VideoEnable),
+    /// `1`: Set the stream mode
+    VideoStreamMode(VideoStreamMode),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::VideoEnable(_) => 0,
+            Command::VideoStreamMode(_) => 1,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::VideoEnable(args)
+            }
+            1 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::VideoStreamMode(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::VideoEnable(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::VideoStreamMode(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Enable/disable video streaming
+pub struct VideoEnable {
+    /// 1 to enable, 0 to disable.
+    pub enable: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for VideoEnable {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            enable: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for VideoEnable {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.enable, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Set the stream mode
+pub struct VideoStreamMode {
+    /// stream mode
+    pub mode: VideoStreamModeMode,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for VideoStreamMode {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            mode: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for VideoStreamMode {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.mode, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// stream mode
+pub enum VideoStreamModeMode {
+    /// `0`: Minimize latency with average reliability (best for piloting).
+    LowLatency,
+    /// `1`: Maximize the reliability with an average latency (best when streaming quality is important but not the latency).
+    HighReliability,
+    /// `2`: Maximize the reliability using a framerate decimation with an average latency (best when streaming quality is important but not the latency).
+    HighReliabilityLowFramerate,
+}
+
+impl From<VideoStreamModeMode> for u32 {
+    fn from(value: VideoStreamModeMode) -> Self {
+        match value {
+            VideoStreamModeMode::LowLatency => 0,
+            VideoStreamModeMode::HighReliability => 1,
+            VideoStreamModeMode::HighReliabilityLowFramerate => 2,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for VideoStreamModeMode {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::LowLatency,
+            1 => Self::HighReliability,
+            2 => Self::HighReliabilityLowFramerate,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "VideoStreamModeMode".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for VideoStreamModeMode {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
@@ -1,140 +1,600 @@
-pub(crate) mod pcmd;
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `0`: All commands related to piloting the drone
+//!
+//! Generated from the `Piloting` Class of `arsdk-gen/xml/ardrone3.xml` (the `ardrone3` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_unknown, write_bytes},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
 
-use pcmd::PCMD;
-
-/// eARCOMMANDS_ID_ARDRONE3_PILOTING_CMD
+#[derive(Debug, Clone, PartialEq)]
 /// u16
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum Piloting {
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_FLATTRIM = 0
+pub enum Command {
+    /// `0`: Do a flat trim
     FlatTrim,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_TAKEOFF = 1
+    /// `1`: Take off
     TakeOff,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_PCMD = 2
-    /// ARCOMMANDS_Decoder_ARDrone3PilotingPCMDCb (_flag, _roll, _pitch, _yaw, _gaz, _timestampAndSeqNum, ARCOMMANDS_Decoder_ARDrone3PilotingPCMDCustom);
-    /// ARCOMMANDS_Decoder_ARDrone3PilotingPCMDDecodeArgs (uint8_t *_flag, int8_t *_roll, int8_t *_pitch, int8_t *_yaw, int8_t *_gaz, uint32_t *_timestampAndSeqNum)
-    /// * @param _timestampAndSeqNum Command timestamp in milliseconds (low 24 bits) + command sequence number (high 8 bits) [0;255].
-    /// 1_588_771_372_921
-    /// @see https://developer.parrot.com/docs/reference/bebop_2/index.html#move-the-drone
-    PCMD(PCMD),
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_LANDING = 3
+    /// `2`: Move the drone
+    Pcmd(Pcmd),
+    /// `3`: Land
     Landing,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_EMERGENCY = 4
-    /// Frame { frame_type: DataWithAck, buffer_id: DCEvent, sequence_id: 0,
-    /// feature: Some(ArDrone3(Some(Unknown { ardrone3: 4, data: [1, 0, 0, 0, 0, 0] }))) }
+    /// `4`: Cut out the motors
     Emergency,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_NAVIGATEHOME = 5
-    /// requires: uint8_t _start
-    /// as u8
-    NavigateHome,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_AUTOTAKEOFFMODE = 6
-    AutoTakeOffMode,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_MOVEBY = 7
-    MoveBy,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_USERTAKEOFF = 8
-    UserTakeOff,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_CIRCLE = 9
-    Circle,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_MOVETO = 10
-    MoveTo,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_CANCELMOVETO = 11
+    /// `5`: Return home
+    NavigateHome(NavigateHome),
+    /// `6`: Auto take off mode
+    AutoTakeOffMode(AutoTakeOffMode),
+    /// `7`: Move the drone to a relative position
+    MoveBy(MoveBy),
+    /// `8`: Prepare the drone to take off
+    UserTakeOff(UserTakeOff),
+    /// `9`: Circle
+    Circle(Circle),
+    /// `10`: Move to a location
+    MoveTo(MoveTo),
+    /// `11`: Cancel the moveTo
     CancelMoveTo,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_STARTPILOTEDPOI = 12
-    StartPilotedPOI,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTING_CMD_STOPPILOTEDPOI = 13
+    /// `12`: Start a piloted POI
+    StartPilotedPOI(StartPilotedPOI),
+    /// `13`: Stop the piloted POI
     StopPilotedPOI,
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::FlatTrim => 0,
+            Command::TakeOff => 1,
+            Command::Pcmd(_) => 2,
+            Command::Landing => 3,
+            Command::Emergency => 4,
+            Command::NavigateHome(_) => 5,
+            Command::AutoTakeOffMode(_) => 6,
+            Command::MoveBy(_) => 7,
+            Command::UserTakeOff(_) => 8,
+            Command::Circle(_) => 9,
+            Command::MoveTo(_) => 10,
+            Command::CancelMoveTo => 11,
+            Command::StartPilotedPOI(_) => 12,
+            Command::StopPilotedPOI => 13,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
 }
 
-impl Into<u16> for &Piloting {
-    fn into(self) -> u16 {
-        use Piloting::*;
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => Self::FlatTrim,
+            1 => Self::TakeOff,
+            2 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::Pcmd(args)
+            }
+            3 => Self::Landing,
+            4 => Self::Emergency,
+            5 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::NavigateHome(args)
+            }
+            6 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AutoTakeOffMode(args)
+            }
+            7 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::MoveBy(args)
+            }
+            8 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::UserTakeOff(args)
+            }
+            9 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::Circle(args)
+            }
+            10 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::MoveTo(args)
+            }
+            11 => Self::CancelMoveTo,
+            12 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::StartPilotedPOI(args)
+            }
+            13 => Self::StopPilotedPOI,
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
 
         match self {
-            FlatTrim => 0,
-            TakeOff => 1,
-            PCMD(_) => 2,
-            Landing => 3,
-            Emergency => 4,
-            NavigateHome => 5,
-            AutoTakeOffMode => 6,
-            MoveBy => 7,
-            UserTakeOff => 8,
-            Circle => 9,
-            MoveTo => 10,
-            CancelMoveTo => 11,
-            StartPilotedPOI => 12,
-            StopPilotedPOI => 13,
+            Self::Pcmd(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::NavigateHome(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AutoTakeOffMode(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::MoveBy(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::UserTakeOff(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Circle(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::MoveTo(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::StartPilotedPOI(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+            // no arguments
+            _ => {}
         }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Move the drone
+pub struct Pcmd {
+    /// Boolean flag: 1 if the roll and pitch values should be taken in consideration. 0 otherwise
+    pub flag: u8,
+    /// Roll angle as signed percentage.
+    pub roll: i8,
+    /// Pitch angle as signed percentage.
+    pub pitch: i8,
+    /// Yaw rotation speed as signed percentage.
+    pub yaw: i8,
+    /// Throttle as signed percentage.
+    pub gaz: i8,
+    /// Command timestamp in milliseconds (low 24 bits) + command sequence number (high 8 bits) [0;255].
+    pub timestamp_and_seq_num: u32,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Pcmd {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            flag: src.gread_with(&mut offset, ctx)?,
+            roll: src.gread_with(&mut offset, ctx)?,
+            pitch: src.gread_with(&mut offset, ctx)?,
+            yaw: src.gread_with(&mut offset, ctx)?,
+            gaz: src.gread_with(&mut offset, ctx)?,
+            timestamp_and_seq_num: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Pcmd {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.flag, &mut offset, ctx)?;
+        this.gwrite_with(self.roll, &mut offset, ctx)?;
+        this.gwrite_with(self.pitch, &mut offset, ctx)?;
+        this.gwrite_with(self.yaw, &mut offset, ctx)?;
+        this.gwrite_with(self.gaz, &mut offset, ctx)?;
+        this.gwrite_with(self.timestamp_and_seq_num, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Return home
+pub struct NavigateHome {
+    /// 1 to start the navigate home, 0 to stop it
+    pub start: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for NavigateHome {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            start: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for NavigateHome {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.start, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Auto take off mode
+pub struct AutoTakeOffMode {
+    /// State of automatic take off mode (1 for enabled)
+    pub state: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AutoTakeOffMode {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            state: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AutoTakeOffMode {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.state, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Move the drone to a relative position
+pub struct MoveBy {
+    /// Wanted displacement along the front axis [m]
+    pub d_x: f32,
+    /// Wanted displacement along the right axis [m]
+    pub d_y: f32,
+    /// Wanted displacement along the down axis [m]
+    pub d_z: f32,
+    /// Wanted rotation of heading [rad]
+    pub d_psi: f32,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for MoveBy {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            d_x: src.gread_with(&mut offset, ctx)?,
+            d_y: src.gread_with(&mut offset, ctx)?,
+            d_z: src.gread_with(&mut offset, ctx)?,
+            d_psi: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
     }
 }
 
-mod scroll_impl {
-    use super::*;
-    use crate::frame::Error;
-    use scroll::{ctx, Endian, Pread, Pwrite};
-
-    impl<'a> ctx::TryFromCtx<'a, Endian> for Piloting {
-        type Error = Error;
-
-        // and the lifetime annotation on `&'a [u8]` here
-        fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
-            let mut offset = 0;
-
-            let piloting = match src.gread_with::<u16>(&mut offset, ctx)? {
-                0 => Piloting::FlatTrim,
-                1 => Piloting::TakeOff,
-                2 => Piloting::PCMD(src.gread_with(&mut offset, ctx)?),
-                3 => Piloting::Landing,
-                4 => Piloting::Emergency,
-                5 => Piloting::NavigateHome,
-                6 => Piloting::AutoTakeOffMode,
-                7 => Piloting::MoveBy,
-                8 => Piloting::UserTakeOff,
-                9 => Piloting::Circle,
-                10 => Piloting::MoveTo,
-                11 => Piloting::CancelMoveTo,
-                12 => Piloting::StartPilotedPOI,
-                13 => Piloting::StopPilotedPOI,
-                value => {
-                    return Err(Error::OutOfBound {
-                        value: value.into(),
-                        param: "Piloting".to_string(),
-                    })
-                }
-            };
-
-            Ok((piloting, offset))
+impl ctx::TryIntoCtx<Endian> for MoveBy {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.d_x, &mut offset, ctx)?;
+        this.gwrite_with(self.d_y, &mut offset, ctx)?;
+        this.gwrite_with(self.d_z, &mut offset, ctx)?;
+        this.gwrite_with(self.d_psi, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Prepare the drone to take off
+pub struct UserTakeOff {
+    /// State of user take off mode - 1 to enter in user take off. - 0 to exit from user take off.
+    pub state: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for UserTakeOff {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            state: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for UserTakeOff {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.state, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Circle
+pub struct Circle {
+    /// The circling direction
+    pub direction: CircleDirection,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Circle {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            direction: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Circle {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.direction, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The circling direction
+pub enum CircleDirection {
+    /// `0`: Circling ClockWise
+    Cw,
+    /// `1`: Circling Counter ClockWise
+    Ccw,
+    /// `2`: Use drone default Circling direction set by CirclingDirection cmd
+    Default,
+}
+
+impl From<CircleDirection> for u32 {
+    fn from(value: CircleDirection) -> Self {
+        match value {
+            CircleDirection::Cw => 0,
+            CircleDirection::Ccw => 1,
+            CircleDirection::Default => 2,
         }
     }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for CircleDirection {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Cw,
+            1 => Self::Ccw,
+            2 => Self::Default,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "CircleDirection".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for CircleDirection {
+    type Error = Error;
 
-    impl<'a> ctx::TryIntoCtx<Endian> for Piloting {
-        type Error = Error;
-
-        fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
-            let mut offset = 0;
-            this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
-
-            match self {
-                // Piloting::FlatTrim => {}
-                Piloting::TakeOff => {}
-                Piloting::PCMD(pcmd) => {
-                    this.gwrite_with(pcmd, &mut offset, ctx)?;
-                }
-                // Piloting::Landing => {}
-                // Piloting::Emergency => {}
-                // Piloting::NavigateHome => {}
-                // Piloting::AutoTakeOffMode => {}
-                // Piloting::MoveBy => {}
-                // Piloting::UserTakeOff => {}
-                // Piloting::Circle => {}
-                // Piloting::MoveTo => {}
-                // Piloting::CancelMoveTo => {}
-                // Piloting::StartPilotedPOI => {}
-                // Piloting::StopPilotedPOI => {}
-                _ => {}
-            }
-
-            Ok(offset)
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Move to a location
+pub struct MoveTo {
+    /// Latitude of the location (in degrees) to reach
+    pub latitude: f64,
+    /// Longitude of the location (in degrees) to reach
+    pub longitude: f64,
+    /// Altitude above take off point (in m) to reach
+    pub altitude: f64,
+    /// Orientation mode of the move to
+    pub orientation_mode: MoveToOrientationMode,
+    /// Heading (relative to the North in degrees).
+    pub heading: f32,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for MoveTo {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            latitude: src.gread_with(&mut offset, ctx)?,
+            longitude: src.gread_with(&mut offset, ctx)?,
+            altitude: src.gread_with(&mut offset, ctx)?,
+            orientation_mode: src.gread_with(&mut offset, ctx)?,
+            heading: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for MoveTo {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.latitude, &mut offset, ctx)?;
+        this.gwrite_with(self.longitude, &mut offset, ctx)?;
+        this.gwrite_with(self.altitude, &mut offset, ctx)?;
+        this.gwrite_with(self.orientation_mode, &mut offset, ctx)?;
+        this.gwrite_with(self.heading, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Orientation mode of the move to
+pub enum MoveToOrientationMode {
+    /// `0`: The drone won't change its orientation
+    None,
+    /// `1`: The drone will make a rotation to look in direction of the given location
+    ToTarget,
+    /// `2`: The drone will orientate itself to the given heading before moving to the location
+    HeadingStart,
+    /// `3`: The drone will orientate itself to the given heading while moving to the location
+    HeadingDuring,
+}
+
+impl From<MoveToOrientationMode> for u32 {
+    fn from(value: MoveToOrientationMode) -> Self {
+        match value {
+            MoveToOrientationMode::None => 0,
+            MoveToOrientationMode::ToTarget => 1,
+            MoveToOrientationMode::HeadingStart => 2,
+            MoveToOrientationMode::HeadingDuring => 3,
         }
     }
 }
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for MoveToOrientationMode {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::None,
+            1 => Self::ToTarget,
+            2 => Self::HeadingStart,
+            3 => Self::HeadingDuring,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "MoveToOrientationMode".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for MoveToOrientationMode {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Start a piloted POI
+pub struct StartPilotedPOI {
+    /// Latitude of the location (in degrees) to look at
+    pub latitude: f64,
+    /// Longitude of the location (in degrees) to look at
+    pub longitude: f64,
+    /// Altitude above take off point (in m) to look at
+    pub altitude: f64,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for StartPilotedPOI {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            latitude: src.gread_with(&mut offset, ctx)?,
+            longitude: src.gread_with(&mut offset, ctx)?,
+            altitude: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for StartPilotedPOI {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.latitude, &mut offset, ctx)?;
+        this.gwrite_with(self.longitude, &mut offset, ctx)?;
+        this.gwrite_with(self.altitude, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
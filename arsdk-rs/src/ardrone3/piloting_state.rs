@@ -1,151 +1,905 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `4`: State from drone
+//!
+//! Generated from the `PilotingState` Class of `arsdk-gen/xml/ardrone3.xml` (the `ardrone3` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_unknown, write_bytes},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq)]
 /// u16
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub enum PilotingState {
-    /// typedef enum {
-    ///     ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_FLATTRIMCHANGED = 0,
-    ///     ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_FLYINGSTATECHANGED = 1,
-    /// }
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_FLATTRIMCHANGED = 0,
+pub enum Command {
+    /// `0`: Drone acknowledges that flat trim was correctly processed
     FlatTrimChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_FLYINGSTATECHANGED = 1,
-    ///
-    /// Example 1:
-    /// ```bash
-    /// [2020-07-26T08:05:46Z DEBUG arsdk_rs::listener] Bytes: 4 126 0 15 0 0 0 1 4 1 0 7 0 0 0
-    /// [2020-07-26T08:05:46Z INFO  arsdk_rs::parse] Frame: Frame { frame_type: DataWithAck, buffer_id: DCEvent, sequence_id: 0, feature: Some(ArDrone3(Some(Unknown { ardrone3: 4, data: [1, 0, 7, 0, 0, 0] }))) }
-    /// ```
-    ///
-    /// Example 2:
-    /// ```bash
-    /// Frame { frame_type: DataWithAck, buffer_id: DCEvent, sequence_id: 0, feature: Some(ArDrone3(Some(Unknown { ardrone3: 4, data: [1, 0, 0, 0, 0, 0] }))) }
-    /// ```
-    /// u16 [1, 0] - FLYINGSTATECHANGED
-    FlyingStateChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_ALERTSTATECHANGED = 2,
-    AlterStateChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_NAVIGATEHOMESTATECHANGED = 3,
-    NavigateHomeStateChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_POSITIONCHANGED = 4,
-    PositionChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_SPEEDCHANGED = 5,
-    ///
-    /// Example:
-    /// Frame { frame_type: Data, buffer_id: DCNavdata, sequence_id: 0, feature: Some(ArDrone3(Some(Unknown { ardrone3: 4, data: [5, 0, 0, 0, 0, 128, 0, 0, 0, 0, 0, 0, 0, 0] }))) }
-    /// u16 [5, 0] - SPEEDCHANGED
-    /// 3 x (4?) per float [0, 0, 0, 128, 0, 0, 0, 0, 0, 0, 0, 0]
-    ///
-    /// ```c
-    /// currIndexInBuffer = ARCOMMANDS_ReadWrite_AddU16ToBuffer (buffer, ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_SPEEDCHANGED, currIndexInBuffer, buffLen);
-    /// currIndexInBuffer = ARCOMMANDS_ReadWrite_AddFloatToBuffer (buffer, _speedX, currIndexInBuffer, buffLen);
-    /// currIndexInBuffer = ARCOMMANDS_ReadWrite_AddFloatToBuffer (buffer, _speedY, currIndexInBuffer, buffLen);
-    /// currIndexInBuffer = ARCOMMANDS_ReadWrite_AddFloatToBuffer (buffer, _speedZ, currIndexInBuffer, buffLen);
-    /// ```
-    SpeedChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_ATTITUDECHANGED = 6,
-    /// Frame { frame_type: Data, buffer_id: DCNavdata, sequence_id: 40, feature: Some(ArDrone3(Some(PilotingState { data: [6, 0, 44, 49, 49, 55, 153, 38, 7, 185, 107, 25, 201, 63] }))) }
-    AttitudeChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_AUTOTAKEOFFMODECHANGED = 7,
-    AutoTakeOffModeChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_ALTITUDECHANGED = 8,
-    AltitudeChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_GPSLOCATIONCHANGED = 9,
-    GpsLocationChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_LANDINGSTATECHANGED = 10,
-    ///
-    /// ```c
-    /// /*
-    ///  * @brief Drone landing state
-    ///  */
-    /// typedef enum
-    /// {
-    ///     ARCOMMANDS_ARDRONE3_PILOTINGSTATE_LANDINGSTATECHANGED_STATE_LINEAR = 0,    ///< Linear landing
-    ///     ARCOMMANDS_ARDRONE3_PILOTINGSTATE_LANDINGSTATECHANGED_STATE_SPIRAL = 1,    ///< Spiral landing
-    ///     ARCOMMANDS_ARDRONE3_PILOTINGSTATE_LANDINGSTATECHANGED_STATE_MAX
-    /// } eARCOMMANDS_ARDRONE3_PILOTINGSTATE_LANDINGSTATECHANGED_STATE;
-    /// ```
-    LandingStateChanged,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_AIRSPEEDCHANGED = 11,
-    AirspeedChanged,
-    ///  ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_MOVETOCHANGED = 12,
-    MoveToChanged,
-    ///  ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_MOTIONSTATE = 13,
-    MotionState,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_PILOTEDPOI = 14,
-    PilotedPOI,
-    /// ARCOMMANDS_ID_ARDRONE3_PILOTINGSTATE_CMD_RETURNHOMEBATTERYCAPACITY = 15,
-    ReturnHomeBatteryCapacity,
-    Unknown {
-        piloting_state: u16,
-        data: Vec<u8>,
-    },
-}
-
-impl Into<u16> for &PilotingState {
-    fn into(self) -> u16 {
-        use PilotingState::*;
+    /// `1`: Flying state
+    FlyingStateChanged(FlyingStateChanged),
+    /// `2`: Alert state
+    AlertStateChanged(AlertStateChanged),
+    /// `3`: Return home state
+    NavigateHomeStateChanged(NavigateHomeStateChanged),
+    /// `4`: Drone's position changed
+    PositionChanged(PositionChanged),
+    /// `5`: Drone's speed changed
+    SpeedChanged(SpeedChanged),
+    /// `6`: Drone's attitude changed
+    AttitudeChanged(AttitudeChanged),
+    /// `7`: Auto takeoff mode
+    AutoTakeOffModeChanged(AutoTakeOffModeChanged),
+    /// `8`: Drone's altitude changed
+    AltitudeChanged(AltitudeChanged),
+    /// `9`: Drone's location changed
+    GpsLocationChanged(GpsLocationChanged),
+    /// `10`: Landing state
+    LandingStateChanged(LandingStateChanged),
+    /// `11`: Drone's air speed changed
+    AirSpeedChanged(AirSpeedChanged),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::FlatTrimChanged => 0,
+            Command::FlyingStateChanged(_) => 1,
+            Command::AlertStateChanged(_) => 2,
+            Command::NavigateHomeStateChanged(_) => 3,
+            Command::PositionChanged(_) => 4,
+            Command::SpeedChanged(_) => 5,
+            Command::AttitudeChanged(_) => 6,
+            Command::AutoTakeOffModeChanged(_) => 7,
+            Command::AltitudeChanged(_) => 8,
+            Command::GpsLocationChanged(_) => 9,
+            Command::LandingStateChanged(_) => 10,
+            Command::AirSpeedChanged(_) => 11,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => Self::FlatTrimChanged,
+            1 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::FlyingStateChanged(args)
+            }
+            2 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AlertStateChanged(args)
+            }
+            3 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::NavigateHomeStateChanged(args)
+            }
+            4 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::PositionChanged(args)
+            }
+            5 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::SpeedChanged(args)
+            }
+            6 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AttitudeChanged(args)
+            }
+            7 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AutoTakeOffModeChanged(args)
+            }
+            8 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AltitudeChanged(args)
+            }
+            9 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::GpsLocationChanged(args)
+            }
+            10 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::LandingStateChanged(args)
+            }
+            11 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AirSpeedChanged(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
 
         match self {
-            FlatTrimChanged => 0,
-            FlyingStateChanged => 1,
-            AlterStateChanged => 2,
-            NavigateHomeStateChanged => 3,
-            PositionChanged => 4,
-            SpeedChanged => 5,
-            AttitudeChanged => 6,
-            AutoTakeOffModeChanged => 7,
-            AltitudeChanged => 8,
-            GpsLocationChanged => 9,
-            LandingStateChanged => 10,
-            AirspeedChanged => 11,
-            MoveToChanged => 12,
-            MotionState => 13,
-            PilotedPOI => 14,
-            ReturnHomeBatteryCapacity => 15,
-            Unknown { piloting_state, .. } => *piloting_state,
+            Self::FlyingStateChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AlertStateChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::NavigateHomeStateChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::PositionChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::SpeedChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AttitudeChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AutoTakeOffModeChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AltitudeChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::GpsLocationChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::LandingStateChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AirSpeedChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+            // no arguments
+            _ => {}
         }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Flying state
+pub struct FlyingStateChanged {
+    /// Drone flying state
+    pub state: FlyingStateChangedState,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for FlyingStateChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            state: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
     }
 }
 
-pub mod scroll_impl {
-    use super::*;
-    use crate::{frame::Error, parse::read_unknown};
-    use scroll::{ctx, Endian, Pread, Pwrite};
+impl ctx::TryIntoCtx<Endian> for FlyingStateChanged {
+    type Error = Error;
 
-    impl<'a> ctx::TryFromCtx<'a, Endian> for PilotingState {
-        type Error = Error;
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
 
-        // and the lifetime annotation on `&'a [u8]` here
-        fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
-            let mut offset = 0;
+        this.gwrite_with(self.state, &mut offset, ctx)?;
 
-            #[allow(clippy::match_single_binding)]
-            let piloting_state = match src.gread_with::<u16>(&mut offset, ctx)? {
-                unknown => Self::Unknown {
-                    piloting_state: unknown,
-                    data: read_unknown(src, &mut offset)?,
-                },
-            };
+        Ok(offset)
+    }
+}
 
-            Ok((piloting_state, offset))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Drone flying state
+pub enum FlyingStateChangedState {
+    /// `0`: Landed state
+    Landed,
+    /// `1`: Taking off state
+    Takingoff,
+    /// `2`: Hovering / Circling (for fixed wings) state
+    Hovering,
+    /// `3`: Flying state
+    Flying,
+    /// `4`: Landing state
+    Landing,
+    /// `5`: Emergency state
+    Emergency,
+    /// `6`: User take off state. Waiting for user action to take off.
+    Usertakeoff,
+    /// `7`: Motor ramping state.
+    MotorRamping,
+    /// `8`: Emergency landing state.
+    EmergencyLanding,
+}
+
+impl From<FlyingStateChangedState> for u32 {
+    fn from(value: FlyingStateChangedState) -> Self {
+        match value {
+            FlyingStateChangedState::Landed => 0,
+            FlyingStateChangedState::Takingoff => 1,
+            FlyingStateChangedState::Hovering => 2,
+            FlyingStateChangedState::Flying => 3,
+            FlyingStateChangedState::Landing => 4,
+            FlyingStateChangedState::Emergency => 5,
+            FlyingStateChangedState::Usertakeoff => 6,
+            FlyingStateChangedState::MotorRamping => 7,
+            FlyingStateChangedState::EmergencyLanding => 8,
         }
     }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for FlyingStateChangedState {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
 
-    impl<'a> ctx::TryIntoCtx<Endian> for PilotingState {
-        type Error = Error;
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Landed,
+            1 => Self::Takingoff,
+            2 => Self::Hovering,
+            3 => Self::Flying,
+            4 => Self::Landing,
+            5 => Self::Emergency,
+            6 => Self::Usertakeoff,
+            7 => Self::MotorRamping,
+            8 => Self::EmergencyLanding,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "FlyingStateChangedState".to_string(),
+                })
+            }
+        };
 
-        fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
-            let mut offset = 0;
+        Ok((value, offset))
+    }
+}
 
-            this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+impl ctx::TryIntoCtx<Endian> for FlyingStateChangedState {
+    type Error = Error;
 
-            match self {
-                PilotingState::Unknown { data, .. } => {
-                    this.gwrite_with(data.as_slice(), &mut offset, ())?;
-                }
-                _ => unimplemented!("Not all PilotingState are impled!"),
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Alert state
+pub struct AlertStateChanged {
+    /// Drone alert state
+    pub state: AlertStateChangedState,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AlertStateChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            state: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AlertStateChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.state, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Drone alert state
+pub enum AlertStateChangedState {
+    /// `0`: No alert
+    None,
+    /// `1`: User emergency alert
+    User,
+    /// `2`: Cut out alert
+    CutOut,
+    /// `3`: Critical battery alert
+    CriticalBattery,
+    /// `4`: Low battery alert
+    LowBattery,
+    /// `5`: The angle of the drone is too high
+    TooMuchAngle,
+}
+
+impl From<AlertStateChangedState> for u32 {
+    fn from(value: AlertStateChangedState) -> Self {
+        match value {
+            AlertStateChangedState::None => 0,
+            AlertStateChangedState::User => 1,
+            AlertStateChangedState::CutOut => 2,
+            AlertStateChangedState::CriticalBattery => 3,
+            AlertStateChangedState::LowBattery => 4,
+            AlertStateChangedState::TooMuchAngle => 5,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AlertStateChangedState {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::None,
+            1 => Self::User,
+            2 => Self::CutOut,
+            3 => Self::CriticalBattery,
+            4 => Self::LowBattery,
+            5 => Self::TooMuchAngle,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "AlertStateChangedState".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AlertStateChangedState {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Return home state
+pub struct NavigateHomeStateChanged {
+    /// State of navigate home
+    pub state: NavigateHomeStateChangedState,
+    /// Reason of the state
+    pub reason: NavigateHomeStateChangedReason,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for NavigateHomeStateChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            state: src.gread_with(&mut offset, ctx)?,
+            reason: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for NavigateHomeStateChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.state, &mut offset, ctx)?;
+        this.gwrite_with(self.reason, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// State of navigate home
+pub enum NavigateHomeStateChangedState {
+    /// `0`: Navigate home is available
+    Available,
+    /// `1`: Navigate home is in progress
+    InProgress,
+    /// `2`: Navigate home is not available
+    Unavailable,
+    /// `3`: Navigate home has been received, but its process is pending
+    Pending,
+}
+
+impl From<NavigateHomeStateChangedState> for u32 {
+    fn from(value: NavigateHomeStateChangedState) -> Self {
+        match value {
+            NavigateHomeStateChangedState::Available => 0,
+            NavigateHomeStateChangedState::InProgress => 1,
+            NavigateHomeStateChangedState::Unavailable => 2,
+            NavigateHomeStateChangedState::Pending => 3,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for NavigateHomeStateChangedState {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Available,
+            1 => Self::InProgress,
+            2 => Self::Unavailable,
+            3 => Self::Pending,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "NavigateHomeStateChangedState".to_string(),
+                })
             }
+        };
 
-            Ok(offset)
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for NavigateHomeStateChangedState {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Reason of the state
+pub enum NavigateHomeStateChangedReason {
+    /// `0`: User requested a navigate home (available->inProgress)
+    UserRequest,
+    /// `1`: Connection between controller and product lost (available->inProgress)
+    ConnectionLost,
+    /// `2`: Low battery occurred (available->inProgress)
+    LowBattery,
+    /// `3`: Navigate home is finished (inProgress->available)
+    Finished,
+    /// `4`: Navigate home has been stopped (inProgress->available)
+    Stopped,
+    /// `5`: Navigate home disabled by product (inProgress->unavailable or available->unavailable)
+    Disabled,
+    /// `6`: Navigate home enabled by product (unavailable->available)
+    Enabled,
+}
+
+impl From<NavigateHomeStateChangedReason> for u32 {
+    fn from(value: NavigateHomeStateChangedReason) -> Self {
+        match value {
+            NavigateHomeStateChangedReason::UserRequest => 0,
+            NavigateHomeStateChangedReason::ConnectionLost => 1,
+            NavigateHomeStateChangedReason::LowBattery => 2,
+            NavigateHomeStateChangedReason::Finished => 3,
+            NavigateHomeStateChangedReason::Stopped => 4,
+            NavigateHomeStateChangedReason::Disabled => 5,
+            NavigateHomeStateChangedReason::Enabled => 6,
         }
     }
 }
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for NavigateHomeStateChangedReason {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::UserRequest,
+            1 => Self::ConnectionLost,
+            2 => Self::LowBattery,
+            3 => Self::Finished,
+            4 => Self::Stopped,
+            5 => Self::Disabled,
+            6 => Self::Enabled,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "NavigateHomeStateChangedReason".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for NavigateHomeStateChangedReason {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Drone's position changed
+pub struct PositionChanged {
+    /// Latitude position in decimal degrees (500.0 if not available)
+    pub latitude: f64,
+    /// Longitude position in decimal degrees (500.0 if not available)
+    pub longitude: f64,
+    /// Altitude in meters (from GPS)
+    pub altitude: f64,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for PositionChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            latitude: src.gread_with(&mut offset, ctx)?,
+            longitude: src.gread_with(&mut offset, ctx)?,
+            altitude: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for PositionChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.latitude, &mut offset, ctx)?;
+        this.gwrite_with(self.longitude, &mut offset, ctx)?;
+        this.gwrite_with(self.altitude, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Drone's speed changed
+pub struct SpeedChanged {
+    /// Speed relative to the North (when drone moves to the north, speed is > 0) (in m/s)
+    pub speed_x: f32,
+    /// Speed relative to the East (when drone moves to the east, speed is > 0) (in m/s)
+    pub speed_y: f32,
+    /// Speed on the z axis (when drone moves down, speed is > 0) (in m/s)
+    pub speed_z: f32,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for SpeedChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            speed_x: src.gread_with(&mut offset, ctx)?,
+            speed_y: src.gread_with(&mut offset, ctx)?,
+            speed_z: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for SpeedChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.speed_x, &mut offset, ctx)?;
+        this.gwrite_with(self.speed_y, &mut offset, ctx)?;
+        this.gwrite_with(self.speed_z, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Drone's attitude changed
+pub struct AttitudeChanged {
+    /// roll value (in radian)
+    pub roll: f32,
+    /// Pitch value (in radian)
+    pub pitch: f32,
+    /// Yaw value (in radian)
+    pub yaw: f32,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AttitudeChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            roll: src.gread_with(&mut offset, ctx)?,
+            pitch: src.gread_with(&mut offset, ctx)?,
+            yaw: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AttitudeChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.roll, &mut offset, ctx)?;
+        this.gwrite_with(self.pitch, &mut offset, ctx)?;
+        this.gwrite_with(self.yaw, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Auto takeoff mode
+pub struct AutoTakeOffModeChanged {
+    /// State of automatic take off mode (1 if enabled)
+    pub state: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AutoTakeOffModeChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            state: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AutoTakeOffModeChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.state, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Drone's altitude changed
+pub struct AltitudeChanged {
+    /// Altitude in meters
+    pub altitude: f64,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AltitudeChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            altitude: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AltitudeChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.altitude, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Drone's location changed
+pub struct GpsLocationChanged {
+    /// Latitude location in decimal degrees (500.0 if not available)
+    pub latitude: f64,
+    /// Longitude location in decimal degrees (500.0 if not available)
+    pub longitude: f64,
+    /// Altitude location in meters.
+    pub altitude: f64,
+    /// Latitude location error in meters (1 sigma/standard deviation) -1 if not available.
+    pub latitude_accuracy: i8,
+    /// Longitude location error in meters (1 sigma/standard deviation) -1 if not available.
+    pub longitude_accuracy: i8,
+    /// Altitude location error in meters (1 sigma/standard deviation) -1 if not available.
+    pub altitude_accuracy: i8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for GpsLocationChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            latitude: src.gread_with(&mut offset, ctx)?,
+            longitude: src.gread_with(&mut offset, ctx)?,
+            altitude: src.gread_with(&mut offset, ctx)?,
+            latitude_accuracy: src.gread_with(&mut offset, ctx)?,
+            longitude_accuracy: src.gread_with(&mut offset, ctx)?,
+            altitude_accuracy: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for GpsLocationChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.latitude, &mut offset, ctx)?;
+        this.gwrite_with(self.longitude, &mut offset, ctx)?;
+        this.gwrite_with(self.altitude, &mut offset, ctx)?;
+        this.gwrite_with(self.latitude_accuracy, &mut offset, ctx)?;
+        this.gwrite_with(self.longitude_accuracy, &mut offset, ctx)?;
+        this.gwrite_with(self.altitude_accuracy, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Landing state
+pub struct LandingStateChanged {
+    /// Drone landing state
+    pub state: LandingStateChangedState,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for LandingStateChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            state: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for LandingStateChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.state, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Drone landing state
+pub enum LandingStateChangedState {
+    /// `0`: Linear landing
+    Linear,
+    /// `1`: Spiral landing
+    Spiral,
+}
+
+impl From<LandingStateChangedState> for u32 {
+    fn from(value: LandingStateChangedState) -> Self {
+        match value {
+            LandingStateChangedState::Linear => 0,
+            LandingStateChangedState::Spiral => 1,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for LandingStateChangedState {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Linear,
+            1 => Self::Spiral,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "LandingStateChangedState".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for LandingStateChangedState {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Drone's air speed changed
+pub struct AirSpeedChanged {
+    /// Speed relative to air on x axis (speed is always > 0) (in m/s)
+    pub air_speed: f32,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AirSpeedChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            air_speed: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AirSpeedChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.air_speed, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
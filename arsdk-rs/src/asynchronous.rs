@@ -0,0 +1,337 @@
+//! Async (`tokio`) version of the [`Drone`](crate::Drone)
+//!
+//! Enabled with the `tokio` feature.
+//! It shares the protocol state with the sync `Drone`, only the loops driving it are `tokio` tasks
+//! instead of OS threads, so the `Drone` can be used in `select!` together with timers and
+//! other futures. The Listener and the Command sender block on the [`Transport`], they run
+//! on `tokio`'s blocking threads.
+use crate::{
+    ack::{Notifier, RETRANSMIT_INTERVAL},
+    event::{Event, Filter},
+    frame,
+    handshake::Response,
+    session::{Session, WeakSession},
+    transport::{Transport, UdpTransport},
+    watchdog::WATCHDOG_INTERVAL,
+    Config, ConnectionError, ConnectionState, DeliveryStatus, Error, PilotingCommand, ReceiveStats,
+    Setpoint, Stats, PILOTING_INTERVAL, PING_INTERVAL, SHUTDOWN_POLL_INTERVAL,
+};
+use chrono::{DateTime, Utc};
+use log::{error, info};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::{
+    sync::{mpsc::Receiver, oneshot},
+    task::{spawn_blocking, JoinHandle},
+    time::delay_for,
+};
+
+#[derive(Clone, Debug)]
+/// The connection is closed with [`Drone::disconnect`] or when the last clone is dropped.
+pub struct Drone {
+    inner: Arc<DroneInner>,
+}
+
+#[derive(Debug)]
+struct DroneInner {
+    session: Arc<Session>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Drone {
+    /// Connects to a drone over UDP, see [`UdpTransport::connect`]
+    ///
+    /// The handshake is performed on a blocking thread.
+    pub async fn connect(config: Config) -> Result<Self, ConnectionError> {
+        let transport = {
+            let config = config.clone();
+
+            spawn_blocking(move || UdpTransport::connect(&config))
+                .await
+                .expect("The handshake panicked")?
+        };
+
+        Self::with_transport(config, transport).await
+    }
+
+    /// Connects to a drone over an already set up [`Transport`]
+    ///
+    /// * Spawns the Listener & the Command sender on blocking threads
+    /// * Spawns the timer tasks
+    pub async fn with_transport(
+        config: Config,
+        transport: impl Transport,
+    ) -> Result<Self, ConnectionError> {
+        let session = Arc::new(Session::new(&config, Arc::new(transport)));
+
+        let drone = Self {
+            inner: Arc::new(DroneInner {
+                session: session.clone(),
+                tasks: Mutex::new(Vec::new()),
+            }),
+        };
+
+        info!("Spawning Listener");
+        let listener = session.listener();
+        drone
+            .inner
+            .add_task(spawn_blocking(move || listener.listen()));
+
+        info!("Spawning CMD Sender");
+        let cmd_sender = session.command_sender();
+        drone
+            .inner
+            .add_task(spawn_blocking(move || cmd_sender.run()));
+
+        let session = session.downgrade();
+        drone.inner.add_task(spawn_ticks(
+            session.clone(),
+            RETRANSMIT_INTERVAL,
+            |session| session.retransmit(Instant::now()),
+        ));
+        // PINGs the Drone to measure the round-trip time
+        drone
+            .inner
+            .add_task(spawn_ticks(session.clone(), PING_INTERVAL, |session| {
+                session.ping()
+            }));
+        // sends nothing until the piloting is started
+        drone
+            .inner
+            .add_task(spawn_ticks(session.clone(), PILOTING_INTERVAL, |session| {
+                session.pilot()
+            }));
+        drone
+            .inner
+            .add_task(spawn_ticks(session, WATCHDOG_INTERVAL, |session| {
+                if let Some(reconnection) = session.check_connection(Instant::now()) {
+                    // not awaited, so disconnecting doesn't wait for the handshake
+                    spawn_blocking(move || reconnection.run());
+                }
+            }));
+
+        if config.send_datetime {
+            drone.send_datetime(Utc::now()).await?;
+        }
+
+        Ok(drone)
+    }
+
+    pub(crate) fn session(&self) -> &Session {
+        &self.inner.session
+    }
+
+    /// Disconnects from the Drone
+    ///
+    /// * Sends the Common `NetworkEvent::Disconnection` frame, see [`Config::send_disconnection`]
+    /// * Stops the spawned tasks and waits for them to finish, which closes the sockets
+    ///
+    /// A reconnection in progress isn't waited for, the sockets are closed once its handshake is done.
+    ///
+    /// Every clone of the Drone gets disconnected and any further sending
+    /// fails with [`Error::Disconnected`].
+    pub async fn disconnect(&self) {
+        self.session().shutdown();
+
+        let tasks = std::mem::take(&mut *self.inner.lock_tasks());
+        for task in tasks {
+            if task.await.is_err() {
+                error!("A Drone task panicked");
+            }
+        }
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.session().is_connected()
+    }
+
+    /// The current [`ConnectionState`], see [`Config::connection_timeout`]
+    pub fn connection_state(&self) -> ConnectionState {
+        self.session().connection_state()
+    }
+
+    /// When we last received a frame from the Drone
+    pub fn last_received(&self) -> Instant {
+        self.session().last_received()
+    }
+
+    /// The `status`, `qos_mode`, `proto_v`, the ports & the arstream parameters
+    /// the Drone answered in the handshake, `None` if the [`Transport`] has no handshake
+    pub fn handshake_response(&self) -> Option<Response> {
+        self.session().handshake_response()
+    }
+
+    /// The received, duplicated & missed frames on the `buffer_id`
+    pub fn receive_stats(&self, buffer_id: frame::BufferID) -> ReceiveStats {
+        self.session().receive_stats(buffer_id)
+    }
+
+    /// A snapshot of the link quality: round-trip time, ack latency, retransmissions,
+    /// throughput and the received frames per `BufferID`
+    pub fn stats(&self) -> Stats {
+        self.session().stats()
+    }
+
+    /// Starts sending the `command` with the current [`Setpoint`] every [`PILOTING_INTERVAL`]
+    pub fn start_piloting(&self, command: PilotingCommand) {
+        self.session().pilot.start(command)
+    }
+
+    /// Stops the piloting loop and resets the [`Setpoint`]
+    pub fn stop_piloting(&self) {
+        self.session().pilot.stop()
+    }
+
+    pub fn setpoint(&self) -> Setpoint {
+        self.session().pilot.setpoint()
+    }
+
+    pub fn set_setpoint(&self, setpoint: Setpoint) {
+        self.session().pilot.update(|current| *current = setpoint)
+    }
+
+    pub fn set_roll(&self, roll: i8) {
+        self.session().pilot.update(|setpoint| setpoint.roll = roll)
+    }
+
+    pub fn set_pitch(&self, pitch: i8) {
+        self.session()
+            .pilot
+            .update(|setpoint| setpoint.pitch = pitch)
+    }
+
+    pub fn set_yaw(&self, yaw: i8) {
+        self.session().pilot.update(|setpoint| setpoint.yaw = yaw)
+    }
+
+    pub fn set_gaz(&self, gaz: i8) {
+        self.session().pilot.update(|setpoint| setpoint.gaz = gaz)
+    }
+
+    /// Subscribes to all the [`Event`]s of the Drone
+    pub fn subscribe(&self) -> Receiver<Event> {
+        self.subscribe_with(Filter::default())
+    }
+
+    /// Subscribes to the [`Event`]s of the Drone that match the `filter`
+    pub fn subscribe_with(&self, filter: Filter) -> Receiver<Event> {
+        self.session().subscribers.subscribe_async(filter)
+    }
+
+    /// Sends the frame without waiting for it to be acknowledged.
+    ///
+    /// `Type::DataWithAck` frames are still retransmitted until acknowledged,
+    /// see [`Config::ack_retries`].
+    ///
+    /// The frames are sent by the Command sender, its failures are published as
+    /// `Event::SendFailed`.
+    pub async fn send_frame(&self, frame: frame::Frame) -> Result<(), Error> {
+        self.session().send_frame(frame, None)
+    }
+
+    /// Sends a `Type::DataWithAck` frame and waits until the Drone acknowledges it
+    /// or all the retransmissions timed out.
+    ///
+    /// Fails with `Error::Send` if the Command sender couldn't send it.
+    pub async fn send_frame_with_ack(&self, frame: frame::Frame) -> Result<DeliveryStatus, Error> {
+        let (tx, rx) = oneshot::channel();
+
+        self.session()
+            .send_frame_with_ack(frame, Notifier::Async(tx))?;
+
+        // the notifier is dropped without a status if the frame gets replaced
+        rx.await.unwrap_or(Ok(DeliveryStatus::Timeout))
+    }
+
+    pub async fn send_raw_message(&self, raw_message: &[u8]) -> Result<(), Error> {
+        self.session().send_raw_message(raw_message)
+    }
+
+    pub async fn send_datetime(&self, date: DateTime<Utc>) -> Result<(), Error> {
+        self.session().send_datetime(date)
+    }
+}
+
+impl DroneInner {
+    fn add_task(&self, task: JoinHandle<()>) {
+        self.lock_tasks().push(task);
+    }
+
+    fn lock_tasks(&self) -> std::sync::MutexGuard<'_, Vec<JoinHandle<()>>> {
+        self.tasks.lock().expect("Tasks lock poisoned")
+    }
+}
+
+impl Drop for DroneInner {
+    fn drop(&mut self) {
+        // The tasks only hold a `WeakSession`, so they stop on their own
+        self.session.shutdown();
+    }
+}
+
+/// Calls `tick` every `interval` until the Drone is disconnected
+fn spawn_ticks(
+    weak_session: WeakSession,
+    interval: Duration,
+    tick: impl Fn(&Arc<Session>) + Send + 'static,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            if !delay_while_connected(&weak_session, interval).await {
+                break;
+            }
+
+            match weak_session.upgrade() {
+                Some(session) => tick(&session),
+                None => break,
+            }
+        }
+    })
+}
+
+/// Returns `false` if the Drone got disconnected in the meantime
+async fn delay_while_connected(weak_session: &WeakSession, duration: Duration) -> bool {
+    let until = Instant::now() + duration;
+
+    loop {
+        let now = Instant::now();
+        if now >= until {
+            return weak_session.upgrade().is_some();
+        }
+
+        if weak_session.upgrade().is_none() {
+            return false;
+        }
+
+        delay_for(SHUTDOWN_POLL_INTERVAL.min(until - now)).await;
+    }
+}
+
+#[cfg(test)]
+mod asynchronous_tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+    use std::net::Ipv4Addr;
+
+    #[tokio::test]
+    async fn test_piloting_runs_in_a_single_task() {
+        let (controller, _drone) = MemoryTransport::pair();
+        let config = Config::builder(Ipv4Addr::LOCALHOST)
+            .send_disconnection(false)
+            .build();
+        let drone = Drone::with_transport(config, controller)
+            .await
+            .expect("Should connect over the transport");
+        let tasks = drone.inner.lock_tasks().len();
+
+        for _ in 0..3 {
+            drone.start_piloting(PilotingCommand::PCMD);
+            drone.stop_piloting();
+        }
+        drone.start_piloting(PilotingCommand::PCMD);
+
+        assert_eq!(tasks, drone.inner.lock_tasks().len());
+    }
+}
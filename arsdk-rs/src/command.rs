@@ -2,7 +2,7 @@ use crate::ardrone3::ArDrone3;
 use crate::common;
 use crate::jumping_sumo;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 /// u8
 pub enum Feature {
     Common(Option<common::Class>),    // ARCOMMANDS_ID_FEATURE_COMMON = 0,
@@ -83,9 +83,25 @@ impl Into<u8> for &Feature {
     }
 }
 
+impl Feature {
+    /// The id of the Feature's Class, if there is one
+    pub fn class_id(&self) -> Option<u8> {
+        match self {
+            Feature::Common(class) => class.as_ref().map(|class| class.into()),
+            Feature::ArDrone3(class) => class.as_ref().map(|class| class.into()),
+            Feature::JumpingSumo(class) => Some(class.into()),
+            Feature::Unknown { data, .. } => data.first().copied(),
+            _ => None,
+        }
+    }
+}
+
 pub mod scroll_impl {
     use super::*;
-    use crate::{frame::Error, parse::read_unknown};
+    use crate::{
+        frame::Error,
+        parse::{read_unknown, write_bytes},
+    };
     use scroll::{ctx, Endian, Pread, Pwrite};
 
     impl<'a> ctx::TryFromCtx<'a, Endian> for Feature {
@@ -164,9 +180,12 @@ pub mod scroll_impl {
             this.gwrite_with::<u8>((&self).into(), &mut offset, ctx)?;
 
             match self {
-                // Self::Common(common) => {
-                //     this.gwrite_with(common, &mut offset, ctx)?;
-                // }
+                Self::Common(common) => {
+                    if let Some(common) = common {
+                        this.gwrite_with(common, &mut offset, ctx)?;
+                    }
+                    // else leave it empty
+                }
                 Self::ArDrone3(ardrone3) => {
                     if let Some(ardrone3) = ardrone3 {
                         this.gwrite_with(ardrone3, &mut offset, ctx)?;
@@ -177,9 +196,16 @@ pub mod scroll_impl {
                     this.gwrite_with(js, &mut offset, ctx)?;
                 }
                 Self::Unknown { data, .. } => {
-                    this.gwrite_with(data.as_slice(), &mut offset, ())?;
+                    write_bytes(this, &data, &mut offset)?;
+                }
+                // they are decoded as `Feature::Unknown`, which is the one to send them with
+                _ => {
+                    return Err(scroll::Error::BadInput {
+                        size: 0,
+                        msg: "the Feature is not implemented, use `Feature::Unknown`",
+                    }
+                    .into())
                 }
-                _ => unimplemented!("Not all Features are impled"),
             }
 
             Ok(offset)
@@ -192,21 +218,23 @@ pub mod scroll_impl {
 #[cfg(test)]
 mod command_tests {
     use super::*;
+    use crate::ardrone3::piloting;
+    use scroll::{Pread, Pwrite, LE};
 
     #[test]
     fn test_feature() {
         assert_feature(
-            Feature::Common(Some(common::Class::Common(common::Common::AllStates))),
+            Feature::Common(Some(common::Class::Common(common::common::Command::AllStates))),
             0,
         );
         assert_feature(
-            Feature::ArDrone3(Some(ArDrone3::Piloting(crate::ardrone3::Piloting::TakeOff))),
+            Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::TakeOff))),
             1,
         );
         assert_feature(Feature::Minidrone, 2);
         assert_feature(
-            Feature::JumpingSumo(jumping_sumo::Class::Piloting(
-                jumping_sumo::PilotingID::Pilot(jumping_sumo::PilotState::default()),
+            Feature::JumpingSumo(jumping_sumo::Class::Animations(
+                jumping_sumo::animations::Command::JumpStop,
             )),
             3,
         );
@@ -226,6 +254,112 @@ mod command_tests {
         assert_feature(Feature::SequoiaCam, 147);
     }
 
+    #[test]
+    fn test_round_trips() {
+        use crate::{
+            ardrone3::{self, media_streaming, piloting_state},
+            common::{common::Command as Common, network_event},
+            jumping_sumo::{animations, piloting_state as js_piloting_state},
+        };
+        use chrono::{DateTime, Utc};
+
+        fn utc(date_time: &str) -> DateTime<Utc> {
+            date_time.parse().expect("Should be a valid date time")
+        }
+
+        let features = vec![
+            Feature::Common(None),
+            Feature::Common(Some(common::Class::NetworkEvent(
+                network_event::Command::Disconnection(network_event::Disconnection {
+                    cause: network_event::DisconnectionCause::OffButton,
+                }),
+            ))),
+            Feature::Common(Some(common::Class::Common(Common::AllStates))),
+            Feature::Common(Some(common::Class::Common(Common::current_date(&utc(
+                "2020-04-26T00:00:00Z",
+            ))))),
+            Feature::Common(Some(common::Class::Common(Common::current_time(&utc(
+                "1970-01-01T15:06:11Z",
+            ))))),
+            Feature::Common(Some(common::Class::Common(Common::Reboot))),
+            Feature::Common(Some(common::Class::Unknown {
+                class: 14,
+                data: vec![1, 0, 0],
+            })),
+            Feature::ArDrone3(None),
+            Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::TakeOff))),
+            Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::Pcmd(
+                piloting::Pcmd {
+                    flag: 1,
+                    roll: -10,
+                    pitch: 20,
+                    yaw: 0,
+                    gaz: 100,
+                    // only the lowest 24 bits of the milliseconds are sent
+                    timestamp_and_seq_num: ardrone3::timestamp_and_seq_num(
+                        utc("1970-01-01T00:20:34.567Z"),
+                        9,
+                    ),
+                },
+            )))),
+            Feature::ArDrone3(Some(ArDrone3::PilotingState(
+                piloting_state::Command::FlyingStateChanged(piloting_state::FlyingStateChanged {
+                    state: piloting_state::FlyingStateChangedState::Hovering,
+                }),
+            ))),
+            Feature::ArDrone3(Some(ArDrone3::PilotingState(
+                piloting_state::Command::Unknown {
+                    command: 21,
+                    data: vec![126, 163, 163, 64],
+                },
+            ))),
+            Feature::ArDrone3(Some(ArDrone3::MediaStreaming(
+                media_streaming::Command::VideoEnable(media_streaming::VideoEnable { enable: 1 }),
+            ))),
+            Feature::ArDrone3(Some(ArDrone3::Unknown {
+                ardrone3: 99,
+                data: vec![],
+            })),
+            Feature::JumpingSumo(jumping_sumo::Class::Animations(animations::Command::Jump(
+                animations::Jump {
+                    r#type: animations::JumpType::High,
+                },
+            ))),
+            Feature::JumpingSumo(jumping_sumo::Class::PilotingState(
+                js_piloting_state::Command::SpeedChanged(js_piloting_state::SpeedChanged {
+                    speed: -50,
+                    real_speed: -300,
+                }),
+            )),
+            Feature::Unknown {
+                feature: 149,
+                data: vec![0, 3, 0, 91, 33],
+            },
+        ];
+
+        for feature in features {
+            let mut buf = [0_u8; 32];
+            let written = buf
+                .pwrite_with(feature.clone(), 0, LE)
+                .expect("Should encode");
+            let decoded = buf[..written]
+                .pread_with::<Feature>(0, LE)
+                .expect("Should decode");
+
+            assert_eq!(feature, decoded);
+        }
+
+        // only `Feature::Unknown` is decoded for the other Features
+        let mut buf = [0_u8; 32];
+        assert!(buf.pwrite_with(Feature::Minidrone, 0, LE).is_err());
+        // and `Unknown` for the Classes & Commands without their arguments
+        let overheat = Feature::Common(Some(common::Class::Overheat));
+        assert!(matches!(
+            buf.pwrite_with(overheat, 0, LE),
+            Err(crate::frame::Error::Scroll(scroll::Error::BadInput { .. }))
+        ));
+    }
+
     fn assert_feature(ref f: Feature, v: u8) {
         let as_u8: u8 = f.into();
         assert_eq!(v, as_u8);
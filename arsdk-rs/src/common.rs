@@ -1,48 +1,68 @@
-use chrono::{offset::Utc, DateTime};
-use std::ffi::CString;
-
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[allow(clippy::module_inception)]
+pub mod common;
+pub mod common_state;
+pub mod network;
+pub mod network_event;
+pub mod settings;
+pub mod settings_state;
+
+use arsdk_derive::FramePart;
+use chrono::{offset::Utc, DateTime, NaiveDate, NaiveTime, TimeZone};
+
+#[derive(Debug, PartialEq, Eq, Clone, FramePart)]
+#[arsdk(id_type = "u8")]
 /// u8
 pub enum Class {
-    Network,        // ARCOMMANDS_ID_COMMON_CLASS_NETWORK = 0,
-    NetworkEvent,   // ARCOMMANDS_ID_COMMON_CLASS_NETWORKEVENT = 1,
-    Settings,       // ARCOMMANDS_ID_COMMON_CLASS_SETTINGS = 2,
-    SettingsState,  // ARCOMMANDS_ID_COMMON_CLASS_SETTINGSSTATE = 3,
-    Common(Common), // ARCOMMANDS_ID_COMMON_CLASS_COMMON = 4,
+    /// ARCOMMANDS_ID_COMMON_CLASS_NETWORK = 0,
+    ///
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 0)]
+    Network(network::Command),
+    /// ARCOMMANDS_ID_COMMON_CLASS_NETWORKEVENT = 1,
+    ///
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 1)]
+    NetworkEvent(network_event::Command),
+    /// ARCOMMANDS_ID_COMMON_CLASS_SETTINGS = 2,
+    ///
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 2)]
+    Settings(settings::Command),
+    /// ARCOMMANDS_ID_COMMON_CLASS_SETTINGSSTATE = 3,
+    ///
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 3)]
+    SettingsState(settings_state::Command),
+    /// ARCOMMANDS_ID_COMMON_CLASS_COMMON = 4,
+    ///
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 4)]
+    Common(common::Command),
     /// ARCOMMANDS_ID_COMMON_CLASS_COMMONSTATE = 5,
     ///
     /// Bytes: 2 127 0 [12 0 0 0] [0] [5] [1 0] 100
-    /// Common Common BatterStateChanged 100%
-    /// _percent: u8
+    /// Common CommonState BatteryStateChanged 100%
     ///
-    /// eARCOMMANDS_ID_COMMON_COMMONSTATE_CMD: u16
-    ////
-    /// typedef enum {
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_ALLSTATESCHANGED = 0,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_BATTERYSTATECHANGED = 1,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_MASSSTORAGESTATELISTCHANGED = 2,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_MASSSTORAGEINFOSTATELISTCHANGED = 3,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_CURRENTDATECHANGED = 4,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_CURRENTTIMECHANGED = 5,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_MASSSTORAGEINFOREMAININGLISTCHANGED = 6,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_WIFISIGNALCHANGED = 7,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_SENSORSSTATESLISTCHANGED = 8,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_PRODUCTMODEL = 9,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_COUNTRYLISTKNOWN = 10,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_DEPRECATEDMASSSTORAGECONTENTCHANGED = 11,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_MASSSTORAGECONTENT = 12,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_MASSSTORAGECONTENTFORCURRENTRUN = 13,
-    ///     ARCOMMANDS_ID_COMMON_COMMONSTATE_CMD_VIDEORECORDINGTIMESTAMP = 14,
-    /// } eARCOMMANDS_ID_COMMON_COMMONSTATE_CMD;
-    CommonState,
-    Overheat,                // ARCOMMANDS_ID_COMMON_CLASS_OVERHEAT = 6,
-    OverheatState,           // ARCOMMANDS_ID_COMMON_CLASS_OVERHEATSTATE = 7,
-    Controller,              // ARCOMMANDS_ID_COMMON_CLASS_CONTROLLER = 8,
-    WifiSettings,            // ARCOMMANDS_ID_COMMON_CLASS_WIFISETTINGS = 9,
-    WifiSettingsState,       // ARCOMMANDS_ID_COMMON_CLASS_WIFISETTINGSSTATE = 10,
-    Mavlink,                 // ARCOMMANDS_ID_COMMON_CLASS_MAVLINK = 11,
-    MavlinkState,            // ARCOMMANDS_ID_COMMON_CLASS_MAVLINKSTATE = 12,
-    FlightPlanSettings,      // ARCOMMANDS_ID_COMMON_CLASS_FLIGHTPLANSETTINGS = 32,
+    /// Generated by `arsdk-gen`
+    #[arsdk(id = 5)]
+    CommonState(common_state::Command),
+    #[arsdk(id = 6, skip_decode)]
+    Overheat, // ARCOMMANDS_ID_COMMON_CLASS_OVERHEAT = 6,
+    #[arsdk(id = 7, skip_decode)]
+    OverheatState, // ARCOMMANDS_ID_COMMON_CLASS_OVERHEATSTATE = 7,
+    #[arsdk(id = 8, skip_decode)]
+    Controller, // ARCOMMANDS_ID_COMMON_CLASS_CONTROLLER = 8,
+    #[arsdk(id = 9, skip_decode)]
+    WifiSettings, // ARCOMMANDS_ID_COMMON_CLASS_WIFISETTINGS = 9,
+    #[arsdk(id = 10, skip_decode)]
+    WifiSettingsState, // ARCOMMANDS_ID_COMMON_CLASS_WIFISETTINGSSTATE = 10,
+    #[arsdk(id = 11, skip_decode)]
+    Mavlink, // ARCOMMANDS_ID_COMMON_CLASS_MAVLINK = 11,
+    #[arsdk(id = 12, skip_decode)]
+    MavlinkState, // ARCOMMANDS_ID_COMMON_CLASS_MAVLINKSTATE = 12,
+    #[arsdk(id = 32, skip_decode)]
+    FlightPlanSettings, // ARCOMMANDS_ID_COMMON_CLASS_FLIGHTPLANSETTINGS = 32,
+    #[arsdk(id = 33, skip_decode)]
     FlightPlanSettingsState, // ARCOMMANDS_ID_COMMON_CLASS_FLIGHTPLANSETTINGSSTATE = 33,
     /// ARCOMMANDS_ID_COMMON_CLASS_CALIBRATION = 13
     ///
@@ -53,6 +73,7 @@ pub enum Class {
     /// Second cmd:
     /// u16 - ARCOMMANDS_ID_COMMON_CALIBRATION_CMD_PITOTCALIBRATION
     /// u8 - _calibrate
+    #[arsdk(id = 13, skip_decode)]
     Calibration,
     /// ARCOMMANDS_ID_COMMON_CLASS_CALIBRATIONSTATE = 14
     /// u16 - ARCOMMANDS_ID_COMMON_CALIBRATIONSTATE_CMD_MAGNETOCALIBRATIONSTATECHANGED
@@ -60,274 +81,312 @@ pub enum Class {
     /// u8 - _yAxisCalibration
     /// u8 - _zAxisCalibration
     /// u8 - _calibrationFailed
+    #[arsdk(id = 14, skip_decode)]
     CalibrationState,
+    #[arsdk(id = 15, skip_decode)]
     CameraSettingsState, // ARCOMMANDS_ID_COMMON_CLASS_CAMERASETTINGSSTATE = 15,
-    Gps,                 // ARCOMMANDS_ID_COMMON_CLASS_GPS = 16,
-    FlightPlanState,     // ARCOMMANDS_ID_COMMON_CLASS_FLIGHTPLANSTATE = 17,
-    FlightPlanEvent,     // ARCOMMANDS_ID_COMMON_CLASS_FLIGHTPLANEVENT = 19,
+    #[arsdk(id = 16, skip_decode)]
+    Gps, // ARCOMMANDS_ID_COMMON_CLASS_GPS = 16,
+    #[arsdk(id = 17, skip_decode)]
+    FlightPlanState, // ARCOMMANDS_ID_COMMON_CLASS_FLIGHTPLANSTATE = 17,
+    #[arsdk(id = 19, skip_decode)]
+    FlightPlanEvent, // ARCOMMANDS_ID_COMMON_CLASS_FLIGHTPLANEVENT = 19,
+    #[arsdk(id = 18, skip_decode)]
     ArLibsVersionsState, // ARCOMMANDS_ID_COMMON_CLASS_ARLIBSVERSIONSSTATE = 18,
-    Audio,               // ARCOMMANDS_ID_COMMON_CLASS_AUDIO = 20,
-    AudioState,          // ARCOMMANDS_ID_COMMON_CLASS_AUDIOSTATE = 21,
-    HeadLights,          // ARCOMMANDS_ID_COMMON_CLASS_HEADLIGHTS = 22,
-    HeadLightsState,     // ARCOMMANDS_ID_COMMON_CLASS_HEADLIGHTSSTATE = 23,
-    Animations,          // ARCOMMANDS_ID_COMMON_CLASS_ANIMATIONS = 24,
-    AnimationsState,     // ARCOMMANDS_ID_COMMON_CLASS_ANIMATIONSSTATE = 25,
-    Accessory,           // ARCOMMANDS_ID_COMMON_CLASS_ACCESSORY = 26,
-    AccessoryState,      // ARCOMMANDS_ID_COMMON_CLASS_ACCESSORYSTATE = 27,
-    Charger,             // ARCOMMANDS_ID_COMMON_CLASS_CHARGER = 28,
-    ChargerState,        // ARCOMMANDS_ID_COMMON_CLASS_CHARGERSTATE = 29,
-    Runstate,            // ARCOMMANDS_ID_COMMON_CLASS_RUNSTATE = 30,
-    Factory,             // ARCOMMANDS_ID_COMMON_CLASS_FACTORY = 31,
-    Unknown {
-        class: u8,
-        data: Vec<u8>,
-    },
+    #[arsdk(id = 20, skip_decode)]
+    Audio, // ARCOMMANDS_ID_COMMON_CLASS_AUDIO = 20,
+    #[arsdk(id = 21, skip_decode)]
+    AudioState, // ARCOMMANDS_ID_COMMON_CLASS_AUDIOSTATE = 21,
+    #[arsdk(id = 22, skip_decode)]
+    HeadLights, // ARCOMMANDS_ID_COMMON_CLASS_HEADLIGHTS = 22,
+    #[arsdk(id = 23, skip_decode)]
+    HeadLightsState, // ARCOMMANDS_ID_COMMON_CLASS_HEADLIGHTSSTATE = 23,
+    #[arsdk(id = 24, skip_decode)]
+    Animations, // ARCOMMANDS_ID_COMMON_CLASS_ANIMATIONS = 24,
+    #[arsdk(id = 25, skip_decode)]
+    AnimationsState, // ARCOMMANDS_ID_COMMON_CLASS_ANIMATIONSSTATE = 25,
+    #[arsdk(id = 26, skip_decode)]
+    Accessory, // ARCOMMANDS_ID_COMMON_CLASS_ACCESSORY = 26,
+    #[arsdk(id = 27, skip_decode)]
+    AccessoryState, // ARCOMMANDS_ID_COMMON_CLASS_ACCESSORYSTATE = 27,
+    #[arsdk(id = 28, skip_decode)]
+    Charger, // ARCOMMANDS_ID_COMMON_CLASS_CHARGER = 28,
+    #[arsdk(id = 29, skip_decode)]
+    ChargerState, // ARCOMMANDS_ID_COMMON_CLASS_CHARGERSTATE = 29,
+    #[arsdk(id = 30, skip_decode)]
+    Runstate, // ARCOMMANDS_ID_COMMON_CLASS_RUNSTATE = 30,
+    #[arsdk(id = 31, skip_decode)]
+    Factory, // ARCOMMANDS_ID_COMMON_CLASS_FACTORY = 31,
+    #[arsdk(unknown)]
+    Unknown { class: u8, data: Vec<u8> },
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-/// u8
-pub enum Common {
-    /// ARCOMMANDS_ID_COMMON_COMMON_CMD_ALLSTATES = 0,
-    AllStates,
-    /// ARCOMMANDS_ID_COMMON_COMMON_CMD_CURRENTDATE = 1,
-    CurrentDate(DateTime<Utc>),
-    /// ARCOMMANDS_ID_COMMON_COMMON_CMD_CURRENTTIME = 2,
-    CurrentTime(DateTime<Utc>),
-    /// ARCOMMANDS_ID_COMMON_COMMON_CMD_REBOOT = 3,
-    Reboot,
-}
-
-// "yyyy-MM-dd"forCommon.Common.CurrentDate.  Ex:2015-08-27
-fn format_date(date: &DateTime<Utc>) -> CString {
-    let format = date.format("%Y-%m-%d").to_string();
+/// "yyyy-MM-dd" for Common.Common.CurrentDate. Ex: 2015-08-27
+const DATE_FORMAT: &str = "%Y-%m-%d";
+/// "'T'HHmmssZZZ" for Common.Common.CurrentTime. Ex: T101527+0200
+const TIME_FORMAT: &str = "T%H%M%S%z";
 
-    CString::new(format.as_bytes()).expect("CString::new failed with formatted date")
+fn format_date(date: &DateTime<Utc>) -> String {
+    date.format(DATE_FORMAT).to_string()
 }
-// "’T’HHmmssZZZ"forCommon.Common.CurrentTime. Ex:T101527+0200.
-fn format_time(time: &DateTime<Utc>) -> CString {
-    let format = time.format("T%H%M%S%z").to_string();
 
-    CString::new(format.as_bytes()).expect("CString::new failed with formatted date")
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.format(TIME_FORMAT).to_string()
 }
 
-// --------------------- Conversion impls --------------------- //
+/// Parses the `date` of `common::CurrentDate` & `common_state::CurrentDateChanged`, at midnight UTC
+pub fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(date, DATE_FORMAT).ok()?;
 
-impl Into<u8> for &Class {
-    fn into(self) -> u8 {
-        use Class::*;
-        match self {
-            Network => 0,
-            NetworkEvent => 1,
-            Settings => 2,
-            SettingsState => 3,
-            Common(_) => 4,
-            CommonState => 5,
-            Overheat => 6,
-            OverheatState => 7,
-            Controller => 8,
-            WifiSettings => 9,
-            WifiSettingsState => 10,
-            Mavlink => 11,
-            MavlinkState => 12,
-            FlightPlanSettings => 32,
-            FlightPlanSettingsState => 33,
-            Calibration => 13,
-            CalibrationState => 14,
-            CameraSettingsState => 15,
-            Gps => 16,
-            FlightPlanState => 17,
-            FlightPlanEvent => 19,
-            ArLibsVersionsState => 18,
-            Audio => 20,
-            AudioState => 21,
-            HeadLights => 22,
-            HeadLightsState => 23,
-            Animations => 24,
-            AnimationsState => 25,
-            Accessory => 26,
-            AccessoryState => 27,
-            Charger => 28,
-            ChargerState => 29,
-            Runstate => 30,
-            Factory => 31,
-            Unknown { class, .. } => *class,
-        }
-    }
+    Some(Utc.from_utc_datetime(&date.and_time(NaiveTime::from_hms_opt(0, 0, 0)?)))
 }
 
-impl Into<u8> for Common {
-    fn into(self) -> u8 {
-        match self {
-            Self::AllStates => 0,
-            Self::CurrentDate(_) => 1,
-            Self::CurrentTime(_) => 2,
-            Self::Reboot => 3,
-        }
-    }
+/// Parses the `time` of `common::CurrentTime` & `common_state::CurrentTimeChanged`,
+/// on the 1st of January 1970 UTC
+pub fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+    // `%z` needs a date to be parsed with
+    let time = DateTime::parse_from_str(
+        &format!("{}{}", epoch.format(DATE_FORMAT), time),
+        &format!("{}{}", DATE_FORMAT, TIME_FORMAT),
+    )
+    .ok()?;
+
+    // the offset might move it to another day
+    Some(Utc.from_utc_datetime(&epoch.and_time(time.with_timezone(&Utc).time())))
 }
 
-pub mod scroll_impl {
-    use super::*;
-    use crate::frame::Error;
-    use scroll::{ctx, Endian, Pread, Pwrite};
-
-    impl<'a> ctx::TryFromCtx<'a, Endian> for Class {
-        type Error = Error;
-
-        // and the lifetime annotation on `&'a [u8]` here
-        fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
-            let mut offset = 0;
-
-            let class = match src.gread_with::<u8>(&mut offset, ctx)? {
-                0 => Self::Network,
-                1 => Self::NetworkEvent,
-                2 => Self::Settings,
-                3 => Self::SettingsState,
-                4 => {
-                    let common = src.gread_with(&mut offset, ctx)?;
-
-                    Self::Common(common)
-                }
-                // 5 => Self::CommonState,
-                // 6 => Self::Overheat,
-                // 7 => Self::OverheatState,
-                // 8 => Self::Controller,
-                // 9 => Self::WifiSettings,
-                // 10 => Self::WifiSettingsState,
-                // 11 => Self::Mavlink,
-                // 12 => Self::MavlinkState,
-                // 13 => Self::Calibration,
-                // 14 => Self::CalibrationState,
-                // 15 => Self::CameraSettingsState,
-                // 16 => Self::Gps,
-                // 17 => Self::FlightPlanState,
-                // 18 => Self::ArLibsVersionsState,
-                // 19 => Self::FlightPlanEvent,
-                // 20 => Self::Audio,
-                // 21 => Self::AudioState,
-                // 22 => Self::HeadLights,
-                // 23 => Self::HeadLightsState,
-                // 24 => Self::Animations,
-                // 25 => Self::AnimationsState,
-                // 26 => Self::Accessory,
-                // 27 => Self::AccessoryState,
-                // 28 => Self::Charger,
-                // 29 => Self::ChargerState,
-                // 30 => Self::Runstate,
-                // 31 => Self::Factory,
-                // 32 => Self::FlightPlanSettings,
-                // 33 => Self::FlightPlanSettingsState,
-                unknown_class => Self::Unknown {
-                    class: unknown_class,
-                    data: crate::parse::read_unknown(src, &mut offset)?,
-                },
-            };
-
-            Ok((class, offset))
-        }
+impl common::Command {
+    /// `CurrentDate` of the `date`, only the date is sent
+    pub fn current_date(date: &DateTime<Utc>) -> Self {
+        Self::CurrentDate(common::CurrentDate {
+            date: format_date(date),
+        })
     }
 
-    impl<'a> ctx::TryIntoCtx<Endian> for Class {
-        type Error = Error;
-
-        fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
-            let mut offset = 0;
-            this.gwrite_with::<u8>((&self).into(), &mut offset, ctx)?;
-
-            match self {
-                Self::Common(common) => {
-                    this.gwrite_with(common, &mut offset, ctx)?;
-                }
-                _ => unimplemented!("Not all Class are impled"),
-            };
-
-            Ok(offset)
-        }
+    /// `CurrentTime` of the `time`, only the time is sent
+    pub fn current_time(time: &DateTime<Utc>) -> Self {
+        Self::CurrentTime(common::CurrentTime {
+            time: format_time(time),
+        })
     }
+}
 
-    impl<'a> ctx::TryFromCtx<'a, Endian> for Common {
-        type Error = Error;
-
-        fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
-            use Common::*;
-            let mut offset = 0;
+// --------------------- Tests --------------------- //
 
-            let common = match src.gread_with::<u8>(&mut offset, ctx)? {
-                0 => AllStates,
-                // @TODO: FIX THIS!
-                1 => CurrentDate(Utc::now()),
-                // @TODO: FIX THIS!
-                2 => CurrentTime(Utc::now()),
-                3 => Reboot,
-                value => {
-                    return Err(Error::OutOfBound {
-                        value: value.into(),
-                        param: "Common".to_string(),
-                    })
-                }
-            };
+#[cfg(test)]
+mod common_tests {
+    use super::*;
+    use network_event::DisconnectionCause;
+    use scroll::{Pread, Pwrite, LE};
 
-            Ok((common, offset))
-        }
+    fn utc(date_time: &str) -> DateTime<Utc> {
+        date_time.parse().expect("Should be a valid date time")
     }
 
-    impl<'a> ctx::TryIntoCtx<Endian> for Common {
-        type Error = Error;
-
-        fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
-            let mut offset = 0;
+    #[test]
+    fn test_current_date_and_time() {
+        let mut date = vec![4, 1, 0];
+        date.extend_from_slice(b"2015-08-27\0");
+        let mut time = vec![4, 2, 0];
+        time.extend_from_slice(b"T101527+0200\0");
+
+        let current_date = date.pread_with::<Class>(0, LE).expect("Should decode");
+        match &current_date {
+            Class::Common(common::Command::CurrentDate(current_date)) => assert_eq!(
+                Some(utc("2015-08-27T00:00:00Z")),
+                parse_date(&current_date.date)
+            ),
+            class => panic!("Expected CurrentDate, got: {:?}", class),
+        }
 
-            this.gwrite_with::<u8>(self.into(), &mut offset, ctx)?;
+        let current_time = time.pread_with::<Class>(0, LE).expect("Should decode");
+        match &current_time {
+            Class::Common(common::Command::CurrentTime(current_time)) => assert_eq!(
+                Some(utc("1970-01-01T08:15:27Z")),
+                parse_time(&current_time.time)
+            ),
+            class => panic!("Expected CurrentTime, got: {:?}", class),
+        }
 
-            match self {
-                Self::CurrentDate(date) => {
-                    let date = format_date(&date);
-                    // null terminated C string
-                    this.gwrite_with(date.as_bytes_with_nul(), &mut offset, ())?;
-                }
-                Self::CurrentTime(time) => {
-                    // null terminated C string
-                    let time = format_time(&time);
+        let mut buf = [0_u8; 16];
+        let current_date = common::Command::current_date(&utc("2015-08-27T10:15:27Z"));
+        let written = buf
+            .pwrite_with(Class::Common(current_date), 0, LE)
+            .expect("Should encode");
+        assert_eq!(date.as_slice(), &buf[..written]);
+
+        let current_time = common::Command::current_time(&utc("2015-08-27T08:15:27Z"));
+        let written = buf
+            .pwrite_with(Class::Common(current_time), 0, LE)
+            .expect("Should encode");
+        assert_eq!(b"T081527+0000\0", &buf[3..written]);
+
+        // the offset moves it to the previous day
+        assert_eq!(
+            Some(utc("1970-01-01T23:00:00Z")),
+            parse_time("T010000+0200")
+        );
+        assert_eq!(None, parse_date("20"));
+    }
 
-                    // null terminated C string
-                    this.gwrite_with(time.as_bytes_with_nul(), &mut offset, ())?;
-                }
-                _ => unimplemented!("Not all Common are impled"),
-            }
+    fn assert_common_state(command: common_state::Command, bytes: &[u8]) {
+        let class = Class::CommonState(command);
+        let decoded = bytes.pread_with::<Class>(0, LE).expect("Should decode");
+        assert_eq!(class, decoded);
 
-            Ok(offset)
-        }
+        let mut buf = [0_u8; 64];
+        let written = buf.pwrite_with(class, 0, LE).expect("Should encode");
+        assert_eq!(bytes, &buf[..written]);
     }
-}
-// --------------------- Tests --------------------- //
-
-#[cfg(test)]
-mod common_tests {
-    use super::*;
-    // use chrono::prelude::*;
-    // #[test]
-    // fn test_format_time() {
-    //     // `2014-07-08T09:10:11Z`
-    //     let test_time = Utc.ymd(2014, 7, 8).and_hms(9, 10, 11);
-    //     assert_eq!(
-    //         "2014-07-08".to_string(),
-    //         String::from_utf8_lossy(&*format_date(&test_time))
-    //     );
 
-    //     assert_eq!(
-    //         "T091011+0000".to_string(),
-    //         String::from_utf8_lossy(&*format_time(&test_time))
-    //     );
-    // }
+    #[test]
+    fn test_common_state() {
+        use common_state::*;
+
+        assert_common_state(Command::AllStatesChanged, &[5, 0, 0]);
+        assert_common_state(
+            Command::BatteryStateChanged(BatteryStateChanged { percent: 100 }),
+            &[5, 1, 0, 100],
+        );
+        assert_common_state(
+            Command::MassStorageStateListChanged(MassStorageStateListChanged {
+                mass_storage_id: 0,
+                name: "internal".into(),
+            }),
+            &[&[5, 2, 0, 0][..], b"internal\0"].concat(),
+        );
+        assert_common_state(
+            Command::MassStorageInfoStateListChanged(MassStorageInfoStateListChanged {
+                mass_storage_id: 0,
+                size: 1024,
+                used_size: 300,
+                plugged: 1,
+                full: 0,
+                internal: 1,
+            }),
+            &[5, 3, 0, 0, 0, 4, 0, 0, 44, 1, 0, 0, 1, 0, 1],
+        );
+        assert_common_state(
+            Command::CurrentDateChanged(CurrentDateChanged {
+                date: "2020-04-26".into(),
+            }),
+            &[&[5, 4, 0][..], b"2020-04-26\0"].concat(),
+        );
+        assert_common_state(
+            Command::CurrentTimeChanged(CurrentTimeChanged {
+                time: "T150611+0200".into(),
+            }),
+            &[&[5, 5, 0][..], b"T150611+0200\0"].concat(),
+        );
+        assert_common_state(
+            Command::MassStorageInfoRemainingListChanged(MassStorageInfoRemainingListChanged {
+                free_space: 2048,
+                rec_time: 3600,
+                photo_remaining: 500,
+            }),
+            &[5, 6, 0, 0, 8, 0, 0, 16, 14, 244, 1, 0, 0],
+        );
+        assert_common_state(
+            Command::WifiSignalChanged(WifiSignalChanged { rssi: -42 }),
+            &[5, 7, 0, 214, 255],
+        );
+        assert_common_state(
+            Command::SensorsStatesListChanged(SensorsStatesListChanged {
+                sensor_name: SensorsStatesListChangedSensorName::Magnetometer,
+                sensor_state: 1,
+            }),
+            &[5, 8, 0, 4, 0, 0, 0, 1],
+        );
+        assert_common_state(
+            Command::ProductModel(ProductModel {
+                model: ProductModelModel::JsMarshall,
+            }),
+            &[5, 9, 0, 7, 0, 0, 0],
+        );
+        assert_common_state(
+            Command::CountryListKnown(CountryListKnown {
+                list_flags: 0x03,
+                country_codes: "FR;US".into(),
+            }),
+            &[&[5, 10, 0, 3][..], b"FR;US\0"].concat(),
+        );
+        assert_common_state(
+            Command::DeprecatedMassStorageContentChanged(DeprecatedMassStorageContentChanged {
+                mass_storage_id: 1,
+                nb_photos: 2,
+                nb_videos: 3,
+                nb_puds: 4,
+                nb_crash_logs: 5,
+            }),
+            &[5, 11, 0, 1, 2, 0, 3, 0, 4, 0, 5, 0],
+        );
+        assert_common_state(
+            Command::MassStorageContent(MassStorageContent {
+                mass_storage_id: 1,
+                nb_photos: 2,
+                nb_videos: 3,
+                nb_puds: 4,
+                nb_crash_logs: 5,
+                nb_raw_photos: 6,
+            }),
+            &[5, 12, 0, 1, 2, 0, 3, 0, 4, 0, 5, 0, 6, 0],
+        );
+        assert_common_state(
+            Command::MassStorageContentForCurrentRun(MassStorageContentForCurrentRun {
+                mass_storage_id: 1,
+                nb_photos: 10,
+                nb_videos: 2,
+                nb_raw_photos: 3,
+            }),
+            &[5, 13, 0, 1, 10, 0, 2, 0, 3, 0],
+        );
+        assert_common_state(
+            Command::VideoRecordingTimestamp(VideoRecordingTimestamp {
+                start_timestamp: 1_587_913_571_000,
+                stop_timestamp: 0,
+            }),
+            &[
+                5, 14, 0, 184, 58, 6, 183, 113, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            ],
+        );
+        assert_common_state(
+            Command::Unknown {
+                command: 15,
+                data: vec![1, 2],
+            },
+            &[5, 15, 0, 1, 2],
+        );
+
+        // a sensor that's not in the definition
+        let sensor = [5_u8, 8, 0, 6, 0, 0, 0, 1]
+            .pread_with::<Class>(0, LE)
+            .expect_err("Should fail");
+        assert_eq!(
+            "Out of bound value 6 for SensorsStatesListChangedSensorName",
+            sensor.to_string()
+        );
+        // the mass storage name without the NUL terminator
+        assert!([5_u8, 2, 0, 0, b'a'].pread_with::<Class>(0, LE).is_err());
+    }
 
     #[test]
     fn test_class() {
-        assert_class(Class::Network, 0);
-        assert_class(Class::NetworkEvent, 1);
-        assert_class(Class::Settings, 2);
-        assert_class(Class::SettingsState, 3);
-        assert_class(Class::Common(Common::AllStates), 4);
-        assert_class(Class::CommonState, 5);
+        assert_class(Class::Network(network::Command::Disconnect), 0);
+        assert_class(
+            Class::NetworkEvent(disconnection(DisconnectionCause::Unknown)),
+            1,
+        );
+        assert_class(Class::Settings(settings::Command::AllSettings), 2);
+        assert_class(
+            Class::SettingsState(settings_state::Command::AllSettingsChanged),
+            3,
+        );
+        assert_class(Class::Common(common::Command::AllStates), 4);
+        assert_class(
+            Class::CommonState(common_state::Command::AllStatesChanged),
+            5,
+        );
         assert_class(Class::Overheat, 6);
         assert_class(Class::OverheatState, 7);
         assert_class(Class::Controller, 8);
@@ -359,20 +418,28 @@ mod common_tests {
     }
 
     #[test]
-    fn test_common() {
-        assert_common(Common::AllStates, 0);
-        assert_common(Common::CurrentDate(chrono::offset::Utc::now()), 1);
-        assert_common(Common::CurrentTime(chrono::offset::Utc::now()), 2);
-        assert_common(Common::Reboot, 3);
+    fn test_network_event_disconnection() {
+        let disconnection = Class::NetworkEvent(disconnection(DisconnectionCause::OffButton));
+        let mut buf = [0_u8; 7];
+        let written = buf
+            .pwrite_with(disconnection.clone(), 0, LE)
+            .expect("Should serialize");
+
+        // Class: 1, Command (u16): 0, Cause (u32): 0
+        assert_eq!(7, written);
+        assert_eq!([1, 0, 0, 0, 0, 0, 0], buf);
+        assert_eq!(
+            disconnection,
+            buf.pread_with::<Class>(0, LE).expect("Should deserialize")
+        );
     }
 
-    fn assert_class(dc: Class, v: u8) {
-        let as_u8: u8 = (&dc).into();
-        assert_eq!(v, as_u8);
+    fn disconnection(cause: DisconnectionCause) -> network_event::Command {
+        network_event::Command::Disconnection(network_event::Disconnection { cause })
     }
 
-    fn assert_common(c: Common, v: u8) {
-        let as_u8: u8 = c.into();
+    fn assert_class(dc: Class, v: u8) {
+        let as_u8: u8 = (&dc).into();
         assert_eq!(v, as_u8);
     }
 }
@@ -0,0 +1,202 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `4`: Common commands
+//!
+//! Generated from the `Common` Class of `arsdk-gen/xml/common.xml` (the `common` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_string, read_unknown, write_bytes, write_string},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// u16
+pub enum Command {
+    /// `0`: Ask for all states
+    AllStates,
+    /// `1`: Set the date
+    CurrentDate(CurrentDate),
+    /// `2`: Set the time
+    CurrentTime(CurrentTime),
+    /// `3`: Reboot
+    Reboot,
+    /// `4`: Set the date/time
+    CurrentDateTime(CurrentDateTime),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::AllStates => 0,
+            Command::CurrentDate(_) => 1,
+            Command::CurrentTime(_) => 2,
+            Command::Reboot => 3,
+            Command::CurrentDateTime(_) => 4,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => Self::AllStates,
+            1 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::CurrentDate(args)
+            }
+            2 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::CurrentTime(args)
+            }
+            3 => Self::Reboot,
+            4 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::CurrentDateTime(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::CurrentDate(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::CurrentTime(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::CurrentDateTime(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+            // no arguments
+            _ => {}
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Set the date
+pub struct CurrentDate {
+    /// Date with ISO-8601 format
+    pub date: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for CurrentDate {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            date: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for CurrentDate {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.date, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Set the time
+pub struct CurrentTime {
+    /// Time with ISO-8601 format
+    pub time: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for CurrentTime {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            time: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for CurrentTime {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.time, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Set the date/time
+pub struct CurrentDateTime {
+    /// Date and time with ISO-8601 format
+    pub datetime: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for CurrentDateTime {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            datetime: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for CurrentDateTime {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.datetime, &mut offset)?;
+
+        Ok(offset)
+    }
+}
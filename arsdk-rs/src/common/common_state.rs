@@ -0,0 +1,936 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `5`: Common state from product
+//!
+//! Generated from the `CommonState` Class of `arsdk-gen/xml/common.xml` (the `common` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_string, read_unknown, write_bytes, write_string},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// u16
+pub enum Command {
+    /// `0`: All states have been sent
+    AllStatesChanged,
+    /// `1`: Battery state
+    BatteryStateChanged(BatteryStateChanged),
+    /// `2`: Mass storage state list
+    MassStorageStateListChanged(MassStorageStateListChanged),
+    /// `3`: Mass storage info state list
+    MassStorageInfoStateListChanged(MassStorageInfoStateListChanged),
+    /// `4`: Date changed
+    CurrentDateChanged(CurrentDateChanged),
+    /// `5`: Time changed
+    CurrentTimeChanged(CurrentTimeChanged),
+    /// `6`: Mass storage remaining data list
+    MassStorageInfoRemainingListChanged(MassStorageInfoRemainingListChanged),
+    /// `7`: Rssi changed
+    WifiSignalChanged(WifiSignalChanged),
+    /// `8`: Sensors state list
+    SensorsStatesListChanged(SensorsStatesListChanged),
+    /// `9`: Product sub-model
+    ProductModel(ProductModel),
+    /// `10`: Country list
+    CountryListKnown(CountryListKnown),
+    /// `11`: Mass storage content changed
+    DeprecatedMassStorageContentChanged(DeprecatedMassStorageContentChanged),
+    /// `12`: Mass storage content
+    MassStorageContent(MassStorageContent),
+    /// `13`: Mass storage content for current run
+    MassStorageContentForCurrentRun(MassStorageContentForCurrentRun),
+    /// `14`: Video recording timestamp
+    VideoRecordingTimestamp(VideoRecordingTimestamp),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::AllStatesChanged => 0,
+            Command::BatteryStateChanged(_) => 1,
+            Command::MassStorageStateListChanged(_) => 2,
+            Command::MassStorageInfoStateListChanged(_) => 3,
+            Command::CurrentDateChanged(_) => 4,
+            Command::CurrentTimeChanged(_) => 5,
+            Command::MassStorageInfoRemainingListChanged(_) => 6,
+            Command::WifiSignalChanged(_) => 7,
+            Command::SensorsStatesListChanged(_) => 8,
+            Command::ProductModel(_) => 9,
+            Command::CountryListKnown(_) => 10,
+            Command::DeprecatedMassStorageContentChanged(_) => 11,
+            Command::MassStorageContent(_) => 12,
+            Command::MassStorageContentForCurrentRun(_) => 13,
+            Command::VideoRecordingTimestamp(_) => 14,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => Self::AllStatesChanged,
+            1 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::BatteryStateChanged(args)
+            }
+            2 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::MassStorageStateListChanged(args)
+            }
+            3 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::MassStorageInfoStateListChanged(args)
+            }
+            4 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::CurrentDateChanged(args)
+            }
+            5 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::CurrentTimeChanged(args)
+            }
+            6 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::MassStorageInfoRemainingListChanged(args)
+            }
+            7 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::WifiSignalChanged(args)
+            }
+            8 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::SensorsStatesListChanged(args)
+            }
+            9 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::ProductModel(args)
+            }
+            10 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::CountryListKnown(args)
+            }
+            11 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::DeprecatedMassStorageContentChanged(args)
+            }
+            12 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::MassStorageContent(args)
+            }
+            13 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::MassStorageContentForCurrentRun(args)
+            }
+            14 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::VideoRecordingTimestamp(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::BatteryStateChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::MassStorageStateListChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::MassStorageInfoStateListChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::CurrentDateChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::CurrentTimeChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::MassStorageInfoRemainingListChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::WifiSignalChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::SensorsStatesListChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::ProductModel(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::CountryListKnown(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::DeprecatedMassStorageContentChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::MassStorageContent(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::MassStorageContentForCurrentRun(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::VideoRecordingTimestamp(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+            // no arguments
+            _ => {}
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Battery state
+pub struct BatteryStateChanged {
+    /// Battery percentage
+    pub percent: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for BatteryStateChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            percent: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for BatteryStateChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.percent, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Mass storage state list
+pub struct MassStorageStateListChanged {
+    /// Mass storage id (unique)
+    pub mass_storage_id: u8,
+    /// Mass storage name
+    pub name: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for MassStorageStateListChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            mass_storage_id: src.gread_with(&mut offset, ctx)?,
+            name: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for MassStorageStateListChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.mass_storage_id, &mut offset, ctx)?;
+        write_string(this, &self.name, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Mass storage info state list
+pub struct MassStorageInfoStateListChanged {
+    /// Mass storage state id (unique)
+    pub mass_storage_id: u8,
+    /// Mass storage size in MBytes
+    pub size: u32,
+    /// Mass storage used size in MBytes
+    pub used_size: u32,
+    /// Mass storage plugged (1 if mass storage is plugged, otherwise 0)
+    pub plugged: u8,
+    /// Mass storage full information state (1 if mass storage full, 0 otherwise).
+    pub full: u8,
+    /// Mass storage internal type state (1 if mass storage is internal, 0 otherwise)
+    pub internal: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for MassStorageInfoStateListChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            mass_storage_id: src.gread_with(&mut offset, ctx)?,
+            size: src.gread_with(&mut offset, ctx)?,
+            used_size: src.gread_with(&mut offset, ctx)?,
+            plugged: src.gread_with(&mut offset, ctx)?,
+            full: src.gread_with(&mut offset, ctx)?,
+            internal: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for MassStorageInfoStateListChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.mass_storage_id, &mut offset, ctx)?;
+        this.gwrite_with(self.size, &mut offset, ctx)?;
+        this.gwrite_with(self.used_size, &mut offset, ctx)?;
+        this.gwrite_with(self.plugged, &mut offset, ctx)?;
+        this.gwrite_with(self.full, &mut offset, ctx)?;
+        this.gwrite_with(self.internal, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Date changed
+pub struct CurrentDateChanged {
+    /// Date with ISO-8601 format
+    pub date: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for CurrentDateChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            date: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for CurrentDateChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.date, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Time changed
+pub struct CurrentTimeChanged {
+    /// Time with ISO-8601 format
+    pub time: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for CurrentTimeChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            time: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for CurrentTimeChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.time, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Mass storage remaining data list
+pub struct MassStorageInfoRemainingListChanged {
+    /// Mass storage free space in MBytes
+    pub free_space: u32,
+    /// Mass storage record time reamining in minute
+    pub rec_time: u16,
+    /// Mass storage photo remaining
+    pub photo_remaining: u32,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for MassStorageInfoRemainingListChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            free_space: src.gread_with(&mut offset, ctx)?,
+            rec_time: src.gread_with(&mut offset, ctx)?,
+            photo_remaining: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for MassStorageInfoRemainingListChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.free_space, &mut offset, ctx)?;
+        this.gwrite_with(self.rec_time, &mut offset, ctx)?;
+        this.gwrite_with(self.photo_remaining, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Rssi changed
+pub struct WifiSignalChanged {
+    /// RSSI of the signal between controller and the product (in dbm)
+    pub rssi: i16,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for WifiSignalChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            rssi: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for WifiSignalChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.rssi, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Sensors state list
+pub struct SensorsStatesListChanged {
+    /// Sensor name
+    pub sensor_name: SensorsStatesListChangedSensorName,
+    /// Sensor state (1 if the sensor is OK, 0 if the sensor is NOT OK)
+    pub sensor_state: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for SensorsStatesListChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            sensor_name: src.gread_with(&mut offset, ctx)?,
+            sensor_state: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for SensorsStatesListChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.sensor_name, &mut offset, ctx)?;
+        this.gwrite_with(self.sensor_state, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Sensor name
+pub enum SensorsStatesListChangedSensorName {
+    /// `0`: Inertial Measurement Unit sensor
+    Imu,
+    /// `1`: Barometer sensor
+    Barometer,
+    /// `2`: Ultrasonic sensor
+    Ultrasound,
+    /// `3`: GPS sensor
+    Gps,
+    /// `4`: Magnetometer sensor
+    Magnetometer,
+    /// `5`: Vertical Camera sensor
+    VerticalCamera,
+}
+
+impl From<SensorsStatesListChangedSensorName> for u32 {
+    fn from(value: SensorsStatesListChangedSensorName) -> Self {
+        match value {
+            SensorsStatesListChangedSensorName::Imu => 0,
+            SensorsStatesListChangedSensorName::Barometer => 1,
+            SensorsStatesListChangedSensorName::Ultrasound => 2,
+            SensorsStatesListChangedSensorName::Gps => 3,
+            SensorsStatesListChangedSensorName::Magnetometer => 4,
+            SensorsStatesListChangedSensorName::VerticalCamera => 5,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for SensorsStatesListChangedSensorName {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Imu,
+            1 => Self::Barometer,
+            2 => Self::Ultrasound,
+            3 => Self::Gps,
+            4 => Self::Magnetometer,
+            5 => Self::VerticalCamera,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "SensorsStatesListChangedSensorName".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for SensorsStatesListChangedSensorName {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Product sub-model
+pub struct ProductModel {
+    /// The Model of the product.
+    pub model: ProductModelModel,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for ProductModel {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            model: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for ProductModel {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.model, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The Model of the product.
+pub enum ProductModelModel {
+    /// `0`: Travis (RS taxi) model.
+    RsTravis,
+    /// `1`: Mars (RS space) model
+    RsMars,
+    /// `2`: SWAT (RS SWAT) model
+    RsSwat,
+    /// `3`: Mc Lane (RS police) model
+    RsMclane,
+    /// `4`: Blaze (RS fire) model
+    RsBlaze,
+    /// `5`: Orak (RS carbon hydrofoil) model
+    RsOrak,
+    /// `6`: New Z (RS wooden hydrofoil) model
+    RsNewz,
+    /// `7`: Marshall (JS fire) model
+    JsMarshall,
+    /// `8`: Diesel (JS SWAT) model
+    JsDiesel,
+    /// `9`: Buzz (JS space) model
+    JsBuzz,
+    /// `10`: Max (JS F1) model
+    JsMax,
+    /// `11`: Jett (JS flames) model
+    JsJett,
+    /// `12`: Tuk-Tuk (JS taxi) model
+    JsTuktuk,
+    /// `13`: Swing black model
+    SwBlack,
+    /// `14`: Swing white model
+    SwWhite,
+}
+
+impl From<ProductModelModel> for u32 {
+    fn from(value: ProductModelModel) -> Self {
+        match value {
+            ProductModelModel::RsTravis => 0,
+            ProductModelModel::RsMars => 1,
+            ProductModelModel::RsSwat => 2,
+            ProductModelModel::RsMclane => 3,
+            ProductModelModel::RsBlaze => 4,
+            ProductModelModel::RsOrak => 5,
+            ProductModelModel::RsNewz => 6,
+            ProductModelModel::JsMarshall => 7,
+            ProductModelModel::JsDiesel => 8,
+            ProductModelModel::JsBuzz => 9,
+            ProductModelModel::JsMax => 10,
+            ProductModelModel::JsJett => 11,
+            ProductModelModel::JsTuktuk => 12,
+            ProductModelModel::SwBlack => 13,
+            ProductModelModel::SwWhite => 14,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for ProductModelModel {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::RsTravis,
+            1 => Self::RsMars,
+            2 => Self::RsSwat,
+            3 => Self::RsMclane,
+            4 => Self::RsBlaze,
+            5 => Self::RsOrak,
+            6 => Self::RsNewz,
+            7 => Self::JsMarshall,
+            8 => Self::JsDiesel,
+            9 => Self::JsBuzz,
+            10 => Self::JsMax,
+            11 => Self::JsJett,
+            12 => Self::JsTuktuk,
+            13 => Self::SwBlack,
+            14 => Self::SwWhite,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "ProductModelModel".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for ProductModelModel {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Country list
+pub struct CountryListKnown {
+    /// List entry attribute Bitfield. 0x01: First: indicate it's the first element of the list. 0x02: Last: indicate it's the last element of the list. 0x04: Empty: indicate the list is empty (implies First/Last). All other arguments should be ignored.
+    pub list_flags: u8,
+    /// Following of country code with ISO 3166 format, separated by ";". Be careful of the command size allowed by the network used. If necessary, split the list in several commands.
+    pub country_codes: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for CountryListKnown {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            list_flags: src.gread_with(&mut offset, ctx)?,
+            country_codes: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for CountryListKnown {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.list_flags, &mut offset, ctx)?;
+        write_string(this, &self.country_codes, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Mass storage content changed
+pub struct DeprecatedMassStorageContentChanged {
+    /// Mass storage id (unique)
+    pub mass_storage_id: u8,
+    /// Number of photos (does not include raw photos)
+    pub nb_photos: u16,
+    /// Number of videos
+    pub nb_videos: u16,
+    /// Number of puds
+    pub nb_puds: u16,
+    /// Number of crash logs
+    pub nb_crash_logs: u16,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for DeprecatedMassStorageContentChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            mass_storage_id: src.gread_with(&mut offset, ctx)?,
+            nb_photos: src.gread_with(&mut offset, ctx)?,
+            nb_videos: src.gread_with(&mut offset, ctx)?,
+            nb_puds: src.gread_with(&mut offset, ctx)?,
+            nb_crash_logs: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for DeprecatedMassStorageContentChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.mass_storage_id, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_photos, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_videos, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_puds, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_crash_logs, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Mass storage content
+pub struct MassStorageContent {
+    /// Mass storage id (unique)
+    pub mass_storage_id: u8,
+    /// Number of photos (does not include raw photos)
+    pub nb_photos: u16,
+    /// Number of videos
+    pub nb_videos: u16,
+    /// Number of puds
+    pub nb_puds: u16,
+    /// Number of crash logs
+    pub nb_crash_logs: u16,
+    /// Number of raw photos
+    pub nb_raw_photos: u16,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for MassStorageContent {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            mass_storage_id: src.gread_with(&mut offset, ctx)?,
+            nb_photos: src.gread_with(&mut offset, ctx)?,
+            nb_videos: src.gread_with(&mut offset, ctx)?,
+            nb_puds: src.gread_with(&mut offset, ctx)?,
+            nb_crash_logs: src.gread_with(&mut offset, ctx)?,
+            nb_raw_photos: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for MassStorageContent {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.mass_storage_id, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_photos, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_videos, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_puds, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_crash_logs, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_raw_photos, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Mass storage content for current run
+pub struct MassStorageContentForCurrentRun {
+    /// Mass storage id (unique)
+    pub mass_storage_id: u8,
+    /// Number of photos (does not include raw photos)
+    pub nb_photos: u16,
+    /// Number of videos
+    pub nb_videos: u16,
+    /// Number of raw photos
+    pub nb_raw_photos: u16,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for MassStorageContentForCurrentRun {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            mass_storage_id: src.gread_with(&mut offset, ctx)?,
+            nb_photos: src.gread_with(&mut offset, ctx)?,
+            nb_videos: src.gread_with(&mut offset, ctx)?,
+            nb_raw_photos: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for MassStorageContentForCurrentRun {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.mass_storage_id, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_photos, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_videos, &mut offset, ctx)?;
+        this.gwrite_with(self.nb_raw_photos, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Video recording timestamp
+pub struct VideoRecordingTimestamp {
+    /// Timestamp in milliseconds since 00:00:00 UTC on 1 January 1970.
+    pub start_timestamp: u64,
+    /// Timestamp in milliseconds since 00:00:00 UTC on 1 January 1970. 0 mean that video is still recording.
+    pub stop_timestamp: u64,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for VideoRecordingTimestamp {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            start_timestamp: src.gread_with(&mut offset, ctx)?,
+            stop_timestamp: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for VideoRecordingTimestamp {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.start_timestamp, &mut offset, ctx)?;
+        this.gwrite_with(self.stop_timestamp, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
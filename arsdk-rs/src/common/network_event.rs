@@ -0,0 +1,151 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `1`: Network Event from product
+//!
+//! Generated from the `NetworkEvent` Class of `arsdk-gen/xml/common.xml` (the `common` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_unknown, write_bytes},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// u16
+pub enum Command {
+    /// `0`: Drone will disconnect
+    Disconnection(Disconnection),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::Disconnection(_) => 0,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::Disconnection(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::Disconnection(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Drone will disconnect
+pub struct Disconnection {
+    /// Cause of the disconnection of the product
+    pub cause: DisconnectionCause,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Disconnection {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            cause: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Disconnection {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.cause, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Cause of the disconnection of the product
+pub enum DisconnectionCause {
+    /// `0`: The button off has been pressed
+    OffButton,
+    /// `1`: Unknown generic cause
+    Unknown,
+}
+
+impl From<DisconnectionCause> for u32 {
+    fn from(value: DisconnectionCause) -> Self {
+        match value {
+            DisconnectionCause::OffButton => 0,
+            DisconnectionCause::Unknown => 1,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for DisconnectionCause {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::OffButton,
+            1 => Self::Unknown,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "DisconnectionCause".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for DisconnectionCause {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
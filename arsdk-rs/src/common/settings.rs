@@ -0,0 +1,202 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `2`: Settings commands
+//!
+//! Generated from the `Settings` Class of `arsdk-gen/xml/common.xml` (the `common` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_string, read_unknown, write_bytes, write_string},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// u16
+pub enum Command {
+    /// `0`: Ask for all settings
+    AllSettings,
+    /// `1`: Reset all settings
+    Reset,
+    /// `2`: Set product name
+    ProductName(ProductName),
+    /// `3`: Set the country
+    Country(Country),
+    /// `4`: Enable auto-country
+    AutoCountry(AutoCountry),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::AllSettings => 0,
+            Command::Reset => 1,
+            Command::ProductName(_) => 2,
+            Command::Country(_) => 3,
+            Command::AutoCountry(_) => 4,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => Self::AllSettings,
+            1 => Self::Reset,
+            2 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::ProductName(args)
+            }
+            3 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::Country(args)
+            }
+            4 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AutoCountry(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::ProductName(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Country(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AutoCountry(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+            // no arguments
+            _ => {}
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Set product name
+pub struct ProductName {
+    /// Product name
+    pub name: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for ProductName {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            name: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for ProductName {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.name, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Set the country
+pub struct Country {
+    /// Country code with ISO 3166 format
+    pub code: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Country {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            code: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Country {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.code, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Enable auto-country
+pub struct AutoCountry {
+    /// Boolean : 0 : Manual / 1 : Auto
+    pub automatic: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AutoCountry {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            automatic: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AutoCountry {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.automatic, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
@@ -0,0 +1,382 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `3`: Settings state from product
+//!
+//! Generated from the `SettingsState` Class of `arsdk-gen/xml/common.xml` (the `common` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_string, read_unknown, write_bytes, write_string},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// u16
+pub enum Command {
+    /// `0`: All settings have been sent
+    AllSettingsChanged,
+    /// `1`: All settings have been reset
+    ResetChanged,
+    /// `2`: Product name changed
+    ProductNameChanged(ProductNameChanged),
+    /// `3`: Product version
+    ProductVersionChanged(ProductVersionChanged),
+    /// `4`: Product serial (1st part)
+    ProductSerialHighChanged(ProductSerialHighChanged),
+    /// `5`: Product serial (2nd part)
+    ProductSerialLowChanged(ProductSerialLowChanged),
+    /// `6`: Country changed
+    CountryChanged(CountryChanged),
+    /// `7`: Auto-country changed
+    AutoCountryChanged(AutoCountryChanged),
+    /// `8`: Board id
+    BoardIdChanged(BoardIdChanged),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::AllSettingsChanged => 0,
+            Command::ResetChanged => 1,
+            Command::ProductNameChanged(_) => 2,
+            Command::ProductVersionChanged(_) => 3,
+            Command::ProductSerialHighChanged(_) => 4,
+            Command::ProductSerialLowChanged(_) => 5,
+            Command::CountryChanged(_) => 6,
+            Command::AutoCountryChanged(_) => 7,
+            Command::BoardIdChanged(_) => 8,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => Self::AllSettingsChanged,
+            1 => Self::ResetChanged,
+            2 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::ProductNameChanged(args)
+            }
+            3 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::ProductVersionChanged(args)
+            }
+            4 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::ProductSerialHighChanged(args)
+            }
+            5 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::ProductSerialLowChanged(args)
+            }
+            6 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::CountryChanged(args)
+            }
+            7 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AutoCountryChanged(args)
+            }
+            8 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::BoardIdChanged(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::ProductNameChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::ProductVersionChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::ProductSerialHighChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::ProductSerialLowChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::CountryChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AutoCountryChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::BoardIdChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+            // no arguments
+            _ => {}
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Product name changed
+pub struct ProductNameChanged {
+    /// Product name
+    pub name: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for ProductNameChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            name: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for ProductNameChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.name, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Product version
+pub struct ProductVersionChanged {
+    /// Product software version
+    pub software: String,
+    /// Product hardware version
+    pub hardware: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for ProductVersionChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            software: read_string(src, &mut offset)?,
+            hardware: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for ProductVersionChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.software, &mut offset)?;
+        write_string(this, &self.hardware, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Product serial (1st part)
+pub struct ProductSerialHighChanged {
+    /// Serial high number (hexadecimal value)
+    pub high: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for ProductSerialHighChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            high: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for ProductSerialHighChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.high, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Product serial (2nd part)
+pub struct ProductSerialLowChanged {
+    /// Serial low number (hexadecimal value)
+    pub low: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for ProductSerialLowChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            low: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for ProductSerialLowChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.low, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Country changed
+pub struct CountryChanged {
+    /// Country code with ISO 3166 format, empty string means unknown country.
+    pub code: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for CountryChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            code: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for CountryChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.code, &mut offset)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Auto-country changed
+pub struct AutoCountryChanged {
+    /// Boolean : 0 : Manual / 1 : Auto
+    pub automatic: u8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AutoCountryChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            automatic: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AutoCountryChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.automatic, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Board id
+pub struct BoardIdChanged {
+    /// Id of the board
+    pub id: String,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for BoardIdChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            id: read_string(src, &mut offset)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for BoardIdChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], _ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        write_string(this, &self.id, &mut offset)?;
+
+        Ok(offset)
+    }
+}
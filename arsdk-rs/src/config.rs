@@ -0,0 +1,223 @@
+use crate::{
+    frame::BufferID, handshake::ArStream2, Product, QueueParams, ReconnectPolicy,
+    DEFAULT_ACK_RETRIES, DEFAULT_ACK_TIMEOUT, DEFAULT_CONNECTION_TIMEOUT, DEFAULT_DEGRADED_TIMEOUT,
+    INIT_PORT, LISTEN_PORT,
+};
+use std::{borrow::Cow, net::IpAddr, time::Duration};
+
+pub const DEFAULT_CONTROLLER_NAME: &str = "arsdk-rs";
+pub const DEFAULT_CONTROLLER_TYPE: &str = "computer";
+/// The `arstream2_client_*` ports sent in the handshake
+pub const DEFAULT_ARSTREAM2: ArStream2 = ArStream2 {
+    stream_port: 44445,
+    control_port: 44446,
+};
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub drone_addr: IpAddr,
+    /// Wheather or not to set after connecting (by sending a frame) the current DateTime to the Drone:
+    ///
+    /// ```rust
+    /// use chrono::{DateTime, Utc};
+    /// let now: DateTime<Utc> = Utc::now();
+    /// ```
+    pub send_datetime: bool,
+    /// After how long a `Type::DataWithAck` frame is retransmitted if it's not acknowledged
+    pub ack_timeout: Duration,
+    /// How many times a `Type::DataWithAck` frame is retransmitted
+    /// before giving up with `DeliveryStatus::Timeout`
+    pub ack_retries: usize,
+    /// After how long without receiving any frame the connection is `ConnectionState::Degraded`
+    pub degraded_timeout: Duration,
+    /// After how long without receiving any frame the connection is `ConnectionState::Lost`
+    pub connection_timeout: Duration,
+    /// Whether or not to send the Common `NetworkEvent::Disconnection` frame
+    /// when the Drone is disconnected or dropped
+    pub send_disconnection: bool,
+    /// Whether or not to perform the handshake again once the connection is `ConnectionState::Lost`
+    pub reconnect: Option<ReconnectPolicy>,
+    /// The TCP port of the Drone for the handshake
+    pub init_port: u16,
+    /// The local UDP port the Drone sends the frames to, `0` picks an ephemeral port
+    ///
+    /// If the default `LISTEN_PORT` is already in use, e.g. when connecting to multiple Drones,
+    /// an ephemeral port is used instead. The actual port is sent in the handshake.
+    pub d2c_port: u16,
+    pub controller_name: Cow<'static, str>,
+    pub controller_type: Cow<'static, str>,
+    /// The client video streaming ports, `Some(None)` for Drones without `arstream2`
+    ///
+    /// `None` leaves them unset: the ports of the `product` are sent, see [`Config::arstream2`].
+    pub arstream2: Option<Option<ArStream2>>,
+    /// The profile of the handshake fields left unset, see [`ConfigBuilder::product`]
+    pub product: Option<Product>,
+    /// The local address to bind to, e.g. `0.0.0.0`
+    ///
+    /// When `None`, the address of the interface which is in the same network as the Drone is used.
+    pub local_addr: Option<IpAddr>,
+    /// The outgoing queue parameters of specific buffers, replacing `QueueParams::default_for`
+    pub send_queues: Vec<(BufferID, QueueParams)>,
+}
+
+impl Config {
+    pub fn builder(drone_addr: impl Into<IpAddr>) -> ConfigBuilder {
+        ConfigBuilder {
+            config: Self::from(drone_addr),
+        }
+    }
+
+    /// The `product` profile at its default address, see [`Product::default_addr`]
+    pub fn for_product(product: Product) -> Self {
+        Self::builder(product.default_addr())
+            .product(product)
+            .build()
+    }
+
+    /// Applies the `product` profile, unless one was already chosen.
+    ///
+    /// The fields which were set explicitly are kept.
+    pub fn or_product(mut self, product: Product) -> Self {
+        self.product = self.product.or(Some(product));
+        self
+    }
+
+    /// The `arstream2` ports sent in the handshake: the explicit ones,
+    /// otherwise the ones of the `product` or [`DEFAULT_ARSTREAM2`] without a profile
+    pub fn arstream2(&self) -> Option<ArStream2> {
+        self.arstream2.unwrap_or_else(|| match self.product {
+            Some(product) => product.arstream2(),
+            None => Some(DEFAULT_ARSTREAM2),
+        })
+    }
+}
+
+impl<I> From<I> for Config
+where
+    I: Into<IpAddr>,
+{
+    fn from(ip: I) -> Self {
+        Self {
+            drone_addr: ip.into(),
+            send_datetime: false,
+            ack_timeout: DEFAULT_ACK_TIMEOUT,
+            ack_retries: DEFAULT_ACK_RETRIES,
+            degraded_timeout: DEFAULT_DEGRADED_TIMEOUT,
+            connection_timeout: DEFAULT_CONNECTION_TIMEOUT,
+            send_disconnection: true,
+            reconnect: None,
+            init_port: INIT_PORT,
+            d2c_port: LISTEN_PORT,
+            controller_name: Cow::Borrowed(DEFAULT_CONTROLLER_NAME),
+            controller_type: Cow::Borrowed(DEFAULT_CONTROLLER_TYPE),
+            arstream2: None,
+            product: None,
+            local_addr: None,
+            send_queues: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Builds a [`Config`] starting from the defaults of `Config::from(drone_addr)`
+///
+/// ```
+/// use arsdk_rs::Config;
+/// use std::net::Ipv4Addr;
+///
+/// let config = Config::builder(Ipv4Addr::new(192, 168, 42, 1))
+///     .local_addr(Ipv4Addr::UNSPECIFIED)
+///     .d2c_port(0)
+///     .controller_name("my-controller")
+///     .build();
+///
+/// assert_eq!(0, config.d2c_port);
+/// ```
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    pub fn send_datetime(mut self, send_datetime: bool) -> Self {
+        self.config.send_datetime = send_datetime;
+        self
+    }
+
+    pub fn ack_timeout(mut self, ack_timeout: Duration) -> Self {
+        self.config.ack_timeout = ack_timeout;
+        self
+    }
+
+    pub fn ack_retries(mut self, ack_retries: usize) -> Self {
+        self.config.ack_retries = ack_retries;
+        self
+    }
+
+    pub fn degraded_timeout(mut self, degraded_timeout: Duration) -> Self {
+        self.config.degraded_timeout = degraded_timeout;
+        self
+    }
+
+    pub fn connection_timeout(mut self, connection_timeout: Duration) -> Self {
+        self.config.connection_timeout = connection_timeout;
+        self
+    }
+
+    pub fn send_disconnection(mut self, send_disconnection: bool) -> Self {
+        self.config.send_disconnection = send_disconnection;
+        self
+    }
+
+    pub fn reconnect(mut self, reconnect: ReconnectPolicy) -> Self {
+        self.config.reconnect = Some(reconnect);
+        self
+    }
+
+    pub fn init_port(mut self, init_port: u16) -> Self {
+        self.config.init_port = init_port;
+        self
+    }
+
+    /// `0` picks an ephemeral port
+    pub fn d2c_port(mut self, d2c_port: u16) -> Self {
+        self.config.d2c_port = d2c_port;
+        self
+    }
+
+    pub fn controller_name(mut self, controller_name: impl Into<Cow<'static, str>>) -> Self {
+        self.config.controller_name = controller_name.into();
+        self
+    }
+
+    pub fn controller_type(mut self, controller_type: impl Into<Cow<'static, str>>) -> Self {
+        self.config.controller_type = controller_type.into();
+        self
+    }
+
+    /// `None` for Drones without `arstream2`, it takes precedence over the `product` profile
+    pub fn arstream2(mut self, arstream2: Option<ArStream2>) -> Self {
+        self.config.arstream2 = Some(arstream2);
+        self
+    }
+
+    /// Uses the handshake fields of the `product` for the ones left unset,
+    /// e.g. the Anafi `arstream2` ports
+    pub fn product(mut self, product: Product) -> Self {
+        self.config.product = Some(product);
+        self
+    }
+
+    pub fn local_addr(mut self, local_addr: impl Into<IpAddr>) -> Self {
+        self.config.local_addr = Some(local_addr.into());
+        self
+    }
+
+    pub fn send_queue(mut self, buffer_id: BufferID, params: QueueParams) -> Self {
+        self.config.send_queues.push((buffer_id, params));
+        self
+    }
+
+    pub fn build(self) -> Config {
+        self.config
+    }
+}
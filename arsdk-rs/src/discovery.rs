@@ -0,0 +1,361 @@
+//! Discovery of the Drones on the network with mDNS / DNS-SD
+//!
+//! Parrot products advertise an `_arsdk-XXXX._udp.local` service, where `XXXX` is their
+//! product id in hex (see [`Product::from_product_id`]). The SRV record carries the init port
+//! of the handshake and the TXT record a JSON object with the `device_id`.
+//!
+//! ```no_run
+//! use arsdk_rs::{discovery::Discovery, prelude::*};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let devices = Discovery::new().browse()?;
+//!
+//! if let Some(device) = devices.into_iter().next() {
+//!     println!("Connecting to {}", device.name);
+//!
+//!     let drone = Drone::connect(device.into())?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::{Config, Product};
+use dns_parser::{Builder, Packet, QueryClass, QueryType, RData, ResourceRecord};
+use log::{debug, info};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    time::{Duration, Instant},
+};
+
+/// The mDNS multicast group
+pub const MDNS_IP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+pub const MDNS_PORT: u16 = 5353;
+/// How long we wait for the answers
+pub const DEFAULT_BROWSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The DNS-SD service type of the `product_id`, e.g. `_arsdk-090c._udp.local` for the Bebop 2
+pub fn service_type(product_id: u16) -> String {
+    format!("_arsdk-{:04x}._udp.local", product_id)
+}
+
+/// Returns the product id of the `_arsdk-XXXX._udp.local` service type
+fn product_id(service_type: &str) -> Option<u16> {
+    let hex = service_type
+        .strip_prefix("_arsdk-")?
+        .strip_suffix("._udp.local")?;
+
+    u16::from_str_radix(hex, 16).ok()
+}
+
+/// Splits the `<name>._arsdk-XXXX._udp.local` instance into its name and product id.
+///
+/// The name can contain dots (e.g. renamed Drones), so the service type is stripped from the end.
+fn split_instance(instance: &str) -> Option<(&str, u16)> {
+    let separator = instance.rfind("._arsdk-")?;
+    let product_id = product_id(&instance[separator + 1..])?;
+
+    Some((&instance[..separator], product_id))
+}
+
+/// A Drone which answered the discovery
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    /// The service instance name, e.g. `Bebop2-123456`
+    pub name: String,
+    pub product_id: u16,
+    /// `None` for the product ids we don't know
+    pub product: Option<Product>,
+    pub addr: IpAddr,
+    /// The TCP port of the handshake
+    pub init_port: u16,
+    pub device_id: Option<String>,
+}
+
+impl From<Device> for Config {
+    /// Uses the [`Product`] profile of the Device, if its product id is known
+    fn from(device: Device) -> Self {
+        let builder = Config::builder(device.addr).init_port(device.init_port);
+
+        match device.product {
+            Some(product) => builder.product(product).build(),
+            None => builder.build(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TxtData {
+    device_id: Option<String>,
+}
+
+/// Browses for the `_arsdk-XXXX._udp.local` services
+#[derive(Debug, Clone)]
+pub struct Discovery {
+    responder: SocketAddr,
+    product_ids: Vec<u16>,
+    timeout: Duration,
+}
+
+impl Default for Discovery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Discovery {
+    /// Browses for all the known products, see [`Product::product_ids`]
+    pub fn new() -> Self {
+        Self {
+            responder: SocketAddr::new(MDNS_IP.into(), MDNS_PORT),
+            product_ids: Product::product_ids().collect(),
+            timeout: DEFAULT_BROWSE_TIMEOUT,
+        }
+    }
+
+    /// Where the queries are sent, by default the [`MDNS_IP`] multicast group
+    ///
+    /// E.g. a local mDNS responder for testing.
+    pub fn responder(mut self, responder: SocketAddr) -> Self {
+        self.responder = responder;
+        self
+    }
+
+    /// Browses only for the products with these ids
+    pub fn product_ids(mut self, product_ids: impl IntoIterator<Item = u16>) -> Self {
+        self.product_ids = product_ids.into_iter().collect();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sends the PTR queries & collects the answers until the timeout
+    ///
+    /// The queries ask for unicast answers, so we don't need to bind the mDNS port.
+    pub fn browse(&self) -> io::Result<Vec<Device>> {
+        let local_addr = match self.responder {
+            SocketAddr::V4(_) => SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0),
+            SocketAddr::V6(_) => SocketAddr::new(std::net::Ipv6Addr::UNSPECIFIED.into(), 0),
+        };
+        let socket = UdpSocket::bind(local_addr)?;
+
+        socket.send_to(&self.query()?, self.responder)?;
+        info!(
+            "{}: Browsing for {} products",
+            self.responder,
+            self.product_ids.len()
+        );
+
+        let deadline = Instant::now() + self.timeout;
+        let mut answers = Answers::default();
+        let mut buf = [0_u8; 9000];
+
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            socket.set_read_timeout(Some(deadline - now))?;
+
+            match socket.recv_from(&mut buf) {
+                Ok((read, origin)) => match Packet::parse(&buf[..read]) {
+                    Ok(packet) => answers.add(&packet, origin.ip()),
+                    Err(err) => debug!("{}: Invalid DNS packet - {}", origin, err),
+                },
+                Err(err)
+                    if err.kind() == io::ErrorKind::WouldBlock
+                        || err.kind() == io::ErrorKind::TimedOut =>
+                {
+                    break
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(answers.devices(&self.product_ids))
+    }
+
+    fn query(&self) -> io::Result<Vec<u8>> {
+        // the id is `0` for mDNS queries
+        let mut builder = Builder::new_query(0, false);
+
+        for product_id in self.product_ids.iter() {
+            builder.add_question(
+                &service_type(*product_id),
+                true,
+                QueryType::PTR,
+                QueryClass::IN,
+            );
+        }
+
+        builder
+            .build()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Too many products"))
+    }
+}
+
+#[derive(Debug)]
+struct Service {
+    product_id: u16,
+    /// Where the answer came from, when there's no A record
+    origin: IpAddr,
+    target: Option<String>,
+    port: Option<u16>,
+    device_id: Option<String>,
+}
+
+/// The records of all the answers, which can be spread across multiple packets
+#[derive(Debug, Default)]
+struct Answers {
+    /// By instance name, e.g. `Bebop2-123456._arsdk-090c._udp.local`
+    services: HashMap<String, Service>,
+    hosts: HashMap<String, IpAddr>,
+}
+
+impl Answers {
+    fn add(&mut self, packet: &Packet, origin: IpAddr) {
+        let records = packet.answers.iter().chain(packet.additional.iter());
+
+        for record in records {
+            self.add_record(record, origin);
+        }
+    }
+
+    fn service(&mut self, instance: &str, origin: IpAddr) -> Option<&mut Service> {
+        let (_, product_id) = split_instance(instance)?;
+
+        Some(
+            self.services
+                .entry(instance.to_string())
+                .or_insert_with(|| Service {
+                    product_id,
+                    origin,
+                    target: None,
+                    port: None,
+                    device_id: None,
+                }),
+        )
+    }
+
+    fn add_record(&mut self, record: &ResourceRecord, origin: IpAddr) {
+        let name = record.name.to_string();
+
+        match &record.data {
+            RData::PTR(ptr) => {
+                self.service(&ptr.0.to_string(), origin);
+            }
+            RData::SRV(srv) => {
+                if let Some(service) = self.service(&name, origin) {
+                    service.target = Some(srv.target.to_string());
+                    service.port = Some(srv.port);
+                }
+            }
+            RData::TXT(txt) => {
+                let device_id = txt
+                    .iter()
+                    .filter_map(|data| serde_json::from_slice::<TxtData>(data).ok())
+                    .find_map(|data| data.device_id);
+
+                if let Some(service) = self.service(&name, origin) {
+                    service.device_id = device_id.or_else(|| service.device_id.take());
+                }
+            }
+            RData::A(a) => {
+                self.hosts.insert(name, a.0.into());
+            }
+            RData::AAAA(aaaa) => {
+                self.hosts.entry(name).or_insert_with(|| aaaa.0.into());
+            }
+            _ => {}
+        }
+    }
+
+    /// The services with a SRV record of the browsed `product_ids`
+    fn devices(self, product_ids: &[u16]) -> Vec<Device> {
+        let hosts = self.hosts;
+
+        let mut devices: Vec<Device> = self
+            .services
+            .into_iter()
+            .filter(|(_, service)| product_ids.contains(&service.product_id))
+            .filter_map(|(instance, service)| {
+                let (name, _) = split_instance(&instance)?;
+                let addr = service
+                    .target
+                    .as_ref()
+                    .and_then(|target| hosts.get(target))
+                    .copied()
+                    .unwrap_or(service.origin);
+
+                Some(Device {
+                    name: name.to_string(),
+                    product_id: service.product_id,
+                    product: Product::from_product_id(service.product_id),
+                    addr,
+                    init_port: service.port?,
+                    device_id: service.device_id,
+                })
+            })
+            .collect();
+
+        devices.sort_by(|left, right| left.name.cmp(&right.name));
+
+        devices
+    }
+}
+
+#[cfg(test)]
+mod discovery_tests {
+    use super::*;
+
+    #[test]
+    fn test_service_type() {
+        assert_eq!("_arsdk-090c._udp.local", service_type(0x090c));
+        assert_eq!(Some(0x090c), product_id("_arsdk-090c._udp.local"));
+        assert_eq!(None, product_id("_http._tcp.local"));
+    }
+
+    #[test]
+    fn test_dotted_instance_name() {
+        assert_eq!(
+            Some(("Bebop2-123456", 0x090c)),
+            split_instance("Bebop2-123456._arsdk-090c._udp.local")
+        );
+        assert_eq!(None, split_instance("Printer._http._tcp.local"));
+
+        let origin = Ipv4Addr::new(192, 168, 42, 1).into();
+        let mut answers = Answers::default();
+        let service = answers
+            .service("Mr. Bebop 2.0._arsdk-090c._udp.local", origin)
+            .expect("Should be an arsdk service");
+        service.port = Some(44444);
+
+        let devices = answers.devices(&[0x090c]);
+        assert_eq!(1, devices.len());
+        assert_eq!("Mr. Bebop 2.0", devices[0].name);
+        assert_eq!(0x090c, devices[0].product_id);
+        assert_eq!(origin, devices[0].addr);
+    }
+
+    #[test]
+    fn test_device_into_config() {
+        let device = Device {
+            name: "Anafi-000001".to_string(),
+            product_id: 0x0914,
+            product: Some(Product::Anafi),
+            addr: Ipv4Addr::new(192, 168, 42, 1).into(),
+            init_port: 44444,
+            device_id: Some("PI040416AA0000000".to_string()),
+        };
+
+        let config = Config::from(device);
+
+        assert_eq!(Some(Product::Anafi), config.product);
+        assert_eq!(Some(crate::ANAFI_ARSTREAM2), config.arstream2());
+        assert_eq!(44444, config.init_port);
+    }
+}
@@ -0,0 +1,335 @@
+//! Events published to the subscribers of a [`Drone`](crate::Drone)
+//!
+//! ```no_run
+//! use arsdk_rs::{event::Filter, prelude::*};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let drone = Drone::connect(PARROT_SPHINX_CONFIG)?;
+//!
+//! // ArDrone3 (1) - PilotingState (4)
+//! let piloting_state = drone.subscribe_with(Filter::feature(1).class(4));
+//!
+//! for event in piloting_state {
+//!     println!("{:?}", event);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use crate::{
+    frame::{BufferID, FrameRef, FrameType, Type},
+    ConnectionState,
+};
+use log::{error, warn};
+use scroll::{Pread, LE};
+use std::sync::{
+    mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
+    Mutex,
+};
+
+/// How many events a subscriber can lag behind before new events get dropped for it
+pub const SUBSCRIBER_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Event {
+    /// A decoded Frame received from the Drone.
+    ///
+    /// Frames handled by the network layer itself (PINGs and Acks) are not published.
+    Frame(FrameType),
+    /// The [`ConnectionState`] of the Drone changed,
+    /// `ConnectionState::Lost` is the disconnection event.
+    ConnectionState(ConnectionState),
+    /// Performing the handshake again, see [`Config::reconnect`](crate::Config::reconnect)
+    Reconnecting { attempt: usize },
+    /// The handshake succeeded and a new session started
+    Reconnected,
+    /// All the reconnection attempts failed
+    ReconnectFailed,
+    /// The Command sender gave up on a datagram of the buffer `buffer_id`, even after retrying.
+    ///
+    /// `Drone::send_frame_with_ack` fails with `Error::Send` for the `Type::DataWithAck` frames.
+    SendFailed {
+        buffer_id: u8,
+        kind: std::io::ErrorKind,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// Filters the `Event::Frame`s by their Feature and Class ids
+///
+/// An empty (`Default`) filter lets every event through.
+pub struct Filter {
+    feature: Option<u8>,
+    class: Option<u8>,
+}
+
+impl Filter {
+    /// Only Frames with the given Feature, e.g. `1` for `Feature::ArDrone3`
+    pub fn feature(feature: u8) -> Self {
+        Self {
+            feature: Some(feature),
+            class: None,
+        }
+    }
+
+    /// Only Frames with the given Class of the Feature, e.g. `4` for `ArDrone3::PilotingState`
+    pub fn class(mut self, class: u8) -> Self {
+        self.class = Some(class);
+        self
+    }
+
+    fn is_empty(&self) -> bool {
+        self.feature.is_none() && self.class.is_none()
+    }
+
+    pub fn matches(&self, event: &Event) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        match event {
+            Event::Frame(FrameType::Known(frame)) => match &frame.feature {
+                Some(feature) => {
+                    let feature_id: u8 = feature.into();
+                    let feature_matches =
+                        self.feature.is_none() || self.feature == Some(feature_id);
+                    let class_matches = self.class.is_none() || self.class == feature.class_id();
+
+                    feature_matches && class_matches
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the `Event::Frame` of the `frame` might match, without decoding its Feature
+    pub(crate) fn matches_frame(&self, frame: &FrameRef<'_>) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        match &frame.feature {
+            Some(feature) => {
+                let feature_matches = self.feature.is_none() || self.feature == Some(feature.id());
+                let class_matches = self.class.is_none() || self.class == feature.class_id();
+
+                feature_matches && class_matches
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum EventSender {
+    Sync(SyncSender<Event>),
+    #[cfg(feature = "tokio")]
+    Async(tokio::sync::mpsc::Sender<Event>),
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    filter: Filter,
+    sender: EventSender,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Subscribers {
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+impl Subscribers {
+    pub(crate) fn subscribe(&self, filter: Filter) -> Receiver<Event> {
+        let (sender, receiver) = sync_channel(SUBSCRIBER_CAPACITY);
+
+        self.add(filter, EventSender::Sync(sender));
+
+        receiver
+    }
+
+    #[cfg(feature = "tokio")]
+    pub(crate) fn subscribe_async(&self, filter: Filter) -> tokio::sync::mpsc::Receiver<Event> {
+        let (sender, receiver) = tokio::sync::mpsc::channel(SUBSCRIBER_CAPACITY);
+
+        self.add(filter, EventSender::Async(sender));
+
+        receiver
+    }
+
+    fn add(&self, filter: Filter, sender: EventSender) {
+        self.subscribers
+            .lock()
+            .expect("Subscribers lock poisoned")
+            .push(Subscriber { filter, sender });
+    }
+
+    /// Sends the event to every subscriber it matches.
+    ///
+    /// Subscribers that dropped their receiver are removed and
+    /// subscribers that are lagging behind miss the event.
+    pub(crate) fn publish(&self, event: Event) {
+        let mut subscribers = self.subscribers.lock().expect("Subscribers lock poisoned");
+
+        subscribers.retain(|subscriber| {
+            if !subscriber.filter.matches(&event) {
+                return true;
+            }
+
+            match &subscriber.sender {
+                EventSender::Sync(sender) => match sender.try_send(event.clone()) {
+                    Ok(()) => true,
+                    Err(TrySendError::Full(_)) => {
+                        warn!("Subscriber is lagging behind, dropping event");
+                        true
+                    }
+                    Err(TrySendError::Disconnected(_)) => false,
+                },
+                #[cfg(feature = "tokio")]
+                // `tokio` 0.2 `Sender::try_send` requires `&mut self`
+                EventSender::Async(sender) => match sender.clone().try_send(event.clone()) {
+                    Ok(()) => true,
+                    Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
+                        warn!("Subscriber is lagging behind, dropping event");
+                        true
+                    }
+                    Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => false,
+                },
+            }
+        });
+    }
+
+    /// Publishes a received frame, except for the PINGs, PONGs & Acks.
+    ///
+    /// The `raw_frame` is only decoded into an `Event::Frame` if a subscriber's filter matches it.
+    pub(crate) fn publish_frame(&self, frame: &FrameRef<'_>, raw_frame: &[u8]) {
+        if [BufferID::PING, BufferID::PONG].contains(&frame.buffer_id)
+            || frame.frame_type == Type::Ack
+        {
+            return;
+        }
+
+        if self.any_filter(|filter| filter.matches_frame(frame)) {
+            self.publish_raw_frame(raw_frame);
+        }
+    }
+
+    /// Publishes a frame whose header couldn't be parsed as a `FrameType::Unknown`,
+    /// which only the empty filters match
+    pub(crate) fn publish_unknown_frame(&self, raw_frame: &[u8]) {
+        if self.any_filter(Filter::is_empty) {
+            self.publish_raw_frame(raw_frame);
+        }
+    }
+
+    fn any_filter(&self, predicate: impl Fn(&Filter) -> bool) -> bool {
+        self.subscribers
+            .lock()
+            .expect("Subscribers lock poisoned")
+            .iter()
+            .any(|subscriber| predicate(&subscriber.filter))
+    }
+
+    fn publish_raw_frame(&self, raw_frame: &[u8]) {
+        match raw_frame.pread_with::<FrameType>(0, LE) {
+            Ok(frame_type) => self.publish(Event::Frame(frame_type)),
+            Err(err) => error!("Decoding Frame: {}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod event_tests {
+    use super::*;
+    use crate::{
+        ardrone3::{piloting_state, ArDrone3},
+        command::Feature,
+        common,
+        frame::Frame,
+    };
+
+    fn piloting_state_event() -> Event {
+        Event::Frame(FrameType::Known(Frame {
+            frame_type: Type::DataWithAck,
+            buffer_id: BufferID::DCEvent,
+            sequence_id: 0,
+            feature: Some(Feature::ArDrone3(Some(ArDrone3::PilotingState(
+                piloting_state::Command::FlyingStateChanged(piloting_state::FlyingStateChanged {
+                    state: piloting_state::FlyingStateChangedState::Hovering,
+                }),
+            )))),
+        }))
+    }
+
+    #[test]
+    fn test_filter() {
+        let event = piloting_state_event();
+
+        assert!(Filter::default().matches(&event));
+        assert!(Filter::feature(1).matches(&event));
+        assert!(Filter::feature(1).class(4).matches(&event));
+        assert!(!Filter::feature(1).class(0).matches(&event));
+        assert!(!Filter::feature(0).matches(&event));
+        assert!(!Filter::feature(0).class(4).matches(&event));
+    }
+
+    #[test]
+    fn test_publish() {
+        let subscribers = Subscribers::default();
+
+        let all = subscribers.subscribe(Filter::default());
+        let common = subscribers.subscribe(Filter::feature(0));
+        let dropped = subscribers.subscribe(Filter::default());
+        drop(dropped);
+
+        let raw_frames: [&[u8]; 5] = [
+            // PING
+            &[2, 0, 0, 8, 0, 0, 0, 0],
+            // PONG
+            &[2, 1, 0, 8, 0, 0, 0, 0],
+            // Ack of the sequence id 0 on Ack(11)
+            &[1, 139, 0, 8, 0, 0, 0, 0],
+            // Common - Common - AllStates
+            &[4, 126, 0, 11, 0, 0, 0, 0, 4, 0, 0],
+            // an unknown frame type
+            &[9, 126, 0, 7, 0, 0, 0],
+        ];
+        let all_states = FrameType::Known(Frame {
+            frame_type: Type::DataWithAck,
+            buffer_id: BufferID::DCEvent,
+            sequence_id: 0,
+            feature: Some(Feature::Common(Some(common::Class::Common(
+                common::common::Command::AllStates,
+            )))),
+        });
+        let piloting_state = piloting_state_event();
+
+        for raw_frame in raw_frames.iter() {
+            match raw_frame.pread_with::<FrameRef>(0, LE) {
+                Ok(frame) => subscribers.publish_frame(&frame, raw_frame),
+                Err(_) => subscribers.publish_unknown_frame(raw_frame),
+            }
+        }
+        subscribers.publish(piloting_state.clone());
+
+        assert_eq!(Ok(Event::Frame(all_states.clone())), all.try_recv());
+        assert!(matches!(
+            all.try_recv(),
+            Ok(Event::Frame(FrameType::Unknown(_)))
+        ));
+        assert_eq!(Ok(piloting_state), all.try_recv());
+        assert!(all.try_recv().is_err());
+
+        assert_eq!(Ok(Event::Frame(all_states)), common.try_recv());
+        assert!(common.try_recv().is_err());
+
+        assert_eq!(
+            2,
+            subscribers
+                .subscribers
+                .lock()
+                .expect("Subscribers lock poisoned")
+                .len()
+        );
+    }
+}
@@ -3,6 +3,15 @@ use std::convert::TryFrom;
 use std::fmt;
 use thiserror::Error;
 
+mod borrowed;
+
+pub use borrowed::{FeatureRef, FrameRef};
+
+/// The largest UDP datagram, which can hold any number of Frames
+pub const MAX_DATAGRAM_SIZE: usize = 65_535;
+/// `[type][buffer id][sequence id][length: u32]`
+pub const HEADER_LENGTH: usize = 7;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Message parsing error")]
@@ -16,9 +25,11 @@ pub enum Error {
     },
     #[error("Expected {expected} bytes, got {actual}")]
     BytesLength { expected: u32, actual: u32 },
+    #[error("Truncated Frame: expected {expected} bytes, only {actual} left in the datagram")]
+    Truncated { expected: usize, actual: usize },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum FrameType {
     Known(Frame),
     Unknown(UnknownFrame),
@@ -32,15 +43,15 @@ pub struct UnknownFrame {
     data: Vec<u8>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Frame {
     pub frame_type: Type,
     pub buffer_id: BufferID,
     pub sequence_id: u8,
-    /// Example of empty feature:
+    /// The data of the `Type::Ack` frames is the acknowledged sequence id, it's not decoded:
     /// ```bash
     /// [2020-07-25T18:51:13Z DEBUG arsdk_rs] Bytes: 1 139 0 8 0 0 0 1
-    /// [2020-07-25T18:51:13Z INFO  arsdk_rs::parse] Frame: Frame { frame_type: Ack, buffer_id: ACKFromSendWithAck, sequence_id: 0, feature: Some(ArDrone3(None)) }
+    /// [2020-07-25T18:51:13Z INFO  arsdk_rs::parse] Frame: Frame { frame_type: Ack, buffer_id: Ack(11), sequence_id: 0, feature: Some(Unknown { feature: 1, data: [] }) }
     /// ```
     pub feature: Option<command::Feature>,
 }
@@ -69,7 +80,22 @@ impl Frame {
         Frame::new(
             frame_type,
             buffer_id,
-            drone.inner.sequence_id(buffer_id),
+            drone.session().sequence_id(buffer_id),
+            feature,
+        )
+    }
+
+    #[cfg(feature = "tokio")]
+    pub fn for_async_drone(
+        drone: &crate::asynchronous::Drone,
+        frame_type: Type,
+        buffer_id: BufferID,
+        feature: Option<command::Feature>,
+    ) -> Frame {
+        Frame::new(
+            frame_type,
+            buffer_id,
+            drone.session().sequence_id(buffer_id),
             feature,
         )
     }
@@ -109,63 +135,118 @@ impl fmt::Display for Type {
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum BufferID {
-    /// pings from device
-    PING = 0,
-    /// respond to pings
-    PONG = 1,
+    /// `0`, pings from device
+    PING,
+    /// `1`, respond to pings
+    PONG,
     /// C:
     /// #define BD_NET_CD_NONACK_ID 10
     ///
     /// PyParrot:
     /// 'SEND_NO_ACK': 10, # not-ack commandsandsensors (piloting and camera rotations)
-    CDNonAck = 10,
+    CDNonAck,
     /// C:
     /// `#define BD_NET_CD_ACK_ID 11`
     ///
     /// PyParrot:
     /// 'SEND_WITH_ACK': 11, # ack commandsandsensors (all piloting commandsandsensors)
-    CDAck = 11,
+    CDAck,
     /// C:
     /// `#define BD_NET_CD_EMERGENCY_ID 12`
     ///
     /// PyParrot:
     /// `'SEND_HIGH_PRIORITY': 12, # emergency commandsandsensors`
-    CDEmergency = 12,
+    CDEmergency,
     /// C:
     /// #define BD_NET_CD_VIDEO_ACK_ID 13
     ///
     /// PyParrot:
     /// `'VIDEO_ACK': 13, # ack for video`
-    CDVideoAck = 13,
+    CDVideoAck,
     /// C:
     /// #define BD_NET_DC_VIDEO_DATA_ID 125
     ///
     /// PyParrot:
     /// `'VIDEO_DATA' : 125, # video data`
-    DCVideo = 125,
+    DCVideo,
     /// C:
     /// #define BD_NET_DC_EVENT_ID 126
     ///
     /// PyParrot:
     // 'NO_ACK_DRONE_DATA' : 126, # data from drone (including battery and others), no ack
     ///
-    DCEvent = 126,
+    DCEvent,
     /// C:
     /// #define BD_NET_DC_NAVDATA_ID 127
     ///
     /// PyParrot:
     /// `'ACK_DRONE_DATA' : 127, # drone data that needs an ack`
-    DCNavdata = 127,
-    /// @TODO: Find the corresponding C definition if there is one and name the new enum variant!
+    DCNavdata,
+    /// C:
+    /// `ARNETWORK_MANAGER_INTERNAL_BUFFER_ID_ACK 128`, added to the id of the acknowledged buffer
+    ///
+    /// The `Type::Ack` frames of a buffer's `Type::DataWithAck` frames are sent on the buffer
+    /// `128 + buffer id`, with the acknowledged sequence id as their only data byte.
+    /// Holds the id of the acknowledged buffer, e.g. `Ack(11)` is the buffer `139`.
+    /// It's at most [`MAX_ACKNOWLEDGED_BUFFER`], build it with [`BufferID::ack_of`]:
+    /// encoding a frame with a larger one fails.
+    ///
     /// PyParrot:
     /// `'ACK_FROM_SEND_WITH_ACK': 139  # 128 + buffer id for 'SEND_WITH_ACK' is 139`
     /// Type = Ack = 1
-    /// BufferId = ACKFromSendWithAck = 139
+    /// BufferId = Ack(11) = 139
     /// Sequence = 1
     /// length = 8
-    /// Feature = ArDrone3 = 1
+    /// Acknowledged sequence id = 1
     /// 1 139 1 8 0 0 0 1
-    ACKFromSendWithAck = 139,
+    Ack(u8),
+}
+
+/// The ack buffers are `128 + buffer id`
+pub const ACK_BUFFER_OFFSET: u8 = 128;
+/// The largest buffer id an ack buffer can hold, `255 - 128`
+pub const MAX_ACKNOWLEDGED_BUFFER: u8 = u8::MAX - ACK_BUFFER_OFFSET;
+
+impl BufferID {
+    /// The buffer the `Type::DataWithAck` frames of `buffer_id` are acknowledged on,
+    /// `None` for the ack buffers themselves.
+    pub fn ack_of(buffer_id: BufferID) -> Option<BufferID> {
+        match buffer_id {
+            Self::Ack(_) => None,
+            // all the other buffer ids are at most 127
+            buffer_id => Some(Self::Ack(buffer_id.into())),
+        }
+    }
+
+    /// The Drone acknowledges our `Type::DataWithAck` frames on buffer `128 + buffer id`.
+    ///
+    /// Returns the `BufferID` of the acknowledged frames for an ack buffer.
+    pub fn acknowledged_buffer(self) -> Option<BufferID> {
+        match self {
+            Self::Ack(buffer_id) => Self::try_from(buffer_id).ok(),
+            _ => None,
+        }
+    }
+
+    /// The buffer we acknowledge the `Type::DataWithAck` frames of this buffer on,
+    /// `None` for the ack buffers themselves.
+    pub fn ack_buffer(self) -> Option<BufferID> {
+        Self::ack_of(self)
+    }
+
+    /// The id on the wire, it fails for an `Ack` of a buffer above [`MAX_ACKNOWLEDGED_BUFFER`]
+    /// which would be encoded as another buffer.
+    pub(crate) fn wire_id(self) -> Result<u8, scroll::Error> {
+        match self {
+            Self::Ack(buffer_id) if buffer_id > MAX_ACKNOWLEDGED_BUFFER => {
+                Err(scroll::Error::BadInput {
+                    size: 1,
+                    msg: "the acknowledged buffer id is at most 127",
+                })
+            }
+            buffer_id => Ok(buffer_id.into()),
+        }
+    }
 }
 
 // --------------------- Conversion impls --------------------- //
@@ -213,7 +294,7 @@ impl TryFrom<u8> for BufferID {
             125 => Ok(Self::DCVideo),
             126 => Ok(Self::DCEvent),
             127 => Ok(Self::DCNavdata),
-            139 => Ok(Self::ACKFromSendWithAck),
+            v if v >= ACK_BUFFER_OFFSET => Ok(Self::Ack(v - ACK_BUFFER_OFFSET)),
             _ => Err(Error::OutOfBound {
                 value: v.into(),
                 param: "BufferID".to_string(),
@@ -234,7 +315,8 @@ impl Into<u8> for BufferID {
             Self::DCVideo => 125,
             Self::DCEvent => 126,
             Self::DCNavdata => 127,
-            Self::ACKFromSendWithAck => 139,
+            // the acknowledged buffer id is at most 127, see `BufferID::wire_id`
+            Self::Ack(buffer_id) => ACK_BUFFER_OFFSET | buffer_id,
         }
     }
 }
@@ -253,7 +335,7 @@ impl fmt::Display for BufferID {
             Self::DCVideo => write!(f, "DCVideo"),
             Self::DCEvent => write!(f, "DCEvent"),
             Self::DCNavdata => write!(f, "DCNavdata"),
-            Self::ACKFromSendWithAck => write!(f, "ACKFromSendWithAck"),
+            Self::Ack(buffer_id) => write!(f, "Ack({})", buffer_id),
         }
     }
 }
@@ -289,67 +371,11 @@ pub mod impl_scroll {
     impl<'a> ctx::TryFromCtx<'a, Endian> for Frame {
         type Error = Error;
 
-        // and the lifetime annotation on `&'a [u8]` here
+        /// Parses the [`FrameRef`] and decodes its Feature
         fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
-            let mut actual_buf_len = 0;
-            let frame_type = src.gread_with(&mut actual_buf_len, ctx)?;
-            let buffer_id = src.gread_with(&mut actual_buf_len, ctx)?;
-            let sequence_id = src.gread_with(&mut actual_buf_len, ctx)?;
-            let buf_len: u32 = src.gread_with(&mut actual_buf_len, ctx)?;
-
-            // TODO: Fix this as it might fail, use TryFrom<u32>
-            let buf_len_usize = buf_len as usize;
-
-            let feature = if buf_len >= 8 {
-                // we can receive multiple frames, so the feature should be limited
-                // to buf_len from source
-
-                // for the PING & PONG we don't reasonable data
-                // only data to echo back from PING
-                if [BufferID::PING, BufferID::PONG].contains(&buffer_id) {
-                    // even if it's a known one, the PING doesn't send sane data
-                    let feature = src[..buf_len_usize].gread_with(&mut actual_buf_len, ctx)?;
-
-                    let mut feature_data = [0_u8; 256];
-                    let actual_written = feature_data.gwrite_with(
-                        &src[actual_buf_len..buf_len_usize],
-                        &mut 0,
-                        (),
-                    )?;
-
-                    actual_buf_len += actual_written;
-
-                    Some(Feature::Unknown {
-                        feature,
-                        data: feature_data[..actual_written].to_vec(),
-                    })
-                } else {
-                    let feature =
-                        src[..buf_len_usize].gread_with::<Feature>(&mut actual_buf_len, ctx)?;
+            let (frame, length) = FrameRef::try_from_ctx(src, ctx)?;
 
-                    Some(feature)
-                }
-            } else {
-                None
-            };
-
-            if buf_len_usize != actual_buf_len {
-                Err(Error::BytesLength {
-                    expected: buf_len,
-                    // @TODO: actual_buf_len as u32 can fail (TryFrom is impled for usize)
-                    actual: actual_buf_len as u32,
-                })
-            } else {
-                Ok((
-                    Frame {
-                        frame_type,
-                        buffer_id,
-                        sequence_id,
-                        feature,
-                    },
-                    actual_buf_len,
-                ))
-            }
+            Ok((Frame::try_from(frame)?, length))
         }
     }
 
@@ -360,7 +386,7 @@ pub mod impl_scroll {
             let mut offset = 0;
 
             this.gwrite_with::<u8>(self.frame_type.into(), &mut offset, ctx)?;
-            this.gwrite_with::<u8>(self.buffer_id.into(), &mut offset, ctx)?;
+            this.gwrite_with::<u8>(self.buffer_id.wire_id()?, &mut offset, ctx)?;
             this.gwrite_with::<u8>(self.sequence_id, &mut offset, ctx)?;
 
             let buf_length_offset = offset;
@@ -419,26 +445,7 @@ pub mod impl_scroll {
         // and the lifetime annotation on `&'a [u8]` here
         fn try_from_ctx(src: &'a [u8], _ctx: Endian) -> Result<(Self, usize), Self::Error> {
             let mut offset = 0;
-            let id_value = src.gread::<u8>(&mut offset)?;
-
-            let buffer_id = match id_value {
-                0 => Self::PING,
-                1 => Self::PONG,
-                10 => Self::CDNonAck,
-                11 => Self::CDAck,
-                12 => Self::CDEmergency,
-                13 => Self::CDVideoAck,
-                125 => Self::DCVideo,
-                126 => Self::DCEvent,
-                127 => Self::DCNavdata,
-                139 => Self::ACKFromSendWithAck,
-                value => {
-                    return Err(Error::OutOfBound {
-                        value: value.into(),
-                        param: "BufferID".to_string(),
-                    })
-                }
-            };
+            let buffer_id = Self::try_from(src.gread::<u8>(&mut offset)?)?;
 
             Ok((buffer_id, offset))
         }
@@ -448,7 +455,7 @@ pub mod impl_scroll {
         type Error = Error;
 
         fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
-            Ok(this.pwrite_with::<u8>(self.into(), 0, ctx)?)
+            Ok(this.pwrite_with::<u8>(self.wire_id()?, 0, ctx)?)
         }
     }
 }
@@ -459,9 +466,9 @@ pub mod impl_scroll {
 mod frame_tests {
     use super::*;
     use crate::{
-        ardrone3::{self, ArDrone3},
+        ardrone3::{piloting_state, ArDrone3},
         common::{self, Class as CommonClass},
-        jumping_sumo::*,
+        jumping_sumo::{animations, piloting, Class},
     };
     use chrono::{TimeZone, Utc};
     use scroll::{Pread, Pwrite, LE};
@@ -475,8 +482,8 @@ mod frame_tests {
             0x2, 0xa, 0x67, 0xe, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x1, 0x0, 0x9c,
         ];
 
-        let pilot_state = PilotState {
-            flag: true,
+        let pcmd = piloting::Pcmd {
+            flag: 1,
             speed: 0,
             turn: -100,
         };
@@ -486,7 +493,7 @@ mod frame_tests {
             buffer_id: BufferID::CDNonAck,
             sequence_id: 103,
             feature: Some(command::Feature::JumpingSumo(Class::Piloting(
-                PilotingID::Pilot(pilot_state),
+                piloting::Command::Pcmd(pcmd),
             ))),
         };
 
@@ -494,21 +501,24 @@ mod frame_tests {
     }
 
     #[test]
-    #[ignore]
     fn test_common_date_command() {
-        let message = [
-            0x4, 0xb, 0x1, 0x15, 0x0, 0x0, 0x0, 0x4, 0x1, 0x0, 0x32, 0x30, 0x32, 0x30, 0x2d, 0x30,
-            0x34, 0x2d, 0x32, 0x36, 0x0,
+        let message: [u8; 22] = [
+            0x4, 0xb, 0x1, 0x16, 0x0, 0x0, 0x0, // Feature::Common
+            0x0, // common::Class::Common
+            0x4, // Current date
+            0x1, 0x0, // 2020-04-26 incl nul
+            0x32, 0x30, 0x32, 0x30, 0x2d, 0x30, 0x34, 0x2d, 0x32, 0x36, 0x0,
         ];
 
-        let date = Utc.ymd(2020, 04, 26).and_hms(15, 06, 11);
+        // only the date is sent
+        let date = Utc.ymd(2020, 04, 26).and_hms(0, 0, 0);
 
         let frame = Frame {
             frame_type: Type::DataWithAck,
             buffer_id: BufferID::CDAck,
             sequence_id: 1,
             feature: Some(command::Feature::Common(Some(CommonClass::Common(
-                common::Common::CurrentDate(date),
+                common::common::Command::current_date(&date),
             )))),
         };
 
@@ -516,24 +526,24 @@ mod frame_tests {
     }
 
     #[test]
-    #[ignore]
     fn test_common_time_command() {
-        let message: [u8; 22] = [
-            0x4, 0xb, 0x2, 0x15, 0x0, 0x0, 0x0, // Feature::Common
+        let message: [u8; 24] = [
+            0x4, 0xb, 0x2, 0x18, 0x0, 0x0, 0x0, // Feature::Common
             0x0, // common::Class::Common
             0x4, // Current time
-            0x2, // 12 bytes incl nul
-            0x0, 0x54, 0x31, 0x35, 0x30, 0x36, 0x31, 0x31, 0x30, 0x30, 0x30, 0x0,
+            0x2, 0x0, // T150611+0000 incl nul
+            0x54, 0x31, 0x35, 0x30, 0x36, 0x31, 0x31, 0x2b, 0x30, 0x30, 0x30, 0x30, 0x0,
         ];
 
-        let date = Utc.ymd(2020, 04, 26).and_hms(15, 06, 11);
+        // only the time is sent
+        let time = Utc.ymd(1970, 01, 01).and_hms(15, 06, 11);
 
         let frame = Frame {
             frame_type: Type::DataWithAck,
             buffer_id: BufferID::CDAck,
             sequence_id: 2,
             feature: Some(command::Feature::Common(Some(CommonClass::Common(
-                common::Common::CurrentTime(date),
+                common::common::Command::current_time(&time),
             )))),
         };
 
@@ -556,8 +566,8 @@ mod frame_tests {
             0x2, 0xa, 0x67, 0xe, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x1, 0x0, 0x9c,
         ];
 
-        let pilot_state = PilotState {
-            flag: true,
+        let pcmd = piloting::Pcmd {
+            flag: 1,
             speed: 0,
             turn: -100,
         };
@@ -567,7 +577,7 @@ mod frame_tests {
             buffer_id: BufferID::CDNonAck,
             sequence_id: 103,
             feature: Some(command::Feature::JumpingSumo(Class::Piloting(
-                PilotingID::Pilot(pilot_state),
+                piloting::Command::Pcmd(pcmd),
             ))),
         };
 
@@ -576,7 +586,7 @@ mod frame_tests {
 
     #[test]
     fn test_jumpingsumo_jump_command() {
-        //                              type buf  seq  [         len      ] [JS  Anim Jump       DATA                 ]
+        //                              type buf  seq  [         len      ] [JS  Anim Jump     Type LONG         ]
         let message: [u8; 15] = [
             0x4, 0xb, 0x1, 0xf, 0x0, 0x0, 0x0, 0x3, 0x2, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0,
         ];
@@ -585,7 +595,11 @@ mod frame_tests {
             frame_type: Type::DataWithAck,
             buffer_id: BufferID::CDAck,
             sequence_id: 1,
-            feature: Some(command::Feature::JumpingSumo(Class::Animations(Anim::Jump))),
+            feature: Some(command::Feature::JumpingSumo(Class::Animations(
+                animations::Command::Jump(animations::Jump {
+                    r#type: animations::JumpType::Long,
+                }),
+            ))),
         };
 
         assert_frames_match(&message, frame);
@@ -608,39 +622,41 @@ mod frame_tests {
     }
 
     #[test]
-    #[ignore]
-    // TODO: Impl CommonState!
     fn test_feature_common_state() {
         let message: [u8; 12] = [
             2, 127, 20, 12, 0, 0, 0, // common
-            0, // Common State
-            5, //
-            1, 0, 100,
+            0, // Common
+            5, // Common State
+            1, 0,   // BatteryStateChanged
+            100, // 100%
         ];
 
         let frame = Frame {
             frame_type: Type::Data,
             buffer_id: BufferID::DCNavdata,
             sequence_id: 20,
-            feature: Some(command::Feature::Common(Some(CommonClass::CommonState))),
+            feature: Some(command::Feature::Common(Some(CommonClass::CommonState(
+                common::common_state::Command::BatteryStateChanged(
+                    common::common_state::BatteryStateChanged { percent: 100 },
+                ),
+            )))),
         };
 
         assert_frames_match(&message, frame);
     }
 
     #[test]
-    #[ignore = "We've received it from Anafi4k and it doesn't make sense atm"]
     /// [1] Type::Ack
-    /// [139] BufferID::ACKFromSendWithAck
+    /// [139] BufferID::Ack(11)
     /// [4] Sequence ID
     /// [8, 0, 0, 0] 8 length
-    /// [3] Jumping Sumo
+    /// [3] Sequence ID of the acknowledged frame (not a Jumping Sumo feature)
     fn test_unknown_jumping_sumo_feature_from_anafi() {
         let message: [u8; 8] = [1, 139, 4, 8, 0, 0, 0, 3];
 
         let frame = Frame {
             frame_type: Type::Ack,
-            buffer_id: BufferID::ACKFromSendWithAck,
+            buffer_id: BufferID::Ack(11),
             sequence_id: 4,
             feature: Some(Feature::Unknown {
                 feature: 3,
@@ -658,7 +674,7 @@ mod frame_tests {
     /// [15, 0, 0, 0] 15 length
     /// [1] ArDrone3
     /// [4] Piloting state
-    /// [21, 0]
+    /// [21, 0] not in the definitions
     /// [126, 163, 163, 64] Data?
     fn test_unknown_ardrone3_piloting_state_feature() {
         let message: [u8; 15] = [2, 127, 206, 15, 0, 0, 0, 1, 4, 21, 0, 126, 163, 163, 64];
@@ -668,8 +684,8 @@ mod frame_tests {
             buffer_id: BufferID::DCNavdata,
             sequence_id: 206,
             feature: Some(Feature::ArDrone3(Some(ArDrone3::PilotingState(
-                ardrone3::PilotingState::Unknown {
-                    piloting_state: 21,
+                piloting_state::Command::Unknown {
+                    command: 21,
                     data: message[11..].to_vec(),
                 },
             )))),
@@ -729,6 +745,56 @@ mod frame_tests {
         assert_command(BufferID::DCNavdata, 127);
     }
 
+    #[test]
+    fn test_acknowledged_buffer() {
+        assert_eq!(
+            Some(BufferID::CDAck),
+            BufferID::Ack(11).acknowledged_buffer()
+        );
+        assert_eq!(None, BufferID::CDAck.acknowledged_buffer());
+        // the ack buffer of an unknown buffer
+        assert_eq!(None, BufferID::Ack(50).acknowledged_buffer());
+
+        assert_eq!(Some(BufferID::Ack(126)), BufferID::DCEvent.ack_buffer());
+        assert_eq!(None, BufferID::Ack(126).ack_buffer());
+
+        assert_command(BufferID::Ack(11), 139);
+        assert_command(BufferID::Ack(126), 254);
+        assert_command(BufferID::Ack(127), 255);
+    }
+
+    #[test]
+    fn test_ack_of() {
+        assert_eq!(Some(BufferID::Ack(11)), BufferID::ack_of(BufferID::CDAck));
+        assert_eq!(
+            Some(BufferID::Ack(127)),
+            BufferID::ack_of(BufferID::DCNavdata)
+        );
+        assert_eq!(None, BufferID::ack_of(BufferID::Ack(11)));
+
+        // `Ack(200)` would be sent as `Ack(72)`
+        let out_of_range = Frame {
+            frame_type: Type::Ack,
+            buffer_id: BufferID::Ack(200),
+            sequence_id: 0,
+            feature: None,
+        };
+        let mut buf = [0_u8; 16];
+        assert!(matches!(
+            buf.pwrite_with(out_of_range, 0, LE),
+            Err(Error::Scroll(scroll::Error::BadInput { .. }))
+        ));
+        assert!(matches!(
+            buf.pwrite_with(BufferID::Ack(128), 0, LE),
+            Err(Error::Scroll(scroll::Error::BadInput { .. }))
+        ));
+        assert_eq!(
+            Ok(1),
+            buf.pwrite_with(BufferID::Ack(127), 0, LE).map_err(|_| ())
+        );
+        assert_eq!(255, buf[0]);
+    }
+
     fn assert_frame(t: Type, v: u8) {
         assert_eq!(t, v.try_into().unwrap());
         let as_u8: u8 = t.into();
@@ -0,0 +1,264 @@
+//! Zero-copy views of the received Frames
+//!
+//! A [`FrameRef`] borrows its Feature from the receive buffer and only the header is parsed,
+//! the arguments are decoded on demand with [`FeatureRef::decode`]
+//! or by converting it into the owned [`Frame`].
+use super::{BufferID, Error, Frame, Type, HEADER_LENGTH};
+use crate::command::Feature;
+use scroll::{
+    ctx::{self, TryFromCtx},
+    Endian, Pread, Pwrite, LE,
+};
+use std::convert::TryFrom;
+
+/// A Frame borrowed from the bytes it was parsed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameRef<'a> {
+    pub frame_type: Type,
+    pub buffer_id: BufferID,
+    pub sequence_id: u8,
+    /// `None` for a Frame with only a header
+    pub feature: Option<FeatureRef<'a>>,
+}
+
+/// The not yet decoded bytes of a Feature: its id, followed by the Class, Command & arguments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureRef<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> FrameRef<'a> {
+    /// The length of the Frame, header included
+    pub fn length(&self) -> usize {
+        HEADER_LENGTH + self.feature.map_or(0, |feature| feature.as_bytes().len())
+    }
+
+    /// The sequence id a `Type::Ack` frame acknowledges, its only data byte
+    pub fn acknowledged_sequence_id(&self) -> Option<u8> {
+        if self.frame_type != Type::Ack {
+            return None;
+        }
+
+        self.feature.map(|feature| feature.as_bytes()[0])
+    }
+}
+
+impl<'a> FeatureRef<'a> {
+    /// `None` if there are no bytes, not even the Feature id
+    pub fn new(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.is_empty() {
+            None
+        } else {
+            Some(Self { bytes })
+        }
+    }
+
+    /// The Feature id, e.g. `1` for `Feature::ArDrone3`
+    pub fn id(&self) -> u8 {
+        self.bytes[0]
+    }
+
+    /// The id of the Feature's Class, like [`Feature::class_id`]
+    pub fn class_id(&self) -> Option<u8> {
+        self.data().first().copied()
+    }
+
+    /// The bytes after the Feature id
+    pub fn data(&self) -> &'a [u8] {
+        &self.bytes[1..]
+    }
+
+    /// All the bytes, the Feature id included
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Decodes the owned `Feature`, all the bytes have to be part of it
+    pub fn decode(&self) -> Result<Feature, Error> {
+        let (feature, length) = Feature::try_from_ctx(self.bytes, LE)?;
+
+        if length != self.bytes.len() {
+            return Err(Error::BytesLength {
+                expected: self.bytes.len() as u32,
+                actual: length as u32,
+            });
+        }
+
+        Ok(feature)
+    }
+}
+
+impl<'a> TryFrom<FrameRef<'a>> for Frame {
+    type Error = Error;
+
+    fn try_from(frame: FrameRef<'a>) -> Result<Self, Self::Error> {
+        let feature = match frame.feature {
+            // the PING & PONG only carry data to echo back and the Ack the acknowledged sequence id,
+            // even if it looks like a known Feature
+            Some(feature)
+                if frame.frame_type == Type::Ack
+                    || [BufferID::PING, BufferID::PONG].contains(&frame.buffer_id) =>
+            {
+                Some(Feature::Unknown {
+                    feature: feature.id(),
+                    data: feature.data().to_vec(),
+                })
+            }
+            Some(feature) => Some(feature.decode()?),
+            None => None,
+        };
+
+        Ok(Frame::new(
+            frame.frame_type,
+            frame.buffer_id,
+            frame.sequence_id,
+            feature,
+        ))
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for FrameRef<'a> {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+        let frame_type = src.gread_with(&mut offset, ctx)?;
+        let buffer_id = src.gread_with(&mut offset, ctx)?;
+        let sequence_id = src.gread_with(&mut offset, ctx)?;
+        let length = src.gread_with::<u32>(&mut offset, ctx)? as usize;
+
+        if length > src.len() {
+            return Err(Error::Truncated {
+                expected: length,
+                actual: src.len(),
+            });
+        }
+
+        if length < HEADER_LENGTH {
+            return Err(Error::BytesLength {
+                expected: length as u32,
+                actual: HEADER_LENGTH as u32,
+            });
+        }
+
+        // we can receive multiple frames, so the feature is limited to the frame length
+        let frame = FrameRef {
+            frame_type,
+            buffer_id,
+            sequence_id,
+            feature: FeatureRef::new(&src[HEADER_LENGTH..length]),
+        };
+
+        Ok((frame, length))
+    }
+}
+
+impl<'a> ctx::TryIntoCtx<Endian> for FrameRef<'a> {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u8>(self.frame_type.into(), &mut offset, ctx)?;
+        this.gwrite_with::<u8>(self.buffer_id.wire_id()?, &mut offset, ctx)?;
+        this.gwrite_with::<u8>(self.sequence_id, &mut offset, ctx)?;
+        this.gwrite_with::<u32>(self.length() as u32, &mut offset, ctx)?;
+
+        if let Some(feature) = self.feature {
+            this.gwrite_with(feature.as_bytes(), &mut offset, ())?;
+        }
+
+        Ok(offset)
+    }
+}
+
+#[cfg(test)]
+mod borrowed_tests {
+    use super::*;
+    use crate::jumping_sumo::{
+        animations::{Command, Jump, JumpType},
+        Class,
+    };
+
+    #[test]
+    fn test_frame_ref() {
+        // the Jumping Sumo jump, followed by the next frame
+        let buf: [u8; 17] = [4, 11, 1, 15, 0, 0, 0, 3, 2, 3, 0, 0, 0, 0, 0, 2, 0];
+
+        let (frame, length) = FrameRef::try_from_ctx(&buf, LE).expect("Should parse");
+        assert_eq!(15, length);
+        assert_eq!(15, frame.length());
+
+        let feature = frame.feature.expect("Should have a Feature");
+        assert_eq!(3, feature.id());
+        assert_eq!(Some(2), feature.class_id());
+        assert_eq!(&buf[7..15], feature.as_bytes());
+
+        let owned = Frame::try_from(frame).expect("Should decode");
+        assert_eq!(
+            Some(Feature::JumpingSumo(Class::Animations(Command::Jump(
+                Jump {
+                    r#type: JumpType::Long
+                }
+            )))),
+            owned.feature
+        );
+        assert_eq!(Ok(owned), buf.pread_with::<Frame>(0, LE).map_err(|_| ()));
+
+        let mut written = [0_u8; 15];
+        assert_eq!(Ok(15), written.pwrite_with(frame, 0, LE).map_err(|_| ()));
+        assert_eq!(&buf[..15], &written[..]);
+
+        // a Feature with extra bytes
+        let extra = FeatureRef::new(&[3, 2, 3, 0, 0, 0, 0, 0, 1]).expect("Should have bytes");
+        assert!(matches!(
+            extra.decode(),
+            Err(Error::BytesLength {
+                expected: 9,
+                actual: 8
+            })
+        ));
+    }
+
+    #[test]
+    fn test_ack() {
+        // acknowledges the sequence id 3, which is not a Jumping Sumo Feature
+        let (ack, _) =
+            FrameRef::try_from_ctx(&[1, 139, 4, 8, 0, 0, 0, 3], LE).expect("Should parse");
+        assert_eq!(Some(3), ack.acknowledged_sequence_id());
+
+        let owned = Frame::try_from(ack).expect("Should not decode the sequence id");
+        assert_eq!(
+            Some(Feature::Unknown {
+                feature: 3,
+                data: vec![],
+            }),
+            owned.feature
+        );
+
+        // the same byte in a Data frame is a Feature
+        let (data, _) =
+            FrameRef::try_from_ctx(&[2, 11, 4, 8, 0, 0, 0, 3], LE).expect("Should parse");
+        assert_eq!(None, data.acknowledged_sequence_id());
+        assert!(Frame::try_from(data).is_err());
+    }
+
+    #[test]
+    fn test_header_only_and_truncated() {
+        let (ack, _) = FrameRef::try_from_ctx(&[1, 139, 0, 7, 0, 0, 0], LE).expect("Should parse");
+        assert_eq!(None, ack.feature);
+        assert_eq!(None, ack.acknowledged_sequence_id());
+
+        assert!(matches!(
+            FrameRef::try_from_ctx(&[2, 127, 0, 20, 0, 0, 0, 1], LE),
+            Err(Error::Truncated {
+                expected: 20,
+                actual: 8
+            })
+        ));
+        assert!(matches!(
+            FrameRef::try_from_ctx(&[9, 127, 0, 8, 0, 0, 0, 1], LE),
+            Err(Error::OutOfBound { value: 9, .. })
+        ));
+    }
+}
@@ -1,56 +1,68 @@
+use crate::Config;
 use log::{error, info};
 use serde::{Deserialize, Serialize};
 use serde_with::with_prefix;
+use socket2::SockRef;
 use std::{
-    io::{Read, Write},
+    io::{self, Read, Write},
     net::{Shutdown, SocketAddr, TcpStream},
     string::FromUtf8Error,
+    time::Duration,
 };
 use thiserror::Error;
 
-#[derive(Serialize, Debug)]
-pub(crate) struct Request {
-    controller_name: String,
-    controller_type: String,
-    d2c_port: u16,
-    #[serde(flatten, with = "prefix_arstream2_client")]
+/// The ARNetworkAL protocol version we implement, i.e. the legacy framing
+pub const PROTOCOL_VERSION: u8 = 1;
+/// The response is read until its `\u{0}` terminator, but not further than this
+pub const MAX_RESPONSE_SIZE: usize = 4096;
+/// C SDK: `ARSAL_SOCKET_CLASS_SELECTOR_CS6`, the `IP_TOS` of the datagrams when `qos_mode` is `1`
+pub(crate) const CLASS_SELECTOR_CS6: u32 = 0xC0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub controller_name: String,
+    pub controller_type: String,
+    pub d2c_port: u16,
+    #[serde(default, flatten, with = "prefix_arstream2_client")]
     pub arstream2: Option<ArStream2>,
+    /// The highest protocol version we support, the Drone answers with the one it picked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proto_v: Option<u8>,
 }
 
-#[derive(Deserialize, Debug)]
-/// Request: "{\"controller_name\":\"arsdk-rs\",\"controller_type\":\"computer\",\"d2c_port\":43210}"
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+/// Request: "{\"controller_name\":\"arsdk-rs\",\"controller_type\":\"computer\",\"d2c_port\":43210,\"proto_v\":1}"
 /// Response: "{ \"status\": 0, \"c2d_port\": 54321, \"c2d_update_port\": 51, \"c2d_user_port\": 21, \"qos_mode\": 0, \"arstream2_server_stream_port\": 5004, \"arstream2_server_control_port\": 5005 }\u{0}"
-/// `\u{0}` causes issues, but for now we `trim_end_matches`
-/// Error: trailing characters at line 1 column 171
+///
+/// The response is terminated by `\u{0}` and can be split across multiple TCP segments.
 pub struct Response {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub arstream_fragment_maximum_number: Option<u8>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub arstream_fragment_size: Option<u16>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub arstream_max_ack_interval: Option<i8>,
     #[serde(default, flatten, with = "prefix_arstream2_server")]
     pub arstream2: Option<ArStream2>,
     pub c2d_port: u16,
     pub c2d_update_port: u16,
     pub c2d_user_port: u16,
+    /// `0` if the Drone accepted the connection
     pub status: i8,
-    // @TODO: Check what this field is for.
-    /// Bool?!
+    /// `1` when the datagrams should be marked with the `CS6` class selector, see [`Response::qos`]
     pub qos_mode: u8,
-    // @TODO: Check what this field is for **and** if it's a bool at all
-    /// Bool?!
-    #[serde(default)]
+    /// The protocol version picked by the Drone, older firmwares only speak the version `1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub proto_v: Option<u8>,
 }
 
 with_prefix!(prefix_arstream2_client "arstream2_client_");
 with_prefix!(prefix_arstream2_server "arstream2_server_");
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ArStream2 {
-    stream_port: u16,
-    control_port: u16,
+    pub stream_port: u16,
+    pub control_port: u16,
 }
 #[derive(Debug, Error)]
 pub enum Error {
@@ -60,6 +72,13 @@ pub enum Error {
     ConnectionRefused(Response),
     #[error("Maximum allowed retries reached for {target}")]
     Retry { target: SocketAddr },
+    #[error(
+        "Unsupported protocol version {0}, only {} is supported",
+        PROTOCOL_VERSION
+    )]
+    UnsupportedProtocol(u8),
+    #[error("The response exceeds {} bytes", MAX_RESPONSE_SIZE)]
+    ResponseTooLarge,
     #[error("Json (de)serialization - {0:?}")]
     Json(#[from] serde_json::Error),
     /// Primarily used for logging the response string
@@ -67,65 +86,126 @@ pub enum Error {
     ResponseString(#[from] FromUtf8Error),
 }
 
+impl Request {
+    /// `d2c_port` is the actual port we listen on, which differs from `Config::d2c_port` if it's ephemeral
+    pub(crate) fn new(config: &Config, d2c_port: u16) -> Self {
+        Self {
+            controller_name: config.controller_name.to_string(),
+            controller_type: config.controller_type.to_string(),
+            d2c_port,
+            // set by the `Product` profile
+            arstream2: config.arstream2(),
+            proto_v: Some(PROTOCOL_VERSION),
+        }
+    }
+}
+
+impl Response {
+    /// Parses the response bytes, trimming the trailing `\u{0}` that the drone sends.
+    fn from_bytes(buf: &[u8]) -> Result<Self, Error> {
+        let response_string = String::from_utf8(buf.to_vec())?;
+
+        info!("Response: {}", response_string);
+
+        let response: Response = serde_json::from_str(response_string.trim_end_matches('\u{0}'))?;
+
+        if response.status != 0 {
+            Err(Error::ConnectionRefused(response))
+        } else if response.protocol_version() > PROTOCOL_VERSION {
+            Err(Error::UnsupportedProtocol(response.protocol_version()))
+        } else {
+            Ok(response)
+        }
+    }
+
+    /// Whether or not the Drone asked for QoS
+    pub fn qos(&self) -> bool {
+        self.qos_mode == 1
+    }
+
+    /// Without `proto_v` in the response, it's the version `1`
+    pub fn protocol_version(&self) -> u8 {
+        self.proto_v.unwrap_or(1)
+    }
+
+    /// Marks the datagrams of the `socket` with the `CS6` class selector, if the Drone asked for QoS
+    pub(crate) fn apply_qos<'s>(&self, socket: impl Into<SockRef<'s>>) -> io::Result<()> {
+        if self.qos() {
+            socket.into().set_tos(CLASS_SELECTOR_CS6)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Appends the `chunk` to the `response` and returns whether the `\u{0}` terminator was reached,
+/// in which case it's truncated from the `response`.
+///
+/// Fails if the `response` would exceed [`MAX_RESPONSE_SIZE`], terminated or not.
+fn push_chunk(response: &mut Vec<u8>, chunk: &[u8]) -> Result<bool, Error> {
+    let (data, terminated) = match chunk.iter().position(|byte| *byte == 0) {
+        Some(terminator) => (&chunk[..terminator], true),
+        None => (chunk, false),
+    };
+
+    if response.len() + data.len() > MAX_RESPONSE_SIZE {
+        return Err(Error::ResponseTooLarge);
+    }
+    response.extend_from_slice(data);
+
+    Ok(terminated)
+}
+
+/// Reads the response until its `\u{0}` terminator or the end of the stream
+fn read_response(stream: &mut impl Read) -> Result<Vec<u8>, Error> {
+    let mut response = Vec::new();
+    let mut buf = [0_u8; 256];
+
+    loop {
+        let read = match stream.read(&mut buf) {
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+
+        if read == 0 || push_chunk(&mut response, &buf[..read])? {
+            return Ok(response);
+        }
+    }
+}
+
 pub(crate) fn perform_handshake(
     init_address: SocketAddr,
-    d2c_port: u16,
+    request: &Request,
 ) -> Result<Response, Error> {
-    let request = Request {
-        controller_name: "arsdk-rs".to_string(),
-        controller_type: "computer".to_string(),
-        d2c_port,
-        // Anafi4k:
-        // self.stream_port = 55004
-        // self.stream_control_port = 55005
-        arstream2: Some(ArStream2 {
-            stream_port: 44445,
-            control_port: 44446,
-        }),
-        // TODO: Check when we don't need `arstream2`
-        // the pyparrot has a check for setting `arstream2` when:
-        // `if(self.drone_type in ("Anafi", "Bebop", "Bebop2", "Disco")):`
-        // arstream2: None,
-    };
-
     info!("Connecting controller {}", request.controller_name);
 
     let mut handshake_stream = retry(10, init_address)?;
 
-    info!("Request: {}", serde_json::to_string(&request)?);
-    let request_string = serde_json::to_vec(&request)?;
+    info!("Request: {}", serde_json::to_string(request)?);
+    let request_string = serde_json::to_vec(request)?;
 
     handshake_stream.write_all(&request_string)?;
 
-    let mut buf = [0_u8; 256];
-    let read = handshake_stream.read(&mut buf)?;
-    info!("Read {} bytes!", read);
-
-    let response_string = String::from_utf8(buf[..read].to_vec())?;
-
-    info!("Response: {}", response_string);
+    let response = read_response(&mut handshake_stream)?;
+    info!("Read {} bytes!", response.len());
 
     handshake_stream.shutdown(Shutdown::Both)?;
 
-    let response: Response = serde_json::from_str(&response_string.trim_end_matches('\u{0}'))?;
-
-    if response.status != 0 {
-        Err(Error::ConnectionRefused(response))
-    } else {
-        Ok(response)
-    }
+    Response::from_bytes(&response)
 }
 
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(2);
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
 fn retry(times: usize, target: SocketAddr) -> Result<TcpStream, Error> {
-    let connection_timeout = std::time::Duration::from_secs(2);
-    let read_timeout = std::time::Duration::from_secs(2);
     let mut retry = 0;
 
-    let mut res = TcpStream::connect_timeout(&target, connection_timeout);
+    let mut res = TcpStream::connect_timeout(&target, CONNECTION_TIMEOUT);
 
     while res.is_err() && retry < times {
         retry += 1;
-        res = TcpStream::connect_timeout(&target, connection_timeout);
+        res = TcpStream::connect_timeout(&target, CONNECTION_TIMEOUT);
     }
 
     let tcp_stream = match res {
@@ -139,7 +219,124 @@ fn retry(times: usize, target: SocketAddr) -> Result<TcpStream, Error> {
 
     info!("{}: TCP Stream initialized", target);
 
-    tcp_stream.set_read_timeout(Some(read_timeout))?;
+    tcp_stream.set_read_timeout(Some(READ_TIMEOUT))?;
 
     Ok(tcp_stream)
 }
+
+#[cfg(test)]
+mod handshake_tests {
+    use super::*;
+
+    #[test]
+    fn test_response_from_bytes() {
+        let response = b"{ \"status\": 0, \"c2d_port\": 54321, \"c2d_update_port\": 51, \"c2d_user_port\": 21, \"qos_mode\": 0, \"arstream2_server_stream_port\": 5004, \"arstream2_server_control_port\": 5005 }\0";
+
+        let response = Response::from_bytes(response).expect("Should parse the Response");
+
+        assert_eq!(54321, response.c2d_port);
+        assert_eq!(0, response.status);
+    }
+
+    #[test]
+    fn test_request_from_config() {
+        let config = Config::builder([192, 168, 42, 1])
+            .controller_name("test")
+            .controller_type("tablet")
+            .arstream2(None)
+            .build();
+
+        let request = Request::new(&config, 55555);
+
+        assert_eq!(
+            r#"{"controller_name":"test","controller_type":"tablet","d2c_port":55555,"proto_v":1}"#,
+            serde_json::to_string(&request).expect("Should serialize")
+        );
+
+        let default_request = Request::new(&Config::from([192, 168, 42, 1]), 43210);
+
+        assert_eq!(
+            r#"{"controller_name":"arsdk-rs","controller_type":"computer","d2c_port":43210,"arstream2_client_stream_port":44445,"arstream2_client_control_port":44446,"proto_v":1}"#,
+            serde_json::to_string(&default_request).expect("Should serialize")
+        );
+    }
+
+    #[test]
+    fn test_serialized_response_from_bytes() {
+        let response = Response {
+            arstream_fragment_maximum_number: None,
+            arstream_fragment_size: None,
+            arstream_max_ack_interval: None,
+            arstream2: Some(ArStream2 {
+                stream_port: 5004,
+                control_port: 5005,
+            }),
+            c2d_port: 54321,
+            c2d_update_port: 51,
+            c2d_user_port: 21,
+            status: 0,
+            qos_mode: 0,
+            proto_v: None,
+        };
+
+        let mut bytes = serde_json::to_vec(&response).expect("Should serialize");
+        bytes.push(0);
+
+        assert_eq!(
+            response,
+            Response::from_bytes(&bytes).expect("Should parse the Response")
+        );
+    }
+
+    #[test]
+    fn test_refused_response_from_bytes() {
+        let response = b"{ \"status\": -1, \"c2d_port\": 54321, \"c2d_update_port\": 51, \"c2d_user_port\": 21, \"qos_mode\": 0 }\0";
+
+        match Response::from_bytes(response) {
+            Err(Error::ConnectionRefused(response)) => assert_eq!(-1, response.status),
+            result => panic!("Expected a refused connection, got: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_read_split_response() {
+        let response = b"{ \"status\": 0, \"c2d_port\": 54321, \"c2d_update_port\": 51, \"c2d_user_port\": 21, \"qos_mode\": 1, \"proto_v\": 1 }\0";
+        // the response is split across 2 TCP segments
+        let (first, second) = response.split_at(40);
+        let mut stream = first.chain(second);
+
+        let bytes = read_response(&mut stream).expect("Should read the whole response");
+        assert_eq!(&response[..response.len() - 1], bytes.as_slice());
+
+        let response = Response::from_bytes(&bytes).expect("Should parse the Response");
+        assert!(response.qos());
+        assert_eq!(1, response.protocol_version());
+    }
+
+    #[test]
+    fn test_response_too_large_or_unsupported() {
+        let endless = vec![b' '; MAX_RESPONSE_SIZE + 1];
+
+        match read_response(&mut endless.as_slice()) {
+            Err(Error::ResponseTooLarge) => {}
+            result => panic!("Expected a too large response, got: {:?}", result),
+        }
+
+        // the chunk with the terminator counts too
+        let mut response = vec![b' '; MAX_RESPONSE_SIZE - 1];
+        assert!(matches!(
+            push_chunk(&mut response, b"  \0"),
+            Err(Error::ResponseTooLarge)
+        ));
+        assert_eq!(MAX_RESPONSE_SIZE - 1, response.len());
+        assert!(matches!(push_chunk(&mut response, b" \0"), Ok(true)));
+        assert_eq!(MAX_RESPONSE_SIZE, response.len());
+
+        let response = b"{ \"status\": 0, \"c2d_port\": 54321, \"c2d_update_port\": 51, \"c2d_user_port\": 21, \"qos_mode\": 0, \"proto_v\": 3 }\0";
+
+        match Response::from_bytes(response) {
+            Err(Error::UnsupportedProtocol(3)) => {}
+            result => panic!("Expected an unsupported protocol, got: {:?}", result),
+        }
+    }
+}
@@ -0,0 +1,288 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `2`: Animation commands
+//!
+//! Generated from the `Animations` Class of `arsdk-gen/xml/jpsumo.xml` (the `jpsumo` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_unknown, write_bytes},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// u16
+pub enum Command {
+    /// `0`: Stop jump, emergency jump stop, stop jump motor and stay there
+    JumpStop,
+    /// `1`: Cancel jump and come back to previous state (if possible)
+    JumpCancel,
+    /// `2`: Request jump loading
+    JumpLoad,
+    /// `3`: Jump
+    Jump(Jump),
+    /// `4`: Play a parameterless animation
+    SimpleAnimation(SimpleAnimation),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::JumpStop => 0,
+            Command::JumpCancel => 1,
+            Command::JumpLoad => 2,
+            Command::Jump(_) => 3,
+            Command::SimpleAnimation(_) => 4,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => Self::JumpStop,
+            1 => Self::JumpCancel,
+            2 => Self::JumpLoad,
+            3 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::Jump(args)
+            }
+            4 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::SimpleAnimation(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::Jump(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::SimpleAnimation(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+            // no arguments
+            _ => {}
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Jump
+pub struct Jump {
+    /// Type of jump
+    pub r#type: JumpType,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Jump {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            r#type: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Jump {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.r#type, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Type of jump
+pub enum JumpType {
+    /// `0`: Long jump
+    Long,
+    /// `1`: High jump
+    High,
+}
+
+impl From<JumpType> for u32 {
+    fn from(value: JumpType) -> Self {
+        match value {
+            JumpType::Long => 0,
+            JumpType::High => 1,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for JumpType {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Long,
+            1 => Self::High,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "JumpType".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for JumpType {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Play a parameterless animation
+pub struct SimpleAnimation {
+    /// Animation ID.
+    pub id: SimpleAnimationId,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for SimpleAnimation {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            id: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for SimpleAnimation {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.id, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Animation ID.
+pub enum SimpleAnimationId {
+    /// `0`: Stop ongoing animation.
+    Stop,
+    /// `1`: Start a spin animation.
+    Spin,
+    /// `2`: Start a tap animation.
+    Tap,
+    /// `3`: Start a slow shake animation.
+    Slowshake,
+    /// `4`: Start a Metronome animation.
+    Metronome,
+    /// `5`: Start a standing dance animation.
+    Ondulation,
+    /// `6`: Start a spin jump animation.
+    Spinjump,
+    /// `7`: Start a spin that end in standing posture, or in jumper if it was standing animation.
+    Spintoposture,
+    /// `8`: Start a spiral animation.
+    Spiral,
+    /// `9`: Start a slalom animation.
+    Slalom,
+}
+
+impl From<SimpleAnimationId> for u32 {
+    fn from(value: SimpleAnimationId) -> Self {
+        match value {
+            SimpleAnimationId::Stop => 0,
+            SimpleAnimationId::Spin => 1,
+            SimpleAnimationId::Tap => 2,
+            SimpleAnimationId::Slowshake => 3,
+            SimpleAnimationId::Metronome => 4,
+            SimpleAnimationId::Ondulation => 5,
+            SimpleAnimationId::Spinjump => 6,
+            SimpleAnimationId::Spintoposture => 7,
+            SimpleAnimationId::Spiral => 8,
+            SimpleAnimationId::Slalom => 9,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for SimpleAnimationId {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Stop,
+            1 => Self::Spin,
+            2 => Self::Tap,
+            3 => Self::Slowshake,
+            4 => Self::Metronome,
+            5 => Self::Ondulation,
+            6 => Self::Spinjump,
+            7 => Self::Spintoposture,
+            8 => Self::Spiral,
+            9 => Self::Slalom,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "SimpleAnimationId".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for SimpleAnimationId {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
@@ -0,0 +1,251 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `0`: All commands related to piloting the JumpingSumo
+//!
+//! Generated from the `Piloting` Class of `arsdk-gen/xml/jpsumo.xml` (the `jpsumo` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_unknown, write_bytes},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq)]
+/// u16
+pub enum Command {
+    /// `0`: Move the robot
+    Pcmd(Pcmd),
+    /// `1`: Set posture
+    Posture(Posture),
+    /// `2`: Turn the robot
+    AddCapOffset(AddCapOffset),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::Pcmd(_) => 0,
+            Command::Posture(_) => 1,
+            Command::AddCapOffset(_) => 2,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::Pcmd(args)
+            }
+            1 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::Posture(args)
+            }
+            2 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AddCapOffset(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::Pcmd(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Posture(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AddCapOffset(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Move the robot
+pub struct Pcmd {
+    /// Boolean for "touch screen".
+    pub flag: u8,
+    /// Speed value [-100:100].
+    pub speed: i8,
+    /// Turn value. [-100:100]
+    pub turn: i8,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Pcmd {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            flag: src.gread_with(&mut offset, ctx)?,
+            speed: src.gread_with(&mut offset, ctx)?,
+            turn: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Pcmd {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.flag, &mut offset, ctx)?;
+        this.gwrite_with(self.speed, &mut offset, ctx)?;
+        this.gwrite_with(self.turn, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Set posture
+pub struct Posture {
+    /// Type of Posture
+    pub r#type: PostureType,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Posture {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            r#type: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Posture {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.r#type, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Type of Posture
+pub enum PostureType {
+    /// `0`: Standing type
+    Standing,
+    /// `1`: Jumper type
+    Jumper,
+    /// `2`: Kicker type
+    Kicker,
+}
+
+impl From<PostureType> for u32 {
+    fn from(value: PostureType) -> Self {
+        match value {
+            PostureType::Standing => 0,
+            PostureType::Jumper => 1,
+            PostureType::Kicker => 2,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for PostureType {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Standing,
+            1 => Self::Jumper,
+            2 => Self::Kicker,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "PostureType".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for PostureType {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Turn the robot
+pub struct AddCapOffset {
+    /// Offset value in radians. [-pi:pi]
+    pub offset: f32,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AddCapOffset {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            offset: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AddCapOffset {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.offset, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
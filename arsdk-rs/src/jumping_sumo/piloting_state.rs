@@ -0,0 +1,306 @@
+// @generated by `arsdk-gen`, do not edit by hand!
+//! `1`: Piloting state from product
+//!
+//! Generated from the `PilotingState` Class of `arsdk-gen/xml/jpsumo.xml` (the `jpsumo` Feature),
+//! regenerate it with `cargo run -p arsdk-gen`.
+use crate::{
+    frame::Error,
+    parse::{read_unknown, write_bytes},
+};
+use scroll::{ctx, Endian, Pread, Pwrite};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// u16
+pub enum Command {
+    /// `0`: Posture
+    PostureChanged(PostureChanged),
+    /// `1`: Alert state
+    AlertStateChanged(AlertStateChanged),
+    /// `2`: Speed
+    SpeedChanged(SpeedChanged),
+    /// A Command that is not in the definitions
+    Unknown { command: u16, data: Vec<u8> },
+}
+
+impl From<&Command> for u16 {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::PostureChanged(_) => 0,
+            Command::AlertStateChanged(_) => 1,
+            Command::SpeedChanged(_) => 2,
+            Command::Unknown { command, .. } => *command,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for Command {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let command = match src.gread_with::<u16>(&mut offset, ctx)? {
+            0 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::PostureChanged(args)
+            }
+            1 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::AlertStateChanged(args)
+            }
+            2 => {
+                let args = src.gread_with(&mut offset, ctx)?;
+
+                Self::SpeedChanged(args)
+            }
+            unknown => Self::Unknown {
+                command: unknown,
+                data: read_unknown(src, &mut offset)?,
+            },
+        };
+
+        Ok((command, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for Command {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with::<u16>((&self).into(), &mut offset, ctx)?;
+
+        match self {
+            Self::PostureChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::AlertStateChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::SpeedChanged(args) => {
+                this.gwrite_with(args, &mut offset, ctx)?;
+            }
+            Self::Unknown { data, .. } => {
+                write_bytes(this, &data, &mut offset)?;
+            }
+        }
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Posture
+pub struct PostureChanged {
+    /// State of posture changed
+    pub state: PostureChangedState,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for PostureChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            state: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for PostureChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.state, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// State of posture changed
+pub enum PostureChangedState {
+    /// `0`: Standing posture
+    Standing,
+    /// `1`: Jumper posture
+    Jumper,
+    /// `2`: Kicker posture
+    Kicker,
+    /// `3`: Stuck posture
+    Stuck,
+    /// `4`: Unknown posture
+    Unknown,
+}
+
+impl From<PostureChangedState> for u32 {
+    fn from(value: PostureChangedState) -> Self {
+        match value {
+            PostureChangedState::Standing => 0,
+            PostureChangedState::Jumper => 1,
+            PostureChangedState::Kicker => 2,
+            PostureChangedState::Stuck => 3,
+            PostureChangedState::Unknown => 4,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for PostureChangedState {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::Standing,
+            1 => Self::Jumper,
+            2 => Self::Kicker,
+            3 => Self::Stuck,
+            4 => Self::Unknown,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "PostureChangedState".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for PostureChangedState {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Alert state
+pub struct AlertStateChanged {
+    /// JumpingSumo alert state
+    pub state: AlertStateChangedState,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AlertStateChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            state: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AlertStateChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.state, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// JumpingSumo alert state
+pub enum AlertStateChangedState {
+    /// `0`: No alert
+    None,
+    /// `1`: Critical battery alert
+    CriticalBattery,
+    /// `2`: Low battery alert
+    LowBattery,
+}
+
+impl From<AlertStateChangedState> for u32 {
+    fn from(value: AlertStateChangedState) -> Self {
+        match value {
+            AlertStateChangedState::None => 0,
+            AlertStateChangedState::CriticalBattery => 1,
+            AlertStateChangedState::LowBattery => 2,
+        }
+    }
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for AlertStateChangedState {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let value = match src.gread_with::<u32>(&mut offset, ctx)? {
+            0 => Self::None,
+            1 => Self::CriticalBattery,
+            2 => Self::LowBattery,
+            value => {
+                return Err(Error::OutOfBound {
+                    value: value.into(),
+                    param: "AlertStateChangedState".to_string(),
+                })
+            }
+        };
+
+        Ok((value, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for AlertStateChangedState {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        Ok(this.pwrite_with::<u32>(self.into(), 0, ctx)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Speed
+pub struct SpeedChanged {
+    /// Speed command applied to motors in range [-100;100].
+    pub speed: i8,
+    /// Actual speed of the robot (in cm/s).
+    pub real_speed: i16,
+}
+
+impl<'a> ctx::TryFromCtx<'a, Endian> for SpeedChanged {
+    type Error = Error;
+
+    fn try_from_ctx(src: &'a [u8], ctx: Endian) -> Result<(Self, usize), Self::Error> {
+        let mut offset = 0;
+
+        let args = Self {
+            speed: src.gread_with(&mut offset, ctx)?,
+            real_speed: src.gread_with(&mut offset, ctx)?,
+        };
+
+        Ok((args, offset))
+    }
+}
+
+impl ctx::TryIntoCtx<Endian> for SpeedChanged {
+    type Error = Error;
+
+    fn try_into_ctx(self, this: &mut [u8], ctx: Endian) -> Result<usize, Self::Error> {
+        let mut offset = 0;
+
+        this.gwrite_with(self.speed, &mut offset, ctx)?;
+        this.gwrite_with(self.real_speed, &mut offset, ctx)?;
+
+        Ok(offset)
+    }
+}
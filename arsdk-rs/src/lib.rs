@@ -1,16 +1,25 @@
-use crate::frame::{Frame, FrameType};
+use crate::ack::{Notifier, RETRANSMIT_INTERVAL};
+use crate::event::{Event, Filter};
+use crate::frame::FrameType;
+use crate::session::{Session, WeakSession};
+use crate::watchdog::WATCHDOG_INTERVAL;
 use chrono::{DateTime, Utc};
-use dashmap::DashMap;
-use log::{error, info};
+use log::{error, info, warn};
 use pnet::datalink;
-use scroll::{ctx::TryIntoCtx, Pread, LE};
+use std::borrow::Cow;
+use std::io::ErrorKind;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
 use std::sync::{
-    mpsc::{sync_channel, Receiver, SendError, SyncSender},
-    Arc,
+    mpsc::{sync_channel, Receiver},
+    Arc, Mutex,
 };
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
+// `arsdk_derive::FramePart` refers to the crate as `::arsdk_rs`
+extern crate self as arsdk_rs;
+
 // re-export chrono
 pub use chrono;
 
@@ -21,33 +30,92 @@ pub const PARROT_SPHINX_CONFIG: Config = Config {
     drone_addr: PARROT_SPHINX_IP,
     // @TODO: Once we fix the Date Time sending, set to `TRUE`
     send_datetime: false,
+    ack_timeout: DEFAULT_ACK_TIMEOUT,
+    ack_retries: DEFAULT_ACK_RETRIES,
+    degraded_timeout: DEFAULT_DEGRADED_TIMEOUT,
+    connection_timeout: DEFAULT_CONNECTION_TIMEOUT,
+    send_disconnection: true,
+    reconnect: None,
+    init_port: INIT_PORT,
+    d2c_port: LISTEN_PORT,
+    controller_name: Cow::Borrowed(DEFAULT_CONTROLLER_NAME),
+    controller_type: Cow::Borrowed(DEFAULT_CONTROLLER_TYPE),
+    arstream2: None,
+    product: Some(Product::Sphinx),
+    local_addr: None,
+    send_queues: Vec::new(),
 };
 
+/// How often the spawned threads check if the Drone was disconnected
+pub(crate) const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How many times the Command sender sends a datagram again when the socket fails,
+/// e.g. with a transient `ENETUNREACH` while the Wi-Fi reconnects
+pub(crate) const SEND_RETRIES: usize = 3;
+pub(crate) const SEND_RETRY_INTERVAL: Duration = Duration::from_millis(20);
+
+mod ack;
 pub mod ardrone3;
+#[cfg(feature = "tokio")]
+pub mod asynchronous;
 pub mod command;
 pub mod common;
+mod config;
+pub mod discovery;
+pub mod event;
 pub mod frame;
-mod handshake;
+pub mod handshake;
 pub mod jumping_sumo;
 pub mod listener;
 pub mod parse;
-
-pub(crate) use handshake::perform_handshake;
-use listener::Listener;
+mod piloting;
+mod product;
+mod queue;
+mod receive;
+mod reconnect;
+mod session;
+mod stats;
+pub mod transport;
+mod watchdog;
+
+pub use ack::{DeliveryStatus, DEFAULT_ACK_RETRIES, DEFAULT_ACK_TIMEOUT};
+pub use config::{
+    Config, ConfigBuilder, DEFAULT_ARSTREAM2, DEFAULT_CONTROLLER_NAME, DEFAULT_CONTROLLER_TYPE,
+};
+pub use handshake::ArStream2;
+pub use piloting::{PilotingCommand, Setpoint, PILOTING_INTERVAL};
+pub use product::{Product, ANAFI_ARSTREAM2};
+pub use queue::{Overflow, QueueParams, DEFAULT_QUEUE_PARAMS};
+pub use receive::ReceiveStats;
+pub use reconnect::{ReconnectPolicy, DEFAULT_INITIAL_BACKOFF, DEFAULT_MAX_BACKOFF};
+pub use stats::{Stats, Throughput, PING_INTERVAL};
+use transport::{Transport, UdpTransport};
+pub use watchdog::{ConnectionState, DEFAULT_CONNECTION_TIMEOUT, DEFAULT_DEGRADED_TIMEOUT};
 
 pub mod prelude {
     pub use crate::{
-        frame, Config, ConnectionError, Drone, Error, PARROT_SPHINX_CONFIG, PARROT_SPHINX_IP,
+        frame, Config, ConnectionError, ConnectionState, DeliveryStatus, Drone, Error, Product,
+        ReconnectPolicy, PARROT_SPHINX_CONFIG, PARROT_SPHINX_IP,
     };
     pub use chrono::{DateTime, Utc};
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("Sending command")]
-    Send(#[from] SendError<Vec<u8>>),
+    #[error("The queue of buffer {0} is full")]
+    QueueFull(u8),
     #[error("Receiving a Frame")]
     Receive(#[from] frame::Error),
+    #[error("Only {} frames are acknowledged, got {0}", frame::Type::DataWithAck)]
+    NotAcknowledged(frame::Type),
+    #[error("The Drone is disconnected")]
+    Disconnected,
+    #[error("Sending a datagram on buffer {buffer_id} failed - {error}")]
+    Send {
+        buffer_id: u8,
+        error: std::io::Error,
+    },
+    #[error("The Command sender stopped")]
+    SenderStopped,
 }
 
 #[derive(Debug, Error)]
@@ -67,145 +135,238 @@ pub enum ConnectionError {
     Handshake(#[from] handshake::Error),
 }
 
-#[derive(Debug)]
-pub struct Config {
-    pub drone_addr: IpAddr,
-    /// Wheather or not to set after connecting (by sending a frame) the current DateTime to the Drone:
-    ///
-    /// ```rust
-    /// use chrono::{DateTime, Utc};
-    /// let now: DateTime<Utc> = Utc::now();
-    /// ```
-    pub send_datetime: bool,
-}
-
-impl<I> From<I> for Config
-where
-    I: Into<IpAddr>,
-{
-    fn from(ip: I) -> Self {
-        Self {
-            drone_addr: ip.into(),
-            send_datetime: false,
-        }
-    }
-}
-
 #[derive(Clone, Debug)]
+/// The connection is closed with [`Drone::disconnect`] or when the last clone is dropped.
 pub struct Drone {
     inner: Arc<DroneInner>,
 }
 
 #[derive(Debug)]
 struct DroneInner {
-    // Each frame::BufferID gets its own sequence_id
-    sequence_ids: DashMap<frame::BufferID, u8>,
-    sender: SyncSender<Vec<u8>>,
+    session: Arc<Session>,
+    threads: Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl Drone {
-    /// Connects to a drone
-    ///
-    /// * Spawns Listener at LISTENER_PORT
-    /// * Performs Handshake at INIT_PORT
-    /// * Spawns Command sender at `c2d_port`
+    /// Connects to a drone over UDP, see [`UdpTransport::connect`]
     pub fn connect(config: Config) -> Result<Self, ConnectionError> {
-        let local_ip = local_ip(config.drone_addr)
-            .ok_or_else(|| ConnectionError::DroneAddr(config.drone_addr))?;
+        let transport = UdpTransport::connect(&config)?;
+
+        Self::with_transport(config, transport)
+    }
 
-        // @TODO: Check if we're going to miss any messages between spawning the listener and the receiver of commands
-        let (tx_cmd, rx_cmd) = sync_channel(200);
+    /// Connects to a drone over an already set up [`Transport`]
+    ///
+    /// * Spawns Listener
+    /// * Spawns Command sender
+    pub fn with_transport(
+        config: Config,
+        transport: impl Transport,
+    ) -> Result<Self, ConnectionError> {
+        let session = Arc::new(Session::new(&config, Arc::new(transport)));
 
         let drone = Self {
             inner: Arc::new(DroneInner {
-                sequence_ids: DashMap::new(),
-                sender: tx_cmd,
+                session: session.clone(),
+                threads: Mutex::new(Vec::new()),
             }),
         };
 
-        let local_listener = SocketAddr::new(local_ip, LISTEN_PORT);
-        info!("{}: Spawning Listener", &&local_listener);
+        info!("Spawning Listener");
+        let listener = session.listener();
+        drone
+            .inner
+            .add_thread(std::thread::spawn(move || listener.listen()));
+
+        info!("Spawning CMD Sender");
+        let cmd_sender = session.command_sender();
+        drone
+            .inner
+            .add_thread(std::thread::spawn(move || cmd_sender.run()));
+
+        let session = session.downgrade();
+        drone.inner.add_thread(spawn_ticks(
+            session.clone(),
+            RETRANSMIT_INTERVAL,
+            |session| session.retransmit(Instant::now()),
+        ));
+        // PINGs the Drone to measure the round-trip time
+        drone
+            .inner
+            .add_thread(spawn_ticks(session.clone(), PING_INTERVAL, |session| {
+                session.ping()
+            }));
+        // sends nothing until the piloting is started
+        drone
+            .inner
+            .add_thread(spawn_ticks(session.clone(), PILOTING_INTERVAL, |session| {
+                session.pilot()
+            }));
+        drone
+            .inner
+            .add_thread(spawn_ticks(session, WATCHDOG_INTERVAL, |session| {
+                if let Some(reconnection) = session.check_connection(Instant::now()) {
+                    // not joined, so disconnecting doesn't wait for the handshake
+                    std::thread::spawn(move || reconnection.run());
+                }
+            }));
+
+        if config.send_datetime {
+            drone.send_datetime(Utc::now())?;
+        }
 
-        spawn_listener(drone.clone(), local_listener)?;
+        Ok(drone)
+    }
 
-        let init_addr = SocketAddr::new(config.drone_addr, INIT_PORT);
+    pub(crate) fn session(&self) -> &Session {
+        &self.inner.session
+    }
 
-        info!("Init address {}", &init_addr);
+    /// Disconnects from the Drone
+    ///
+    /// * Sends the Common `NetworkEvent::Disconnection` frame, see [`Config::send_disconnection`]
+    /// * Stops the spawned threads and waits for them to finish, which closes the sockets
+    ///
+    /// A reconnection in progress isn't waited for, the sockets are closed once its handshake is done.
+    ///
+    /// Every clone of the Drone gets disconnected and any further sending
+    /// fails with [`Error::Disconnected`].
+    pub fn disconnect(&self) {
+        self.session().shutdown();
+
+        let threads = std::mem::take(&mut *self.inner.lock_threads());
+        for thread in threads {
+            if thread.join().is_err() {
+                error!("A Drone thread panicked");
+            }
+        }
+    }
 
-        let handshake_response = perform_handshake(init_addr, local_listener.port())?;
-        let cmd_sender_target = SocketAddr::new(config.drone_addr, handshake_response.c2d_port);
+    pub fn is_connected(&self) -> bool {
+        self.session().is_connected()
+    }
 
-        info!("{}: Spawning CMD Sender", cmd_sender_target);
+    /// The current [`ConnectionState`], see [`Config::connection_timeout`]
+    pub fn connection_state(&self) -> ConnectionState {
+        self.session().connection_state()
+    }
 
-        spawn_cmd_sender(rx_cmd, local_ip, cmd_sender_target)?;
+    /// When we last received a frame from the Drone
+    pub fn last_received(&self) -> Instant {
+        self.session().last_received()
+    }
 
-        if config.send_datetime {
-            drone.send_datetime(Utc::now())?;
-        }
+    /// The `status`, `qos_mode`, `proto_v`, the ports & the arstream parameters
+    /// the Drone answered in the handshake, `None` if the [`Transport`] has no handshake
+    pub fn handshake_response(&self) -> Option<handshake::Response> {
+        self.session().handshake_response()
+    }
 
-        Ok(drone)
+    /// The received, duplicated & missed frames on the `buffer_id`
+    pub fn receive_stats(&self, buffer_id: frame::BufferID) -> ReceiveStats {
+        self.session().receive_stats(buffer_id)
     }
 
-    pub fn send_frame(&self, frame: frame::Frame) -> Result<(), Error> {
-        let mut raw_message = [0_u8; 2048];
-        let written = frame.try_into_ctx(&mut raw_message, LE)?;
+    /// A snapshot of the link quality: round-trip time, ack latency, retransmissions,
+    /// throughput and the received frames per `BufferID`
+    pub fn stats(&self) -> Stats {
+        self.session().stats()
+    }
 
-        self.send_raw_message(&raw_message[0..written])
+    /// Starts sending the `command` with the current [`Setpoint`] every [`PILOTING_INTERVAL`]
+    pub fn start_piloting(&self, command: PilotingCommand) {
+        self.session().pilot.start(command)
     }
 
-    pub fn send_raw_message(&self, raw_message: &[u8]) -> Result<(), Error> {
-        Ok(self.inner.sender.send(raw_message.to_vec())?)
+    /// Stops the piloting loop and resets the [`Setpoint`]
+    pub fn stop_piloting(&self) {
+        self.session().pilot.stop()
     }
 
-    pub fn send_datetime(&self, date: DateTime<Utc>) -> Result<(), Error> {
-        use command::Feature::Common;
-        use common::Class;
-        use frame::{BufferID, Type};
+    pub fn setpoint(&self) -> Setpoint {
+        self.session().pilot.setpoint()
+    }
 
-        let date_feature = Common(Some(Class::Common(common::Common::CurrentDate(date))));
+    pub fn set_setpoint(&self, setpoint: Setpoint) {
+        self.session().pilot.update(|current| *current = setpoint)
+    }
 
-        let frame = Frame::for_drone(
-            &self,
-            Type::DataWithAck,
-            BufferID::CDAck,
-            Some(date_feature),
-        );
+    pub fn set_roll(&self, roll: i8) {
+        self.session().pilot.update(|setpoint| setpoint.roll = roll)
+    }
 
-        self.send_frame(frame)?;
+    pub fn set_pitch(&self, pitch: i8) {
+        self.session()
+            .pilot
+            .update(|setpoint| setpoint.pitch = pitch)
+    }
 
-        let time_feature = Common(Some(Class::Common(common::Common::CurrentTime(date))));
-        let frame = Frame::for_drone(
-            &self,
-            Type::DataWithAck,
-            BufferID::CDAck,
-            Some(time_feature),
-        );
+    pub fn set_yaw(&self, yaw: i8) {
+        self.session().pilot.update(|setpoint| setpoint.yaw = yaw)
+    }
 
-        self.send_frame(frame)
+    pub fn set_gaz(&self, gaz: i8) {
+        self.session().pilot.update(|setpoint| setpoint.gaz = gaz)
     }
 
-    fn send_pong(&self, feature: Option<command::Feature>) -> Result<(), Error> {
-        let frame_type = frame::Type::Data;
-        let buffer_id = frame::BufferID::PONG;
+    /// Subscribes to all the [`Event`]s of the Drone
+    pub fn subscribe(&self) -> Receiver<Event> {
+        self.subscribe_with(Filter::default())
+    }
 
-        // send the same feature back
-        let pong = frame::Frame::for_drone(&self, frame_type, buffer_id, feature);
+    /// Subscribes to the [`Event`]s of the Drone that match the `filter`
+    pub fn subscribe_with(&self, filter: Filter) -> Receiver<Event> {
+        self.session().subscribers.subscribe(filter)
+    }
 
-        self.send_frame(pong)
+    /// Sends the frame without waiting for it to be acknowledged.
+    ///
+    /// `Type::DataWithAck` frames are still retransmitted until acknowledged,
+    /// see [`Config::ack_retries`].
+    ///
+    /// The frames are sent by the Command sender, its failures are published as
+    /// `Event::SendFailed`.
+    pub fn send_frame(&self, frame: frame::Frame) -> Result<(), Error> {
+        self.session().send_frame(frame, None)
+    }
+
+    /// Sends a `Type::DataWithAck` frame and blocks until the Drone acknowledges it
+    /// or all the retransmissions timed out.
+    ///
+    /// Fails with `Error::Send` if the Command sender couldn't send it.
+    pub fn send_frame_with_ack(&self, frame: frame::Frame) -> Result<DeliveryStatus, Error> {
+        let (tx, rx) = sync_channel(1);
+
+        self.session()
+            .send_frame_with_ack(frame, Notifier::Sync(tx))?;
+
+        // the notifier is dropped without a status if the frame gets replaced
+        rx.recv().unwrap_or(Ok(DeliveryStatus::Timeout))
+    }
+
+    pub fn send_raw_message(&self, raw_message: &[u8]) -> Result<(), Error> {
+        self.session().send_raw_message(raw_message)
+    }
+
+    pub fn send_datetime(&self, date: DateTime<Utc>) -> Result<(), Error> {
+        self.session().send_datetime(date)
     }
 }
 
 impl DroneInner {
-    pub(crate) fn sequence_id(&self, buffer_id: frame::BufferID) -> u8 {
-        if let Some(mut sequence_id) = self.sequence_ids.get_mut(&buffer_id) {
-            let command_id = *sequence_id;
-            *sequence_id = sequence_id.overflowing_add(1).0;
-
-            command_id
-        } else {
-            self.sequence_ids.insert(buffer_id, 0);
-            0
-        }
+    fn add_thread(&self, thread: JoinHandle<()>) {
+        self.lock_threads().push(thread);
+    }
+
+    fn lock_threads(&self) -> std::sync::MutexGuard<'_, Vec<JoinHandle<()>>> {
+        self.threads.lock().expect("Threads lock poisoned")
+    }
+}
+
+impl Drop for DroneInner {
+    fn drop(&mut self) {
+        // The threads only hold a `WeakSession`, so they stop on their own
+        self.session.shutdown();
     }
 }
 
@@ -218,20 +379,59 @@ fn local_ip(target: IpAddr) -> Option<IpAddr> {
         .next()
 }
 
-fn spawn_listener(drone: Drone, addr: SocketAddr) -> Result<(), ConnectionError> {
-    let listener_socket =
-        UdpSocket::bind(addr).map_err(|error| ConnectionError::Io { error, addr })?;
+/// Binds the d2c socket, every Drone gets its own.
+///
+/// If the default `LISTEN_PORT` is already taken, e.g. by another Drone of this process,
+/// an ephemeral port is used instead.
+pub(crate) fn bind_d2c(addr: SocketAddr) -> Result<UdpSocket, ConnectionError> {
+    match UdpSocket::bind(addr) {
+        Err(error) if error.kind() == ErrorKind::AddrInUse && addr.port() == LISTEN_PORT => {
+            let ephemeral = SocketAddr::new(addr.ip(), 0);
+            warn!("{} is already in use, listening on an ephemeral port", addr);
+
+            UdpSocket::bind(ephemeral).map_err(|error| ConnectionError::Io {
+                error,
+                addr: ephemeral,
+            })
+        }
+        result => result.map_err(|error| ConnectionError::Io { error, addr }),
+    }
+}
 
-    std::thread::spawn(move || {
-        let listener = Listener {
-            drone: drone.clone(),
-            socket: listener_socket,
-        };
+/// Calls `tick` every `interval` until the Drone is disconnected
+fn spawn_ticks(
+    weak_session: WeakSession,
+    interval: Duration,
+    tick: impl Fn(&Arc<Session>) + Send + 'static,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || loop {
+        if !sleep_while_connected(&weak_session, interval) {
+            break;
+        }
+
+        match weak_session.upgrade() {
+            Some(session) => tick(&session),
+            None => break,
+        }
+    })
+}
 
-        listener.listen();
-    });
+/// Returns `false` if the Drone got disconnected in the meantime
+fn sleep_while_connected(weak_session: &WeakSession, duration: Duration) -> bool {
+    let until = Instant::now() + duration;
 
-    Ok(())
+    loop {
+        let now = Instant::now();
+        if now >= until {
+            return weak_session.upgrade().is_some();
+        }
+
+        if weak_session.upgrade().is_none() {
+            return false;
+        }
+
+        std::thread::sleep(SHUTDOWN_POLL_INTERVAL.min(until - now));
+    }
 }
 
 pub(crate) fn print_buf(buf: &[u8]) -> String {
@@ -241,56 +441,15 @@ pub(crate) fn print_buf(buf: &[u8]) -> String {
         .join(" ")
 }
 
-fn spawn_cmd_sender(
-    rx: Receiver<Vec<u8>>,
-    local_ip: IpAddr,
-    target_addr: SocketAddr,
-) -> Result<(), ConnectionError> {
-    let local_addr = SocketAddr::new(local_ip, target_addr.port());
-
-    let socket = UdpSocket::bind(local_addr).map_err(|error| ConnectionError::Io {
-        error,
-        addr: local_addr,
-    })?;
-
-    std::thread::spawn(move || loop {
-        let frame_to_send = match rx.recv() {
-            Ok(frame) => frame,
-            Err(err) => {
-                error!("Receiving Frame for sending failed: {:?}", &err);
-                continue;
-            }
-        };
-
-        info!("Frame to sent: {:?}", &frame_to_send);
-
-        let frame = frame_to_send.pread_with::<Frame>(0, LE);
-
-        info!(
-            "Sent Frame (length: {}) => {:?}",
-            frame_to_send.len(),
-            &frame
-        );
-
-        let size = socket
-            .send_to(&frame_to_send, target_addr)
-            .expect("something terrible happened");
-
-        assert_eq!(size, frame_to_send.len())
-    });
-
-    Ok(())
-}
-
 /// we receive 2 frames sometimes
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::parse::parse_message_frames;
-    use ardrone3::ArDrone3;
+    use ardrone3::{piloting_state, ArDrone3};
     use command::Feature;
-    use frame::{BufferID, Type};
+    use frame::{BufferID, Frame, Type};
 
     #[test]
     fn receiving_two_frames_at_once() {
@@ -307,7 +466,8 @@ mod test {
             // Frame size: 35
             // ArDrone3 - Feature - 1
             // Piloting State - 4
-            // Data?
+            // PositionChanged - 4
+            // 500.0 (f64) for the unavailable latitude, longitude & altitude
             2, 127, 71, 35, 0, 0, 0, 1, 4, 4, 0, 0, 0, 0, 0, 0, 64, 127, 64, 0, 0, 0, 0, 0, 64, 127,
             64, 0, 0, 0, 0, 0, 64, 127, 64,
         ];
@@ -333,10 +493,11 @@ mod test {
             buffer_id: BufferID::DCNavdata,
             sequence_id: 71,
             feature: Some(Feature::ArDrone3(Some(ArDrone3::PilotingState(
-                ardrone3::PilotingState::Unknown {
-                    piloting_state: 4,
-                    data: received[34..].to_vec(),
-                },
+                piloting_state::Command::PositionChanged(piloting_state::PositionChanged {
+                    latitude: 500.0,
+                    longitude: 500.0,
+                    altitude: 500.0,
+                }),
             )))),
         };
 
@@ -354,6 +515,37 @@ mod test {
         );
     }
 
+    #[test]
+    fn piloting_runs_in_a_single_thread() {
+        let (controller, _drone) = transport::MemoryTransport::pair();
+        let config = Config::builder(Ipv4Addr::LOCALHOST)
+            .send_disconnection(false)
+            .build();
+        let drone = Drone::with_transport(config, controller).expect("Should connect");
+        let threads = drone.inner.lock_threads().len();
+
+        for _ in 0..3 {
+            drone.start_piloting(PilotingCommand::PCMD);
+            drone.stop_piloting();
+        }
+        drone.start_piloting(PilotingCommand::PCMD);
+
+        assert_eq!(threads, drone.inner.lock_threads().len());
+    }
+
+    #[test]
+    fn every_drone_gets_its_own_d2c_port() {
+        let default_addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), LISTEN_PORT);
+
+        let first = bind_d2c(default_addr).expect("Should bind the first d2c socket");
+        let second = bind_d2c(default_addr).expect("Should fall back to an ephemeral port");
+
+        let first_port = first.local_addr().expect("Should have an address").port();
+        let second_port = second.local_addr().expect("Should have an address").port();
+
+        assert_ne!(first_port, second_port);
+    }
+
     #[test]
     #[ignore]
     fn receiving_two_frames_at_once_2() {
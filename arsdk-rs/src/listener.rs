@@ -1,22 +1,42 @@
-use crate::{parse::handle_bytes, print_buf, Drone};
-use log::debug;
-use std::net::UdpSocket;
+use crate::{
+    frame::MAX_DATAGRAM_SIZE, print_buf, session::WeakSession, transport::Transport,
+    SHUTDOWN_POLL_INTERVAL,
+};
+use log::{debug, error};
+use std::sync::Arc;
 
+/// Receives the datagrams from the [`Transport`], see [`Session::listener`](crate::session::Session::listener)
+#[derive(Debug)]
 pub struct Listener {
-    pub drone: Drone,
-    pub socket: UdpSocket,
+    pub(crate) session: WeakSession,
+    pub(crate) transport: Arc<dyn Transport>,
 }
 
 impl Listener {
-    /// Blocking listener in a infinite loop
+    /// Blocking listener in a loop, until the Drone is disconnected
     pub fn listen(&self) {
+        let mut buf = vec![0_u8; MAX_DATAGRAM_SIZE];
+
         loop {
-            let mut buf = [0_u8; 256];
-            if let Ok((bytes_read, origin)) = self.socket.recv_from(&mut buf) {
-                debug!("Received: {} bytes from {}", bytes_read, origin);
-                debug!("Bytes: {}", print_buf(&buf[..bytes_read]));
+            let received = self.transport.recv(&mut buf);
+
+            let session = match self.session.upgrade() {
+                Some(session) => session,
+                None => break,
+            };
+
+            match received {
+                Ok(Some(bytes_read)) => {
+                    debug!("Bytes: {}", print_buf(&buf[..bytes_read]));
 
-                handle_bytes(&self.drone, &buf[..bytes_read]);
+                    session.receive(&buf[..bytes_read]);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    error!("Receiving failed: {}", err);
+                    // don't spin on a broken transport
+                    std::thread::sleep(SHUTDOWN_POLL_INTERVAL);
+                }
             }
         }
     }
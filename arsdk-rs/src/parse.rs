@@ -1,95 +1,256 @@
 use crate::{
-    command::Feature,
-    frame::{BufferID, Error, Frame, Type},
-    print_buf, Drone, FrameType,
+    ack::AckTracker,
+    event::Subscribers,
+    frame::{BufferID, Error, FeatureRef, FrameRef, Type, HEADER_LENGTH},
+    print_buf,
+    receive::ReceiveWindows,
+    stats::LinkStats,
+    FrameType,
 };
 use log::{error, info};
 use scroll::{Pread, Pwrite, LE};
 
-/// - Parses Frames
-/// - Sends PING response to cmd Sender
-/// - Logs unknown frames
-pub(crate) fn handle_bytes(drone: &Drone, raw_frames: &[u8]) {
-    let frames = parse_message_frames(&raw_frames);
-
-    for result in frames.iter() {
-        match result {
-            Ok(FrameType::Known(frame)) => info!("Frame: {:?}", frame),
-            Ok(FrameType::Unknown(unknown)) => {
-                info!("Unknown Frame: {:?}", unknown);
-                info!("Bytes: {}", print_buf(raw_frames));
-            }
-            Err(err) => error!("Receiving Frame: {:?}", err),
-        }
-    }
+/// The parts of the [`Session`](crate::session::Session) which handle the received Frames
+pub(crate) struct Link<'a> {
+    pub(crate) acks: &'a AckTracker,
+    pub(crate) stats: &'a LinkStats,
+    pub(crate) subscribers: &'a Subscribers,
+    pub(crate) received: &'a ReceiveWindows,
+}
 
-    for result in frames.iter() {
-        match result {
-            // PING-PONG
-            Ok(FrameType::Known(frame)) if frame.buffer_id == BufferID::PING => {
-                if let Err(err) = drone.send_pong(frame.feature.clone()) {
-                    error!("Sending Frame to Commander: {}", err)
+impl Link<'_> {
+    /// - Parses the Frames of the datagram as [`FrameRef`]s, without decoding their Features
+    /// - Updates the link stats & the acknowledged frames
+    /// - Publishes the new Frames, dropping the duplicates
+    /// - Passes the PONGs & Acks we need to send back to `respond`
+    /// - Logs unknown frames
+    ///
+    /// The Features are only decoded for the subscribers whose filter matches them,
+    /// so nothing gets allocated for the frames nobody is interested in.
+    ///
+    /// `sequence_id` returns the next sequence id for the given `BufferID`
+    pub(crate) fn receive(
+        &self,
+        datagram: &[u8],
+        mut sequence_id: impl FnMut(BufferID) -> u8,
+        mut respond: impl FnMut(FrameRef<'_>),
+    ) {
+        let mut frames = 0;
+
+        for raw_frame in raw_frames(datagram) {
+            frames += 1;
+
+            let raw_frame = match raw_frame {
+                Ok(raw_frame) => raw_frame,
+                Err(err) => {
+                    error!("Receiving Frame: {:?}", err);
+                    continue;
                 }
-            }
-            // Data that needs Ack coming from the Drone
-            Ok(FrameType::Known(frame)) if frame.buffer_id == BufferID::DCNavdata => {
-                let ack_type = Type::Ack;
-                let ack_buffer = BufferID::CDAck;
-                let ack_sequence_id = frame.sequence_id;
-                let feature = frame.feature.as_ref().and_then(|feature| {
-                    match feature {
-                        // we return an empty ArDrone3 as the drone does for our Frames
-                        Feature::ArDrone3(_) => Some(Feature::ArDrone3(None)),
-                        //
-                        _ => None,
+            };
+
+            match raw_frame.pread_with::<FrameRef>(0, LE) {
+                Ok(frame) => {
+                    info!("Frame: {:?}", frame);
+                    self.receive_frame(&frame, raw_frame);
+
+                    // the duplicates are acknowledged again, as our previous Ack might have been lost
+                    if let Some(response) = response(&frame, &mut sequence_id) {
+                        respond(response);
                     }
-                });
-
-                let ack_frame = Frame {
-                    frame_type: ack_type,
-                    buffer_id: ack_buffer,
-                    feature,
-                    sequence_id: ack_sequence_id,
-                };
-                if let Err(err) = drone.send_frame(ack_frame) {
-                    error!("Sending Frame to Commander: {}", err)
                 }
+                Err(err @ Error::OutOfBound { .. }) => {
+                    info!("Unknown Frame: {}", err);
+                    info!("Bytes: {}", print_buf(raw_frame));
+
+                    self.subscribers.publish_unknown_frame(raw_frame);
+                }
+                Err(err) => error!("Receiving Frame: {:?}", err),
             }
-            // we handled the error cases in the upper portion of the function
-            _ => {}
+        }
+
+        self.stats.received(datagram.len(), frames);
+    }
+
+    fn receive_frame(&self, frame: &FrameRef<'_>, raw_frame: &[u8]) {
+        if let (BufferID::PONG, Some(feature)) = (frame.buffer_id, &frame.feature) {
+            self.stats.ponged(feature);
+        }
+
+        if let Some((buffer_id, sequence_id)) = acknowledgement(frame) {
+            match self.acks.acknowledge(buffer_id, sequence_id) {
+                Some(latency) => self.stats.acknowledged(latency),
+                None => info!(
+                    "Ack for {} - {} which is not pending",
+                    buffer_id, sequence_id
+                ),
+            }
+        }
+
+        if self.received.accept(frame) {
+            self.subscribers.publish_frame(frame, raw_frame);
         }
     }
 }
 
-/// Parses the Frames from a buffer
-pub(crate) fn parse_message_frames(buf: &[u8]) -> Vec<Result<FrameType, Error>> {
-    let mut offset = 0;
-    // TODO: Check how many frames can we receive at once
-    // reasonable given that we receive at most (MAYBE?!) 2 frames
-    let mut frames = Vec::with_capacity(3);
+/// Returns the `BufferID` & `sequence_id` of our frame that the Drone acknowledged
+/// if `frame` is an Ack.
+pub(crate) fn acknowledgement(frame: &FrameRef<'_>) -> Option<(BufferID, u8)> {
+    let sequence_id = frame.acknowledged_sequence_id()?;
+
+    Some((frame.buffer_id.acknowledged_buffer()?, sequence_id))
+}
+
+/// Builds the Frame we need to send back to the Drone for the received `frame`:
+///
+/// - PONG for a PING, echoing its bytes
+/// - Ack for a `Type::DataWithAck` frame, on the buffer `128 + buffer id`
+///
+/// `sequence_id` returns the next sequence id for the given `BufferID`
+pub(crate) fn response<'a>(
+    frame: &'a FrameRef<'_>,
+    sequence_id: impl FnOnce(BufferID) -> u8,
+) -> Option<FrameRef<'a>> {
+    // PING-PONG
+    if frame.buffer_id == BufferID::PING {
+        return Some(FrameRef {
+            frame_type: Type::Data,
+            buffer_id: BufferID::PONG,
+            sequence_id: sequence_id(BufferID::PONG),
+            feature: frame.feature,
+        });
+    }
 
-    let mut tried = 1;
-    // try to read all the buf length & limit to 3 Frames of read
-    while offset != buf.len() && tried <= 3 {
-        let frame = buf.gread_with(&mut offset, LE);
+    // Data that needs Ack coming from the Drone, on any buffer
+    if frame.frame_type == Type::DataWithAck {
+        let ack_buffer = frame.buffer_id.ack_buffer()?;
 
-        frames.push(frame);
-        tried += 1;
+        // its only data byte is the acknowledged sequence id
+        return Some(FrameRef {
+            frame_type: Type::Ack,
+            buffer_id: ack_buffer,
+            sequence_id: sequence_id(ack_buffer),
+            feature: FeatureRef::new(std::slice::from_ref(&frame.sequence_id)),
+        });
     }
 
-    frames
+    None
+}
+
+/// Splits a datagram into its Frames, using the length in their header
+///
+/// A Frame which fails to parse doesn't prevent parsing the following ones, as its header
+/// tells where the next one starts. The splitting stops at the first invalid or truncated header.
+pub fn raw_frames(datagram: &[u8]) -> impl Iterator<Item = Result<&[u8], Error>> {
+    let mut remaining = datagram;
+
+    std::iter::from_fn(move || {
+        if remaining.is_empty() {
+            return None;
+        }
+
+        match frame_length(remaining) {
+            Ok(frame_length) => {
+                let (raw_frame, rest) = remaining.split_at(frame_length);
+                remaining = rest;
+
+                Some(Ok(raw_frame))
+            }
+            Err(err) => {
+                remaining = &[];
+
+                Some(Err(err))
+            }
+        }
+    })
+}
+
+/// Parses all the Frames of a datagram, see [`raw_frames`]
+pub fn parse_message_frames(buf: &[u8]) -> Vec<Result<FrameType, Error>> {
+    raw_frames(buf)
+        .map(|raw_frame| raw_frame.and_then(|raw_frame| raw_frame.pread_with(0, LE)))
+        .collect()
+}
+
+/// The length of the first Frame of `buf`, header included, read from its header
+fn frame_length(buf: &[u8]) -> Result<usize, Error> {
+    if buf.len() < HEADER_LENGTH {
+        return Err(Error::Truncated {
+            expected: HEADER_LENGTH,
+            actual: buf.len(),
+        });
+    }
+
+    let frame_length = buf.pread_with::<u32>(3, LE)? as usize;
+
+    if frame_length < HEADER_LENGTH {
+        return Err(Error::BytesLength {
+            expected: HEADER_LENGTH as u32,
+            actual: frame_length as u32,
+        });
+    }
+
+    if frame_length > buf.len() {
+        return Err(Error::Truncated {
+            expected: frame_length,
+            actual: buf.len(),
+        });
+    }
+
+    Ok(frame_length)
 }
 
 /// Helper function to read unknown / not implemented Frames
 pub(crate) fn read_unknown(src: &[u8], offset: &mut usize) -> Result<Vec<u8>, crate::frame::Error> {
-    let mut feature_data = [0_u8; 256];
-    let actual_written = feature_data.gwrite_with(&src[*offset..], &mut 0, ())?;
+    let data = src.get(*offset..).unwrap_or_default().to_vec();
 
-    assert_eq!(actual_written, feature_data[..actual_written].len());
+    *offset += data.len();
 
-    *offset += actual_written;
+    Ok(data)
+}
 
-    Ok(feature_data[..actual_written].to_vec())
+/// Reads a NUL terminated string, the NUL is consumed but it's not part of the string
+pub(crate) fn read_string(src: &[u8], offset: &mut usize) -> Result<String, Error> {
+    let rest = src.get(*offset..).unwrap_or_default();
+    let length = rest
+        .iter()
+        .position(|byte| *byte == 0)
+        .ok_or(scroll::Error::BadInput {
+            size: rest.len(),
+            msg: "missing the NUL terminator of the string",
+        })?;
+    let string = std::str::from_utf8(&rest[..length]).map_err(|_| scroll::Error::BadInput {
+        size: length,
+        msg: "invalid UTF-8 string",
+    })?;
+
+    *offset += length + 1;
+
+    Ok(string.to_string())
+}
+
+/// Writes a NUL terminated string
+pub(crate) fn write_string(this: &mut [u8], string: &str, offset: &mut usize) -> Result<(), Error> {
+    if string.contains('\0') {
+        return Err(scroll::Error::BadInput {
+            size: string.len(),
+            msg: "NUL inside of the string",
+        }
+        .into());
+    }
+
+    write_bytes(this, string.as_bytes(), offset)?;
+    this.gwrite_with::<u8>(0, offset, LE)?;
+
+    Ok(())
+}
+
+/// Unlike `gwrite`, writing no bytes at the end of the buffer is not an error
+pub(crate) fn write_bytes(this: &mut [u8], bytes: &[u8], offset: &mut usize) -> Result<(), Error> {
+    if !bytes.is_empty() {
+        this.gwrite_with(bytes, offset, ())?;
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -100,6 +261,18 @@ mod parse_message_frames {
         command::Feature,
         frame::{BufferID, Frame, FrameType, Type},
     };
+
+    /// The Frames of `buf` which have a valid header
+    fn frame_refs(buf: &[u8]) -> Vec<FrameRef<'_>> {
+        raw_frames(buf)
+            .filter_map(|raw_frame| raw_frame.ok()?.pread_with(0, LE).ok())
+            .collect()
+    }
+
+    fn acknowledgements(buf: &[u8]) -> Vec<(BufferID, u8)> {
+        frame_refs(buf).iter().filter_map(acknowledgement).collect()
+    }
+
     #[test]
     fn test_parsable_messages() {
         let jump_message: [u8; 15] = [
@@ -110,15 +283,19 @@ mod parse_message_frames {
             frame_type: Type::DataWithAck,
             buffer_id: BufferID::CDAck,
             sequence_id: 1,
-            feature: Some(Feature::JumpingSumo(js::Class::Animations(js::Anim::Jump))),
+            feature: Some(Feature::JumpingSumo(js::Class::Animations(
+                js::animations::Command::Jump(js::animations::Jump {
+                    r#type: js::animations::JumpType::Long,
+                }),
+            ))),
         };
 
         let move_message: [u8; 14] = [
             0x2, 0xa, 0x67, 0xe, 0x0, 0x0, 0x0, 0x3, 0x0, 0x0, 0x0, 0x1, 0x0, 0x9c,
         ];
 
-        let pilot_state = js::PilotState {
-            flag: true,
+        let pcmd = js::piloting::Pcmd {
+            flag: 1,
             speed: 0,
             turn: -100,
         };
@@ -128,7 +305,7 @@ mod parse_message_frames {
             buffer_id: BufferID::CDNonAck,
             sequence_id: 103,
             feature: Some(Feature::JumpingSumo(js::Class::Piloting(
-                js::PilotingID::Pilot(pilot_state),
+                js::piloting::Command::Pcmd(pcmd),
             ))),
         };
 
@@ -152,14 +329,75 @@ mod parse_message_frames {
     }
 
     #[test]
-    fn test_feature_common_none() {
+    fn test_acknowledgements() {
+        // ArDrone3 ack of sequence id 1 & Jumping Sumo ack of sequence id 3
+        let buf: [u8; 16] = [1, 139, 0, 8, 0, 0, 0, 1, 1, 139, 1, 8, 0, 0, 0, 3];
+
+        assert_eq!(
+            vec![(BufferID::CDAck, 1), (BufferID::CDAck, 3)],
+            acknowledgements(&buf)
+        );
+    }
+
+    #[test]
+    fn test_responses() {
+        let buf: [u8; 42] = [
+            // DataWithAck on DCEvent, sequence 7
+            // ArDrone3 - PilotingState - FlyingStateChanged - 2
+            4, 126, 7, 15, 0, 0, 0, 1, 4, 1, 0, 2, 0, 0, 0,
+            // Data on DCNavdata, sequence 8, which is not acknowledged
+            2, 127, 8, 8, 0, 0, 0, 1, // DataWithAck on DCNavdata, sequence 9
+            4, 127, 9, 8, 0, 0, 0, 1, // PING, sequence 3
+            2, 0, 3, 11, 0, 0, 0, 155, 216, 221, 13,
+        ];
+
+        let frames = frame_refs(&buf);
+        let responses: Vec<FrameRef> = frames
+            .iter()
+            .filter_map(|frame| response(frame, |_| 42))
+            .collect();
+
+        // Type::Ack on 128 + buffer id, carrying the acknowledged sequence id,
+        // and the PONG echoing the PING
+        let raw_responses: Vec<Vec<u8>> = responses
+            .iter()
+            .map(|response| {
+                let mut raw_response = [0_u8; 16];
+                let written = raw_response
+                    .pwrite_with(*response, 0, LE)
+                    .expect("Should write");
+
+                raw_response[..written].to_vec()
+            })
+            .collect();
+
+        assert_eq!(
+            vec![
+                vec![1, 254, 42, 8, 0, 0, 0, 7],
+                vec![1, 255, 42, 8, 0, 0, 0, 9],
+                vec![2, 1, 42, 11, 0, 0, 0, 155, 216, 221, 13],
+            ],
+            raw_responses
+        );
+
+        // the PONG borrows the bytes of the PING
+        let pong = responses[2].feature.expect("Should echo the PING");
+        assert!(std::ptr::eq(&buf[38..], pong.as_bytes()));
+    }
+
+    #[test]
+    fn test_ack_is_not_decoded_as_a_feature() {
+        // acknowledges the sequence id 0, which is not `Feature::Common(None)`
         let buf: [u8; 8] = [1, 139, 0, 8, 0, 0, 0, 0];
 
         let frame = Frame {
             frame_type: Type::Ack,
-            buffer_id: BufferID::ACKFromSendWithAck,
+            buffer_id: BufferID::Ack(11),
             sequence_id: 0,
-            feature: Some(Feature::Common(None)),
+            feature: Some(Feature::Unknown {
+                feature: 0,
+                data: vec![],
+            }),
         };
 
         let actual = parse_message_frames(&buf);
@@ -231,4 +469,61 @@ mod parse_message_frames {
             assert_eq!(expected, &actual);
         }
     }
+
+    #[test]
+    fn test_many_frames_and_truncated_trailing_frame() {
+        // 100 Acks, more than the 256 bytes & 3 frames we used to read
+        let mut buf: Vec<u8> = (0..100_u8)
+            .flat_map(|sequence_id| vec![1, 139, sequence_id, 8, 0, 0, 0, sequence_id])
+            .collect();
+        // a trailing frame of 20 bytes with only 10 of them
+        buf.extend_from_slice(&[4, 126, 1, 20, 0, 0, 0, 0, 14, 1]);
+
+        let mut frames = parse_message_frames(&buf);
+
+        assert_eq!(101, frames.len());
+        match frames.pop() {
+            Some(Err(Error::Truncated {
+                expected: 20,
+                actual: 10,
+            })) => {}
+            other => panic!("Expected a truncated Frame, got: {:?}", other),
+        }
+        assert_eq!(100, acknowledgements(&buf).len());
+
+        // an incomplete header
+        let frames = parse_message_frames(&[2, 0, 1]);
+        assert!(matches!(
+            frames.as_slice(),
+            [Err(Error::Truncated {
+                expected: HEADER_LENGTH,
+                actual: 3
+            })]
+        ));
+    }
+
+    #[test]
+    fn test_large_frame_and_invalid_frame() {
+        let mut ping = vec![0_u8; 60_000];
+        ping[..HEADER_LENGTH].copy_from_slice(&[2, 0, 1, 0x60, 0xea, 0, 0]);
+        ping[HEADER_LENGTH] = 3;
+        // unknown frame type, the next frame is still parsed
+        let invalid = [9, 10, 1, 8, 0, 0, 0, 0];
+        let ack = [1, 139, 0, 8, 0, 0, 0, 1];
+
+        let buf = [&ping[..], &invalid[..], &ack[..]].concat();
+        let frames = parse_message_frames(&buf);
+
+        assert_eq!(3, frames.len());
+        match &frames[0] {
+            Ok(FrameType::Known(Frame {
+                buffer_id: BufferID::PING,
+                feature: Some(Feature::Unknown { feature: 3, data }),
+                ..
+            })) => assert_eq!(60_000 - HEADER_LENGTH - 1, data.len()),
+            other => panic!("Expected the PING, got: {:?}", other),
+        }
+        assert!(matches!(frames[1], Ok(FrameType::Unknown(_))));
+        assert_eq!(vec![(BufferID::CDAck, 1)], acknowledgements(&buf));
+    }
 }
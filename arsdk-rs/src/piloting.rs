@@ -0,0 +1,177 @@
+//! The periodic piloting loop
+//!
+//! Like the C SDK, the piloting command is sent every [`PILOTING_INTERVAL`] with the current
+//! [`Setpoint`], which callers can change at any time. The 8-bit sequence id & the timestamp
+//! of the `PCMD`s are handled by the loop.
+//!
+//! The loop runs as long as the Drone is connected and only sends while the piloting is started.
+use crate::{
+    ardrone3::{self, piloting, ArDrone3},
+    command::Feature,
+    jumping_sumo,
+};
+use chrono::Utc;
+use std::{sync::Mutex, time::Duration};
+
+pub const PILOTING_INTERVAL: Duration = Duration::from_millis(25);
+
+/// The piloting command sent by the loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PilotingCommand {
+    /// `ArDrone3` `piloting::Command::Pcmd`, e.g. for the Bebop 2
+    PCMD,
+    /// Jumping Sumo `piloting::Command::Pcmd`, the `pitch` is the speed and the `yaw` is the turn
+    PilotState,
+}
+
+/// The values are percentages in `[-100; 100]`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Setpoint {
+    pub roll: i8,
+    pub pitch: i8,
+    pub yaw: i8,
+    pub gaz: i8,
+}
+
+impl Setpoint {
+    fn feature(self, command: PilotingCommand, sequence_id: u8) -> Feature {
+        match command {
+            PilotingCommand::PCMD => {
+                Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::Pcmd(
+                    piloting::Pcmd {
+                        // roll & pitch are used only when the flag is set
+                        flag: (self.roll != 0 || self.pitch != 0).into(),
+                        roll: self.roll,
+                        pitch: self.pitch,
+                        yaw: self.yaw,
+                        gaz: self.gaz,
+                        timestamp_and_seq_num: ardrone3::timestamp_and_seq_num(
+                            Utc::now(),
+                            sequence_id,
+                        ),
+                    },
+                ))))
+            }
+            PilotingCommand::PilotState => Feature::JumpingSumo(jumping_sumo::Class::Piloting(
+                jumping_sumo::piloting::Command::Pcmd(jumping_sumo::piloting::Pcmd {
+                    flag: (self.pitch != 0 || self.yaw != 0).into(),
+                    speed: self.pitch,
+                    turn: self.yaw,
+                }),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// `None` when the loop is stopped
+    command: Option<PilotingCommand>,
+    setpoint: Setpoint,
+    sequence_id: u8,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Pilot {
+    state: Mutex<State>,
+}
+
+impl Pilot {
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("Piloting lock poisoned")
+    }
+
+    /// The loop sends the `command` from its next tick on
+    pub(crate) fn start(&self, command: PilotingCommand) {
+        self.lock_state().command = Some(command);
+    }
+
+    /// Stops the loop & resets the setpoint
+    pub(crate) fn stop(&self) {
+        let mut state = self.lock_state();
+        state.command = None;
+        state.setpoint = Setpoint::default();
+    }
+
+    pub(crate) fn setpoint(&self) -> Setpoint {
+        self.lock_state().setpoint
+    }
+
+    pub(crate) fn update(&self, update: impl FnOnce(&mut Setpoint)) {
+        update(&mut self.lock_state().setpoint)
+    }
+
+    /// The next piloting command to send, `None` while the loop is stopped
+    pub(crate) fn next_feature(&self) -> Option<Feature> {
+        let mut state = self.lock_state();
+
+        let command = state.command?;
+
+        let sequence_id = state.sequence_id;
+        state.sequence_id = sequence_id.wrapping_add(1);
+
+        Some(state.setpoint.feature(command, sequence_id))
+    }
+}
+
+#[cfg(test)]
+mod piloting_tests {
+    use super::*;
+
+    #[test]
+    fn test_pcmd_loop() {
+        let pilot = Pilot::default();
+
+        // nothing is sent until the loop is started
+        assert_eq!(None, pilot.next_feature());
+        pilot.start(PilotingCommand::PCMD);
+
+        pilot.update(|setpoint| setpoint.gaz = 50);
+
+        let sequence_ids: Vec<u8> = (0..2)
+            .map(|_| match pilot.next_feature() {
+                Some(Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::Pcmd(
+                    pcmd,
+                ))))) => {
+                    assert_eq!(0, pcmd.flag);
+                    assert_eq!(50, pcmd.gaz);
+
+                    // the high 8 bits
+                    (pcmd.timestamp_and_seq_num >> 24) as u8
+                }
+                feature => panic!("Expected PCMD, got: {:?}", feature),
+            })
+            .collect();
+        assert_eq!(vec![0, 1], sequence_ids);
+
+        pilot.stop();
+        assert_eq!(Setpoint::default(), pilot.setpoint());
+        assert_eq!(None, pilot.next_feature());
+
+        pilot.start(PilotingCommand::PilotState);
+        assert!(matches!(
+            pilot.next_feature(),
+            Some(Feature::JumpingSumo(_))
+        ));
+    }
+
+    #[test]
+    fn test_pilot_state() {
+        let setpoint = Setpoint {
+            pitch: 100,
+            yaw: -30,
+            ..Setpoint::default()
+        };
+
+        assert_eq!(
+            Feature::JumpingSumo(jumping_sumo::Class::Piloting(
+                jumping_sumo::piloting::Command::Pcmd(jumping_sumo::piloting::Pcmd {
+                    flag: 1,
+                    speed: 100,
+                    turn: -30,
+                })
+            )),
+            setpoint.feature(PilotingCommand::PilotState, 0)
+        );
+    }
+}
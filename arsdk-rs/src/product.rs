@@ -0,0 +1,134 @@
+//! The per-product handshake profiles, see [`Config::for_product`](crate::Config::for_product)
+use crate::{handshake::ArStream2, PilotingCommand, DEFAULT_ARSTREAM2, PARROT_SPHINX_IP};
+use std::net::{IpAddr, Ipv4Addr};
+
+/// The `arstream2_client_*` ports the Anafi expects in the handshake
+pub const ANAFI_ARSTREAM2: ArStream2 = ArStream2 {
+    stream_port: 55004,
+    control_port: 55005,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Product {
+    JumpingSumo,
+    Bebop,
+    Bebop2,
+    Disco,
+    Anafi,
+    /// The Mambo with its FPV camera, which adds the Wi-Fi connection
+    MamboFpv,
+    /// The Parrot Sphinx simulator
+    Sphinx,
+}
+
+/// C SDK: `eARDISCOVERY_PRODUCT`, advertised in the `_arsdk-XXXX._udp` service type
+const PRODUCT_IDS: &[(u16, Product)] = &[
+    (0x0901, Product::Bebop),
+    (0x0902, Product::JumpingSumo),
+    // Jumping Night
+    (0x0905, Product::JumpingSumo),
+    // Jumping Race
+    (0x0906, Product::JumpingSumo),
+    (0x090b, Product::MamboFpv),
+    (0x090c, Product::Bebop2),
+    (0x090e, Product::Disco),
+    (0x0914, Product::Anafi),
+    // Anafi Thermal
+    (0x0919, Product::Anafi),
+];
+
+impl Product {
+    /// The Sphinx simulator advertises the product id of the simulated Drone
+    pub fn from_product_id(product_id: u16) -> Option<Self> {
+        PRODUCT_IDS
+            .iter()
+            .find(|(id, _)| *id == product_id)
+            .map(|(_, product)| *product)
+    }
+
+    /// All the known product ids, e.g. for the discovery
+    pub fn product_ids() -> impl Iterator<Item = u16> {
+        PRODUCT_IDS.iter().map(|(id, _)| *id)
+    }
+
+    /// The `arstream2_client_*` ports sent in the handshake.
+    ///
+    /// Like pyparrot, they are only sent to the Drones streaming with `arstream2`.
+    pub fn arstream2(self) -> Option<ArStream2> {
+        match self {
+            Self::Anafi => Some(ANAFI_ARSTREAM2),
+            Self::Bebop | Self::Bebop2 | Self::Disco | Self::Sphinx => Some(DEFAULT_ARSTREAM2),
+            Self::JumpingSumo | Self::MamboFpv => None,
+        }
+    }
+
+    /// The address of the Drone on its own Wi-Fi access point
+    pub fn default_addr(self) -> IpAddr {
+        match self {
+            Self::JumpingSumo => Ipv4Addr::new(192, 168, 2, 1).into(),
+            Self::Bebop | Self::Bebop2 | Self::Disco | Self::Anafi => {
+                Ipv4Addr::new(192, 168, 42, 1).into()
+            }
+            Self::MamboFpv => Ipv4Addr::new(192, 168, 99, 3).into(),
+            Self::Sphinx => PARROT_SPHINX_IP,
+        }
+    }
+
+    /// The command of the piloting loop, see [`Drone::start_piloting`](crate::Drone::start_piloting)
+    pub fn piloting_command(self) -> PilotingCommand {
+        match self {
+            Self::JumpingSumo => PilotingCommand::PilotState,
+            _ => PilotingCommand::PCMD,
+        }
+    }
+}
+
+#[cfg(test)]
+mod product_tests {
+    use super::*;
+    use crate::{handshake::Request, Config};
+
+    #[test]
+    fn test_handshake_fields() {
+        let jumping_sumo = Request::new(&Config::for_product(Product::JumpingSumo), 43210);
+        assert_eq!(None, jumping_sumo.arstream2);
+
+        let anafi = Request::new(&Config::for_product(Product::Anafi), 43210);
+        assert_eq!(Some(ANAFI_ARSTREAM2), anafi.arstream2);
+
+        // an explicit profile is kept
+        let config = Config::for_product(Product::Anafi).or_product(Product::Bebop2);
+        assert_eq!(Some(Product::Anafi), config.product);
+        assert_eq!(Some(ANAFI_ARSTREAM2), config.arstream2());
+
+        let config = Config::from(Product::Bebop2.default_addr()).or_product(Product::JumpingSumo);
+        assert_eq!(Some(Product::JumpingSumo), config.product);
+        assert_eq!(None, config.arstream2());
+
+        // the fields set explicitly are kept
+        let ports = ArStream2 {
+            stream_port: 55004,
+            control_port: 55005,
+        };
+        let config = Config::builder(Product::JumpingSumo.default_addr())
+            .arstream2(Some(ports))
+            .build()
+            .or_product(Product::JumpingSumo);
+        assert_eq!(Some(Product::JumpingSumo), config.product);
+        assert_eq!(Some(ports), config.arstream2());
+
+        let config = Config::builder(Product::Anafi.default_addr())
+            .arstream2(None)
+            .product(Product::Anafi)
+            .build();
+        assert_eq!(None, config.arstream2());
+    }
+
+    #[test]
+    fn test_product_ids() {
+        assert_eq!(Some(Product::Bebop2), Product::from_product_id(0x090c));
+        assert_eq!(Some(Product::JumpingSumo), Product::from_product_id(0x0906));
+        assert_eq!(None, Product::from_product_id(0xffff));
+        assert_eq!(9, Product::product_ids().count());
+    }
+}
@@ -0,0 +1,346 @@
+//! The outgoing frames are queued per `BufferID`, like the IOBuffers of the C ARNetwork
+//!
+//! The Command sender always sends from the non-empty queue with the highest priority first,
+//! so e.g. a `Piloting::Emergency` on `BufferID::CDEmergency` doesn't wait behind the piloting frames.
+use crate::{
+    frame::{BufferID, HEADER_LENGTH},
+    Error,
+};
+use log::debug;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    convert::TryFrom,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+/// For the buffers without specific parameters, e.g. `PONG` & the acks we send
+pub const DEFAULT_QUEUE_PARAMS: QueueParams = QueueParams {
+    priority: 2,
+    capacity: 64,
+    overflow: Overflow::Reject,
+};
+
+/// What happens to a new frame when its queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Drops the oldest queued frame, i.e. the latest frame wins
+    DropOldest,
+    /// A new frame replaces the queued one of the same Feature, Class & Command,
+    /// otherwise the oldest queued frame is dropped, like the overwriting IOBuffers of the C SDK
+    ReplaceCommand,
+    /// Refuses the new frame with `Error::QueueFull`
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueueParams {
+    /// Queues with a higher priority are sent first
+    pub priority: u8,
+    /// How many frames can wait to be sent, at least `1`
+    pub capacity: usize,
+    pub overflow: Overflow,
+}
+
+impl QueueParams {
+    /// Only the latest frame of each Command is kept, e.g. of the `Piloting::PCMD`s only the last one matters,
+    /// while the other Commands queued on the same buffer are still sent
+    pub const fn latest_wins(priority: u8) -> Self {
+        Self {
+            priority,
+            capacity: 16,
+            overflow: Overflow::ReplaceCommand,
+        }
+    }
+
+    /// * `CDEmergency` - sent before anything else
+    /// * `CDAck` - never dropped, the caller gets `Error::QueueFull` instead
+    /// * `CDNonAck` - the latest frame of each Command wins, as it carries the piloting commands
+    pub fn default_for(buffer_id: BufferID) -> Self {
+        match buffer_id {
+            BufferID::CDEmergency => Self {
+                priority: 3,
+                capacity: 16,
+                overflow: Overflow::Reject,
+            },
+            BufferID::CDAck => Self {
+                priority: 2,
+                capacity: 64,
+                overflow: Overflow::Reject,
+            },
+            BufferID::CDNonAck => Self::latest_wins(1),
+            _ => DEFAULT_QUEUE_PARAMS,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Queue {
+    params: QueueParams,
+    frames: VecDeque<Vec<u8>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct SendQueues {
+    /// See `Config::send_queues`
+    params: Vec<(BufferID, QueueParams)>,
+    /// Ordered by the buffer id
+    queues: Mutex<BTreeMap<u8, Queue>>,
+    queued: Condvar,
+    /// Once the Command sender stopped, nothing would send the queued frames
+    closed: AtomicBool,
+}
+
+/// Closes the [`SendQueues`] when the Command sender stops, even if it panics
+#[derive(Debug)]
+pub(crate) struct CloseOnDrop(Arc<SendQueues>);
+
+impl Drop for CloseOnDrop {
+    fn drop(&mut self) {
+        self.0.closed.store(true, Ordering::SeqCst);
+    }
+}
+
+impl SendQueues {
+    pub(crate) fn new(params: Vec<(BufferID, QueueParams)>) -> Self {
+        Self {
+            params,
+            queues: Mutex::new(BTreeMap::new()),
+            queued: Condvar::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Held by the Command sender, see [`CloseOnDrop`]
+    pub(crate) fn close_on_drop(self: &Arc<Self>) -> CloseOnDrop {
+        CloseOnDrop(self.clone())
+    }
+
+    fn params(&self, buffer_id: u8) -> QueueParams {
+        let configured = self.params.iter().rev().find_map(|(id, params)| {
+            let id: u8 = (*id).into();
+
+            if id == buffer_id {
+                Some(*params)
+            } else {
+                None
+            }
+        });
+
+        match (configured, BufferID::try_from(buffer_id)) {
+            (Some(params), _) => params,
+            (None, Ok(buffer_id)) => QueueParams::default_for(buffer_id),
+            (None, Err(_)) => DEFAULT_QUEUE_PARAMS,
+        }
+    }
+
+    fn lock_queues(&self) -> MutexGuard<'_, BTreeMap<u8, Queue>> {
+        self.queues.lock().expect("Send queues lock poisoned")
+    }
+
+    /// Queues the raw frame on the queue of its `buffer_id`
+    pub(crate) fn push(&self, buffer_id: u8, raw_frame: Vec<u8>) -> Result<(), Error> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(Error::SenderStopped);
+        }
+
+        let params = self.params(buffer_id);
+        let mut queues = self.lock_queues();
+        let queue = queues.entry(buffer_id).or_insert_with(|| Queue {
+            params,
+            frames: VecDeque::new(),
+        });
+
+        if queue.params.overflow == Overflow::ReplaceCommand {
+            let command = command_key(&raw_frame);
+            let queued = queue
+                .frames
+                .iter_mut()
+                .find(|queued| command.is_some() && command_key(queued) == command);
+
+            if let Some(queued) = queued {
+                debug!("Replacing the queued Command on buffer {}", buffer_id);
+
+                *queued = raw_frame;
+                drop(queues);
+                self.queued.notify_one();
+
+                return Ok(());
+            }
+        }
+
+        if queue.frames.len() >= queue.params.capacity.max(1) {
+            match queue.params.overflow {
+                Overflow::DropOldest | Overflow::ReplaceCommand => {
+                    debug!("Dropping the oldest queued Frame on buffer {}", buffer_id);
+
+                    queue.frames.pop_front();
+                }
+                Overflow::Reject => return Err(Error::QueueFull(buffer_id)),
+            }
+        }
+
+        queue.frames.push_back(raw_frame);
+        drop(queues);
+
+        self.queued.notify_one();
+
+        Ok(())
+    }
+
+    /// The next frame to send, without waiting
+    #[cfg(test)]
+    pub(crate) fn try_pop(&self) -> Option<Vec<u8>> {
+        pop(&mut self.lock_queues())
+    }
+
+    /// Waits up to `timeout` for the next frame to send
+    pub(crate) fn pop_timeout(&self, timeout: Duration) -> Option<Vec<u8>> {
+        let deadline = Instant::now() + timeout;
+        let mut queues = self.lock_queues();
+
+        loop {
+            if let Some(raw_frame) = pop(&mut queues) {
+                return Some(raw_frame);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return None;
+            }
+
+            queues = self
+                .queued
+                .wait_timeout(queues, deadline - now)
+                .expect("Send queues lock poisoned")
+                .0;
+        }
+    }
+}
+
+/// The Feature (u8), Class (u8) & Command (u16) of a raw frame, `None` if it's too short
+fn command_key(raw_frame: &[u8]) -> Option<&[u8]> {
+    raw_frame.get(HEADER_LENGTH..HEADER_LENGTH + 4)
+}
+
+/// Pops from the non-empty queue with the highest priority,
+/// or the lowest buffer id amongst the ones with the same priority
+fn pop(queues: &mut BTreeMap<u8, Queue>) -> Option<Vec<u8>> {
+    queues
+        .values_mut()
+        // `max_by_key` returns the last of the equal elements
+        .rev()
+        .filter(|queue| !queue.frames.is_empty())
+        .max_by_key(|queue| queue.params.priority)?
+        .frames
+        .pop_front()
+}
+
+#[cfg(test)]
+mod queue_tests {
+    use super::*;
+
+    fn push(queues: &SendQueues, buffer_id: BufferID, byte: u8) -> Result<(), Error> {
+        queues.push(buffer_id.into(), vec![byte])
+    }
+
+    /// A raw frame of the `command` (Feature, Class, Command) with a single argument byte
+    fn command_frame(command: [u8; 3], argument: u8) -> Vec<u8> {
+        vec![
+            2, 10, 0, 12, 0, 0, 0, command[0], command[1], command[2], 0, argument,
+        ]
+    }
+
+    #[test]
+    fn test_priorities_and_latest_wins() {
+        let queues = SendQueues::new(Vec::new());
+
+        push(&queues, BufferID::CDNonAck, 1).expect("Should queue");
+        push(&queues, BufferID::CDNonAck, 2).expect("Should queue");
+        push(&queues, BufferID::CDAck, 3).expect("Should queue TakeOff");
+        push(&queues, BufferID::PONG, 4).expect("Should queue PONG");
+        push(&queues, BufferID::CDEmergency, 5).expect("Should queue Emergency");
+
+        // Emergency, then the equal priority PONG & CDAck by buffer id and the non-ack frames
+        for expected in [5, 4, 3, 1, 2].iter() {
+            assert_eq!(Some(vec![*expected]), queues.try_pop());
+        }
+        assert_eq!(None, queues.pop_timeout(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn test_latest_wins_per_command() {
+        // ArDrone3 Piloting PCMD & ArDrone3 Camera Orientation
+        const PCMD: [u8; 3] = [1, 0, 2];
+        const ORIENTATION: [u8; 3] = [1, 1, 0];
+        let queues = SendQueues::new(Vec::new());
+        let cd_non_ack = BufferID::CDNonAck.into();
+
+        queues
+            .push(cd_non_ack, command_frame(ORIENTATION, 1))
+            .expect("Should queue the orientation");
+        queues
+            .push(cd_non_ack, command_frame(PCMD, 1))
+            .expect("Should queue PCMD");
+        queues
+            .push(cd_non_ack, command_frame(PCMD, 2))
+            .expect("Should queue PCMD");
+
+        // the orientation isn't replaced by the PCMDs, only the latest PCMD is sent
+        assert_eq!(Some(command_frame(ORIENTATION, 1)), queues.try_pop());
+        assert_eq!(Some(command_frame(PCMD, 2)), queues.try_pop());
+        assert_eq!(None, queues.try_pop());
+
+        // when full, the oldest frame is dropped
+        for class in 0..=16 {
+            queues
+                .push(cd_non_ack, command_frame([1, class, 0], 0))
+                .expect("Should queue");
+        }
+        assert_eq!(Some(command_frame([1, 1, 0], 0)), queues.try_pop());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let params = QueueParams {
+            priority: 2,
+            capacity: 2,
+            overflow: Overflow::Reject,
+        };
+        let queues = SendQueues::new(vec![(BufferID::CDAck, params)]);
+
+        push(&queues, BufferID::CDAck, 1).expect("Should queue");
+        push(&queues, BufferID::CDAck, 2).expect("Should queue");
+
+        match push(&queues, BufferID::CDAck, 3) {
+            Err(Error::QueueFull(11)) => {}
+            result => panic!("Expected a full queue, got: {:?}", result),
+        }
+
+        assert_eq!(Some(vec![1]), queues.try_pop());
+        push(&queues, BufferID::CDAck, 3).expect("Should queue");
+    }
+
+    #[test]
+    fn test_closed_by_the_sender() {
+        let queues = Arc::new(SendQueues::new(Vec::new()));
+
+        let sender = std::thread::spawn({
+            let closing = queues.close_on_drop();
+
+            move || {
+                let _closing = closing;
+                panic!("The Command sender panicked");
+            }
+        });
+        assert!(sender.join().is_err());
+
+        match push(&queues, BufferID::CDAck, 1) {
+            Err(Error::SenderStopped) => {}
+            result => panic!("Expected a stopped sender, got: {:?}", result),
+        }
+    }
+}
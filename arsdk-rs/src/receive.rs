@@ -0,0 +1,153 @@
+//! Duplicate & out-of-order suppression of the received frames
+//!
+//! Like the C ARNetwork, we keep the last `sequence_id` received on each `BufferID`.
+//! A frame which isn't ahead of it is a retransmission (e.g. our ack got lost) or arrived late,
+//! so it's not processed again. Such frames which need an ack are still acknowledged.
+use crate::frame::{BufferID, FrameRef, Type};
+use dashmap::DashMap;
+use std::collections::HashMap;
+
+/// A frame further behind than this means that the Drone started over its sequence ids,
+/// e.g. after it rebooted, so it's not a duplicate.
+pub(crate) const MAXIMUM_SEQUENCE_DIFF: i8 = 10;
+
+/// The received frames on a `BufferID`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReceiveStats {
+    /// Processed frames
+    pub received: u64,
+    /// Retransmitted or out-of-order frames that were dropped
+    pub duplicates: u64,
+    /// Frames that we never got, based on the gaps in the sequence ids
+    pub missed: u64,
+}
+
+#[derive(Debug, Default)]
+struct ReceiveWindow {
+    last_sequence_id: Option<u8>,
+    stats: ReceiveStats,
+}
+
+impl ReceiveWindow {
+    /// Returns whether or not the frame with `sequence_id` is a new one
+    fn receive(&mut self, sequence_id: u8) -> bool {
+        let last_sequence_id = match self.last_sequence_id {
+            Some(last_sequence_id) => last_sequence_id,
+            None => {
+                self.last_sequence_id = Some(sequence_id);
+                self.stats.received += 1;
+
+                return true;
+            }
+        };
+
+        // the sequence ids wrap around
+        let diff = sequence_id.wrapping_sub(last_sequence_id) as i8;
+
+        if (-MAXIMUM_SEQUENCE_DIFF..=0).contains(&diff) {
+            self.stats.duplicates += 1;
+
+            return false;
+        }
+
+        if diff > 1 {
+            self.stats.missed += (diff - 1) as u64;
+        }
+
+        self.last_sequence_id = Some(sequence_id);
+        self.stats.received += 1;
+
+        true
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ReceiveWindows {
+    windows: DashMap<BufferID, ReceiveWindow>,
+}
+
+impl ReceiveWindows {
+    /// Returns whether or not the `frame` should be processed.
+    ///
+    /// Ack frames are always processed, since acknowledging twice is harmless.
+    pub(crate) fn accept(&self, frame: &FrameRef<'_>) -> bool {
+        if frame.frame_type == Type::Ack {
+            return true;
+        }
+
+        self.windows
+            .entry(frame.buffer_id)
+            .or_default()
+            .receive(frame.sequence_id)
+    }
+
+    pub(crate) fn stats(&self, buffer_id: BufferID) -> ReceiveStats {
+        self.windows
+            .get(&buffer_id)
+            .map(|window| window.stats)
+            .unwrap_or_default()
+    }
+
+    /// The stats of every `BufferID` we received frames on
+    pub(crate) fn all_stats(&self) -> HashMap<BufferID, ReceiveStats> {
+        self.windows
+            .iter()
+            .map(|window| (*window.key(), window.value().stats))
+            .collect()
+    }
+
+    /// The Drone starts over its sequence ids with each new session
+    pub(crate) fn clear(&self) {
+        self.windows.clear()
+    }
+}
+
+#[cfg(test)]
+mod receive_tests {
+    use super::*;
+
+    fn navdata(sequence_id: u8) -> FrameRef<'static> {
+        FrameRef {
+            frame_type: Type::Data,
+            buffer_id: BufferID::DCNavdata,
+            sequence_id,
+            feature: None,
+        }
+    }
+
+    #[test]
+    fn test_duplicates_and_gaps() {
+        let windows = ReceiveWindows::default();
+
+        assert!(windows.accept(&navdata(254)));
+        assert!(windows.accept(&navdata(255)));
+        // duplicate
+        assert!(!windows.accept(&navdata(255)));
+        // wraps around & misses 1
+        assert!(windows.accept(&navdata(1)));
+        // out-of-order
+        assert!(!windows.accept(&navdata(0)));
+        // restarted sequence
+        assert!(windows.accept(&navdata(200)));
+
+        assert_eq!(
+            ReceiveStats {
+                received: 4,
+                duplicates: 2,
+                missed: 1,
+            },
+            windows.stats(BufferID::DCNavdata)
+        );
+        assert_eq!(ReceiveStats::default(), windows.stats(BufferID::DCEvent));
+
+        // Acks are not tracked
+        let ack = FrameRef {
+            frame_type: Type::Ack,
+            buffer_id: BufferID::Ack(11),
+            sequence_id: 0,
+            feature: None,
+        };
+        assert!(windows.accept(&ack));
+        assert!(windows.accept(&ack));
+    }
+}
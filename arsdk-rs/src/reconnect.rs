@@ -0,0 +1,250 @@
+//! Automatic reconnection
+//!
+//! When the connection is `ConnectionState::Lost` and [`Config::reconnect`](crate::Config::reconnect)
+//! is set, the handshake is performed again, waiting longer after each failed attempt.
+//!
+//! [`Reconnect`] is driven by the watchdog ticks of the sync & async Drones, which run the
+//! handshake itself in the background, so disconnecting never waits for it.
+use crate::ConnectionState;
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// How long to wait before the first attempt, it doubles after each failed attempt
+    pub initial_backoff: Duration,
+    /// The longest we'll wait between two attempts
+    pub max_backoff: Duration,
+    /// `None` keeps trying until the Drone is disconnected
+    pub max_attempts: Option<usize>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// How long to wait before the `attempt` (starting from `1`)
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(31) as u32;
+
+        self.initial_backoff
+            .checked_mul(1 << doublings)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+
+    pub(crate) fn should_attempt(&self, attempt: usize) -> bool {
+        match self.max_attempts {
+            Some(max_attempts) => attempt <= max_attempts,
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Idle,
+    /// Until the backoff of the `attempt` elapsed
+    Waiting {
+        attempt: usize,
+        until: Instant,
+    },
+    /// The handshake of the `attempt` is in progress
+    Reconnecting {
+        attempt: usize,
+    },
+}
+
+/// What the watchdog has to do next, see [`Reconnect::poll`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step {
+    /// Perform the handshake of the attempt
+    Attempt(usize),
+    /// The connection isn't `Lost` anymore, without reconnecting
+    Recovered,
+    /// The policy ran out of attempts after that many
+    GaveUp(usize),
+}
+
+/// The reconnection state machine of a connection, it does nothing without a policy
+#[derive(Debug)]
+pub(crate) struct Reconnect {
+    policy: Option<ReconnectPolicy>,
+    state: Mutex<State>,
+}
+
+impl Reconnect {
+    pub(crate) fn new(policy: Option<ReconnectPolicy>) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(State::Idle),
+        }
+    }
+
+    /// Starts reconnecting once the connection is `Lost`, unless we already are
+    pub(crate) fn lost(&self, now: Instant) {
+        let policy = match self.policy {
+            Some(policy) => policy,
+            None => return,
+        };
+
+        let mut state = self.lock();
+        if *state == State::Idle {
+            *state = State::Waiting {
+                attempt: 1,
+                until: now + policy.backoff(1),
+            };
+        }
+    }
+
+    /// Called on every watchdog tick with the current `connection` state
+    pub(crate) fn poll(&self, now: Instant, connection: ConnectionState) -> Option<Step> {
+        let policy = self.policy?;
+        let mut state = self.lock();
+
+        let attempt = match *state {
+            State::Waiting { attempt, .. } if !policy.should_attempt(attempt) => {
+                *state = State::Idle;
+
+                return Some(Step::GaveUp(attempt - 1));
+            }
+            State::Waiting { attempt, until } if now >= until => attempt,
+            _ => return None,
+        };
+
+        if connection == ConnectionState::Lost {
+            *state = State::Reconnecting { attempt };
+
+            Some(Step::Attempt(attempt))
+        } else {
+            *state = State::Idle;
+
+            Some(Step::Recovered)
+        }
+    }
+
+    /// The handshake of the attempt in progress is done, a failed one is attempted again
+    pub(crate) fn finished(&self, now: Instant, reconnected: bool) {
+        let policy = match self.policy {
+            Some(policy) => policy,
+            None => return,
+        };
+
+        let mut state = self.lock();
+        if let State::Reconnecting { attempt } = *state {
+            *state = if reconnected {
+                State::Idle
+            } else {
+                State::Waiting {
+                    attempt: attempt + 1,
+                    until: now + policy.backoff(attempt + 1),
+                }
+            };
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("Reconnect lock poisoned")
+    }
+}
+
+#[cfg(test)]
+mod reconnect_tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            max_attempts: Some(3),
+        };
+
+        assert_eq!(Duration::from_millis(100), policy.backoff(1));
+        assert_eq!(Duration::from_millis(200), policy.backoff(2));
+        assert_eq!(Duration::from_millis(800), policy.backoff(4));
+        assert_eq!(Duration::from_secs(1), policy.backoff(5));
+        assert_eq!(Duration::from_secs(1), policy.backoff(usize::MAX));
+
+        assert!(policy.should_attempt(3));
+        assert!(!policy.should_attempt(4));
+        assert!(ReconnectPolicy::default().should_attempt(usize::MAX));
+    }
+
+    #[test]
+    fn test_reconnect_steps() {
+        let reconnect = Reconnect::new(Some(ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            max_attempts: Some(2),
+        }));
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+
+        assert_eq!(None, reconnect.poll(start, ConnectionState::Lost));
+
+        reconnect.lost(start);
+        // already reconnecting
+        reconnect.lost(at(50));
+        assert_eq!(None, reconnect.poll(at(99), ConnectionState::Lost));
+        assert_eq!(
+            Some(Step::Attempt(1)),
+            reconnect.poll(at(100), ConnectionState::Lost)
+        );
+        // the handshake is in progress
+        assert_eq!(None, reconnect.poll(at(500), ConnectionState::Lost));
+
+        reconnect.finished(at(500), false);
+        assert_eq!(None, reconnect.poll(at(699), ConnectionState::Lost));
+        assert_eq!(
+            Some(Step::Attempt(2)),
+            reconnect.poll(at(700), ConnectionState::Lost)
+        );
+
+        reconnect.finished(at(700), false);
+        assert_eq!(
+            Some(Step::GaveUp(2)),
+            reconnect.poll(at(700), ConnectionState::Lost)
+        );
+        assert_eq!(None, reconnect.poll(at(5000), ConnectionState::Lost));
+
+        // the next loss starts over
+        reconnect.lost(at(5000));
+        assert_eq!(
+            Some(Step::Recovered),
+            reconnect.poll(at(5100), ConnectionState::Connected)
+        );
+
+        reconnect.lost(at(6000));
+        assert_eq!(
+            Some(Step::Attempt(1)),
+            reconnect.poll(at(6100), ConnectionState::Lost)
+        );
+        reconnect.finished(at(6200), true);
+        assert_eq!(None, reconnect.poll(at(9000), ConnectionState::Lost));
+    }
+
+    #[test]
+    fn test_no_policy() {
+        let reconnect = Reconnect::new(None);
+        let now = Instant::now();
+
+        reconnect.lost(now);
+        assert_eq!(
+            None,
+            reconnect.poll(now + Duration::from_secs(60), ConnectionState::Lost)
+        );
+    }
+}
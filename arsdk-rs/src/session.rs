@@ -0,0 +1,539 @@
+//! The protocol state of a connection, shared by the sync [`Drone`](crate::Drone) and the
+//! async [`asynchronous::Drone`](crate::asynchronous::Drone)
+//!
+//! A [`Session`] holds the sequence ids, the send queues, the pending Acks, the receive windows,
+//! the watchdog & the reconnection of the connection. The Drones only spawn the loops driving it:
+//! threads for the sync Drone and `tokio` tasks for the async one.
+//!
+//! The [`Listener`] & the [`CommandSender`] block on the [`Transport`], so the async Drone runs
+//! them on `tokio`'s blocking threads.
+use crate::{
+    ack::{AckTracker, Notifier},
+    command, common,
+    event::{Event, Subscribers},
+    frame::{self, BufferID, Frame, FrameRef, Type},
+    handshake::Response,
+    listener::Listener,
+    parse::Link,
+    piloting::Pilot,
+    print_buf,
+    queue::SendQueues,
+    receive::ReceiveWindows,
+    reconnect::{Reconnect, Step},
+    stats::LinkStats,
+    transport::Transport,
+    watchdog::Watchdog,
+    Config, ConnectionError, ConnectionState, Error, ReceiveStats, Stats, SEND_RETRIES,
+    SEND_RETRY_INTERVAL, SHUTDOWN_POLL_INTERVAL,
+};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use log::{error, info, warn};
+use scroll::{ctx::TryIntoCtx, Pread, Pwrite, LE};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Weak,
+    },
+    time::Instant,
+};
+
+#[derive(Debug)]
+pub(crate) struct Session {
+    // Each frame::BufferID gets its own sequence_id
+    sequence_ids: DashMap<BufferID, u8>,
+    send_queues: Arc<SendQueues>,
+    acks: AckTracker,
+    received: ReceiveWindows,
+    stats: Arc<LinkStats>,
+    pub(crate) pilot: Pilot,
+    pub(crate) subscribers: Subscribers,
+    watchdog: Watchdog,
+    reconnect: Reconnect,
+    send_disconnection: bool,
+    connected: AtomicBool,
+    transport: Arc<dyn Transport>,
+}
+
+/// A [`Session`] handle for the spawned loops, which doesn't keep the connection alive
+#[derive(Clone, Debug)]
+pub(crate) struct WeakSession(Weak<Session>);
+
+impl WeakSession {
+    /// Returns `None` once the Drone is disconnected or dropped
+    pub(crate) fn upgrade(&self) -> Option<Arc<Session>> {
+        self.0
+            .upgrade()
+            .filter(|session| session.connected.load(Ordering::SeqCst))
+    }
+}
+
+impl Session {
+    pub(crate) fn new(config: &Config, transport: Arc<dyn Transport>) -> Self {
+        Self {
+            sequence_ids: DashMap::new(),
+            send_queues: Arc::new(SendQueues::new(config.send_queues.clone())),
+            acks: AckTracker::new(config.ack_timeout, config.ack_retries),
+            received: ReceiveWindows::default(),
+            stats: Arc::new(LinkStats::default()),
+            pilot: Pilot::default(),
+            subscribers: Subscribers::default(),
+            watchdog: Watchdog::new(config.degraded_timeout, config.connection_timeout),
+            reconnect: Reconnect::new(config.reconnect),
+            send_disconnection: config.send_disconnection,
+            connected: AtomicBool::new(true),
+            transport,
+        }
+    }
+
+    pub(crate) fn downgrade(self: &Arc<Self>) -> WeakSession {
+        WeakSession(Arc::downgrade(self))
+    }
+
+    pub(crate) fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn sequence_id(&self, buffer_id: BufferID) -> u8 {
+        next_sequence_id(&self.sequence_ids, buffer_id)
+    }
+
+    pub(crate) fn connection_state(&self) -> ConnectionState {
+        self.watchdog.state()
+    }
+
+    pub(crate) fn last_received(&self) -> Instant {
+        self.watchdog.last_received()
+    }
+
+    pub(crate) fn handshake_response(&self) -> Option<Response> {
+        self.transport.handshake_response()
+    }
+
+    pub(crate) fn receive_stats(&self, buffer_id: BufferID) -> ReceiveStats {
+        self.received.stats(buffer_id)
+    }
+
+    pub(crate) fn stats(&self) -> Stats {
+        self.stats.snapshot(self.received.all_stats())
+    }
+
+    fn link(&self) -> Link<'_> {
+        Link {
+            acks: &self.acks,
+            stats: &self.stats,
+            subscribers: &self.subscribers,
+            received: &self.received,
+        }
+    }
+
+    /// A frame with the next sequence id of the `buffer_id`
+    pub(crate) fn frame(
+        &self,
+        frame_type: Type,
+        buffer_id: BufferID,
+        feature: Option<command::Feature>,
+    ) -> Frame {
+        Frame::new(frame_type, buffer_id, self.sequence_id(buffer_id), feature)
+    }
+
+    /// Queues the frame, a `Type::DataWithAck` one is tracked until it's acknowledged
+    pub(crate) fn send_frame(&self, frame: Frame, notifier: Option<Notifier>) -> Result<(), Error> {
+        let mut raw_message = [0_u8; 2048];
+        let (frame_type, buffer_id, sequence_id) =
+            (frame.frame_type, frame.buffer_id, frame.sequence_id);
+        let written = frame.try_into_ctx(&mut raw_message, LE)?;
+
+        if frame_type == Type::DataWithAck {
+            let raw_frame = raw_message[0..written].to_vec();
+
+            self.acks.track(buffer_id, sequence_id, raw_frame, notifier);
+        }
+
+        self.send_raw_message(&raw_message[0..written])
+    }
+
+    /// Queues a `Type::DataWithAck` frame, the `notifier` gets its [`DeliveryStatus`](crate::DeliveryStatus)
+    pub(crate) fn send_frame_with_ack(
+        &self,
+        frame: Frame,
+        notifier: Notifier,
+    ) -> Result<(), Error> {
+        if frame.frame_type != Type::DataWithAck {
+            return Err(Error::NotAcknowledged(frame.frame_type));
+        }
+
+        self.send_frame(frame, Some(notifier))
+    }
+
+    pub(crate) fn send_raw_message(&self, raw_message: &[u8]) -> Result<(), Error> {
+        if !self.is_connected() {
+            return Err(Error::Disconnected);
+        }
+
+        let buffer_id = raw_message_buffer_id(raw_message)?;
+
+        self.send_queues.push(buffer_id, raw_message.to_vec())
+    }
+
+    /// Sends a borrowed frame which isn't retransmitted, i.e. our PONGs & Acks.
+    ///
+    /// It's encoded without an owned `Frame`, into a buffer of its exact length which is queued
+    /// like the other messages and sent as its own datagram by the Command sender.
+    fn send_frame_ref(&self, frame: FrameRef<'_>) -> Result<(), Error> {
+        if !self.is_connected() {
+            return Err(Error::Disconnected);
+        }
+
+        let mut raw_message = vec![0_u8; frame.length()];
+        raw_message.pwrite_with(frame, 0, LE)?;
+
+        self.send_queues.push(frame.buffer_id.into(), raw_message)
+    }
+
+    pub(crate) fn send_datetime(&self, date: DateTime<Utc>) -> Result<(), Error> {
+        use command::Feature::Common;
+        use common::{common::Command, Class};
+
+        let date_feature = Common(Some(Class::Common(Command::current_date(&date))));
+        let frame = self.frame(Type::DataWithAck, BufferID::CDAck, Some(date_feature));
+
+        self.send_frame(frame, None)?;
+
+        let time_feature = Common(Some(Class::Common(Command::current_time(&date))));
+        let frame = self.frame(Type::DataWithAck, BufferID::CDAck, Some(time_feature));
+
+        self.send_frame(frame, None)
+    }
+
+    /// - Feeds the connection watchdog
+    /// - Handles the Frames, see [`Link::receive`]
+    /// - Sends the PONGs & Acks to the Command sender
+    pub(crate) fn receive(&self, datagram: &[u8]) {
+        if let Some(state) = self.watchdog.received(Instant::now()) {
+            info!("Connection state: {:?}", state);
+
+            self.subscribers.publish(Event::ConnectionState(state));
+        }
+
+        self.link().receive(
+            datagram,
+            |buffer_id| self.sequence_id(buffer_id),
+            |response| {
+                if let Err(err) = self.send_frame_ref(response) {
+                    error!("Sending Frame to Commander: {}", err)
+                }
+            },
+        );
+    }
+
+    /// Queues the `Type::DataWithAck` frames which weren't acknowledged in time again
+    pub(crate) fn retransmit(&self, now: Instant) {
+        let due = self.acks.due(now);
+        self.stats.retransmitted(due.len());
+
+        for raw_frame in due {
+            info!("Retransmitting Frame: {}", print_buf(&raw_frame));
+
+            if let Err(err) = self.send_raw_message(&raw_frame) {
+                error!("Retransmitting Frame failed: {}", err);
+            }
+        }
+    }
+
+    /// PINGs the Drone to measure the round-trip time
+    pub(crate) fn ping(&self) {
+        let ping = self.stats.ping_feature();
+        let frame = self.frame(Type::Data, BufferID::PING, Some(ping));
+
+        if let Err(err) = self.send_frame(frame, None) {
+            error!("Sending PING failed: {}", err);
+        }
+    }
+
+    /// Sends the piloting command with the current `Setpoint`, unless the piloting is stopped
+    pub(crate) fn pilot(&self) {
+        let feature = match self.pilot.next_feature() {
+            Some(feature) => feature,
+            None => return,
+        };
+
+        let frame = self.frame(Type::Data, BufferID::CDNonAck, Some(feature));
+
+        if let Err(err) = self.send_frame(frame, None) {
+            error!("Sending the piloting command failed: {}", err);
+        }
+    }
+
+    /// Checks for how long we haven't received any frame & drives the reconnection.
+    ///
+    /// Returns the [`Reconnection`] to run in the background once an attempt is due.
+    pub(crate) fn check_connection(self: &Arc<Self>, now: Instant) -> Option<Reconnection> {
+        if let Some(state) = self.watchdog.check(now) {
+            error!("Connection state: {:?}", state);
+
+            self.subscribers.publish(Event::ConnectionState(state));
+
+            if state == ConnectionState::Lost {
+                self.reconnect.lost(now);
+            }
+        }
+
+        match self.reconnect.poll(now, self.watchdog.state())? {
+            Step::Attempt(attempt) => {
+                info!("Reconnecting, attempt {}", attempt);
+                self.subscribers.publish(Event::Reconnecting { attempt });
+
+                Some(Reconnection {
+                    session: self.downgrade(),
+                    transport: self.transport.clone(),
+                })
+            }
+            Step::Recovered => {
+                info!("Connection recovered without reconnecting");
+
+                None
+            }
+            Step::GaveUp(attempts) => {
+                error!("Giving up reconnecting after {} attempts", attempts);
+                self.subscribers.publish(Event::ReconnectFailed);
+
+                None
+            }
+        }
+    }
+
+    /// Starts a new session once the [`Transport`] reconnected:
+    ///
+    /// * the sequence ids start again from `0`
+    /// * the pending `Type::DataWithAck` frames are dropped
+    fn reconnected(&self, result: Result<(), ConnectionError>) {
+        let now = Instant::now();
+
+        match result {
+            Ok(()) => {
+                info!("Reconnected");
+
+                self.sequence_ids.clear();
+                self.acks.clear();
+                self.received.clear();
+
+                // the handshake response restarts the watchdog: if the Drone stays silent,
+                // the connection is `Lost` again and we reconnect again
+                if let Some(state) = self.watchdog.received(now) {
+                    self.subscribers.publish(Event::ConnectionState(state));
+                }
+                self.subscribers.publish(Event::Reconnected);
+                self.reconnect.finished(now, true);
+            }
+            Err(err) => {
+                error!("Reconnecting failed: {}", err);
+                self.reconnect.finished(now, false);
+            }
+        }
+    }
+
+    /// Stops the connection, only the first call has any effect.
+    ///
+    /// The disconnection frame is queued before that, so the Command sender
+    /// still sends it before stopping.
+    pub(crate) fn shutdown(&self) {
+        if !self.connected.load(Ordering::SeqCst) {
+            return;
+        }
+
+        if self.send_disconnection {
+            match disconnection_frame(self.sequence_id(BufferID::CDNonAck)) {
+                Ok(raw_frame) => {
+                    if let Err(err) = self.send_queues.push(BufferID::CDNonAck.into(), raw_frame) {
+                        error!("Sending Disconnection Frame failed: {}", err);
+                    }
+                }
+                Err(err) => error!("Serializing Disconnection Frame failed: {}", err),
+            }
+        }
+
+        self.connected.store(false, Ordering::SeqCst);
+        // wakes up anyone waiting on `Drone::send_frame_with_ack`
+        self.acks.clear();
+
+        info!("Disconnected");
+    }
+
+    pub(crate) fn listener(self: &Arc<Self>) -> Listener {
+        Listener {
+            session: self.downgrade(),
+            transport: self.transport.clone(),
+        }
+    }
+
+    pub(crate) fn command_sender(self: &Arc<Self>) -> CommandSender {
+        CommandSender {
+            session: self.downgrade(),
+            send_queues: self.send_queues.clone(),
+            stats: self.stats.clone(),
+            transport: self.transport.clone(),
+        }
+    }
+
+    /// Reports a datagram the Command sender gave up on:
+    ///
+    /// * publishes `Event::SendFailed`
+    /// * a `Type::DataWithAck` frame fails with `Error::Send`, instead of being retransmitted
+    fn send_failed(&self, raw_frame: &[u8], error: std::io::Error) {
+        let buffer_id = raw_message_buffer_id(raw_frame).unwrap_or_default();
+        error!("Sending Frame on buffer {} failed: {}", buffer_id, error);
+
+        self.subscribers.publish(Event::SendFailed {
+            buffer_id,
+            kind: error.kind(),
+        });
+        self.acks.fail(raw_frame, Error::Send { buffer_id, error });
+    }
+}
+
+/// Sends the queued frames on the [`Transport`]
+#[derive(Debug)]
+pub(crate) struct CommandSender {
+    session: WeakSession,
+    send_queues: Arc<SendQueues>,
+    stats: Arc<LinkStats>,
+    transport: Arc<dyn Transport>,
+}
+
+impl CommandSender {
+    /// Blocking sender in a loop, until the Drone is disconnected & the queues are empty
+    pub(crate) fn run(self) {
+        // `Drone::send_frame` fails with `Error::SenderStopped` once we're gone
+        let _closing = self.send_queues.close_on_drop();
+
+        loop {
+            // queued frames are still sent after disconnecting
+            let frame_to_send = match self.send_queues.pop_timeout(SHUTDOWN_POLL_INTERVAL) {
+                Some(frame) => frame,
+                None if self.session.upgrade().is_some() => continue,
+                None => break,
+            };
+
+            info!("Frame to sent: {:?}", &frame_to_send);
+
+            let frame = frame_to_send.pread_with::<Frame>(0, LE);
+
+            info!(
+                "Sent Frame (length: {}) => {:?}",
+                frame_to_send.len(),
+                &frame
+            );
+
+            match self.send_with_retries(&frame_to_send) {
+                Ok(()) => self.stats.sent(frame_to_send.len()),
+                Err(err) => match self.session.upgrade() {
+                    Some(session) => session.send_failed(&frame_to_send, err),
+                    None => error!("Sending Frame failed: {}", err),
+                },
+            }
+        }
+    }
+
+    /// Sends the datagram again up to [`SEND_RETRIES`] times if the `transport` fails
+    fn send_with_retries(&self, datagram: &[u8]) -> std::io::Result<()> {
+        let mut retries_left = SEND_RETRIES;
+
+        loop {
+            match self.transport.send(datagram) {
+                Ok(()) => return Ok(()),
+                Err(err) if retries_left > 0 => {
+                    warn!("Sending Frame failed, retrying: {}", err);
+
+                    retries_left -= 1;
+                    std::thread::sleep(SEND_RETRY_INTERVAL);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// A reconnection attempt, see [`Session::check_connection`]
+///
+/// It doesn't keep the connection alive, the Drone can be disconnected during the handshake.
+#[derive(Debug)]
+pub(crate) struct Reconnection {
+    session: WeakSession,
+    transport: Arc<dyn Transport>,
+}
+
+impl Reconnection {
+    /// Blocks until the [`Transport`] reconnected or failed to
+    pub(crate) fn run(self) {
+        let result = self.transport.reconnect();
+
+        if let Some(session) = self.session.upgrade() {
+            session.reconnected(result);
+        }
+    }
+}
+
+/// Common - NetworkEvent - Disconnection
+fn disconnection_frame(sequence_id: u8) -> Result<Vec<u8>, frame::Error> {
+    use command::Feature::Common;
+    use common::{network_event, Class};
+
+    let disconnection = network_event::Disconnection {
+        cause: network_event::DisconnectionCause::Unknown,
+    };
+    let frame = Frame {
+        frame_type: Type::Data,
+        buffer_id: BufferID::CDNonAck,
+        sequence_id,
+        feature: Some(Common(Some(Class::NetworkEvent(
+            network_event::Command::Disconnection(disconnection),
+        )))),
+    };
+
+    let mut raw_frame = [0_u8; 64];
+    let written = frame.try_into_ctx(&mut raw_frame, LE)?;
+
+    Ok(raw_frame[..written].to_vec())
+}
+
+/// The `BufferID` is the 2nd byte of the frame header
+fn raw_message_buffer_id(raw_message: &[u8]) -> Result<u8, frame::Error> {
+    raw_message
+        .get(1)
+        .copied()
+        .ok_or(frame::Error::BytesLength {
+            expected: 7,
+            actual: raw_message.len() as u32,
+        })
+}
+
+/// Each `frame::BufferID` gets its own sequence_id, starting from `0`
+fn next_sequence_id(sequence_ids: &DashMap<BufferID, u8>, buffer_id: BufferID) -> u8 {
+    if let Some(mut sequence_id) = sequence_ids.get_mut(&buffer_id) {
+        let command_id = *sequence_id;
+        *sequence_id = sequence_id.overflowing_add(1).0;
+
+        command_id
+    } else {
+        sequence_ids.insert(buffer_id, 0);
+        0
+    }
+}
+
+#[cfg(test)]
+mod session_tests {
+    use super::*;
+
+    #[test]
+    fn disconnection_frame_is_common_network_event() {
+        // Type::Data - BufferID::CDNonAck - Sequence: 3 - Frame size: 15
+        // Common - NetworkEvent - Disconnection - DisconnectionCause::Unknown
+        let expected = [2, 10, 3, 15, 0, 0, 0, 0, 1, 0, 0, 1, 0, 0, 0];
+
+        assert_eq!(
+            expected.to_vec(),
+            disconnection_frame(3).expect("Should write the disconnection frame")
+        );
+    }
+}
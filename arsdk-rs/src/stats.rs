@@ -0,0 +1,295 @@
+//! Link quality statistics, see [`Drone::stats`](crate::Drone::stats)
+//!
+//! Like the C ARNetworkAL, we PING the Drone every [`PING_INTERVAL`] with the time elapsed
+//! since the connection started, which the Drone echoes back in its PONG.
+use crate::{
+    command::Feature,
+    frame::{BufferID, FeatureRef},
+    ReceiveStats,
+};
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// How often we PING the Drone to measure the round-trip time
+pub const PING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The per second rates are computed over this window
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// A snapshot of the link quality
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Stats {
+    /// The round-trip time of the last answered PING
+    pub rtt: Option<Duration>,
+    /// The round-trip time smoothed like the TCP `SRTT`
+    pub smoothed_rtt: Option<Duration>,
+    /// The smoothed time it takes for our `Type::DataWithAck` frames to be acknowledged,
+    /// retransmissions included
+    pub ack_latency: Option<Duration>,
+    /// How many `Type::DataWithAck` frames were retransmitted
+    pub retransmits: u64,
+    pub sent: Throughput,
+    pub received: Throughput,
+    /// The received, duplicated & missed frames on each `BufferID`
+    pub buffers: HashMap<BufferID, ReceiveStats>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Throughput {
+    pub bytes: u64,
+    pub frames: u64,
+    /// Over the last second
+    pub bytes_per_second: f64,
+    /// Over the last second
+    pub frames_per_second: f64,
+}
+
+#[derive(Debug)]
+struct Counter {
+    throughput: Throughput,
+    window_start: Instant,
+    window_bytes: u64,
+    window_frames: u64,
+}
+
+impl Counter {
+    fn new(now: Instant) -> Self {
+        Self {
+            throughput: Throughput::default(),
+            window_start: now,
+            window_bytes: 0,
+            window_frames: 0,
+        }
+    }
+
+    /// Computes the rates once the window is over
+    fn roll(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+
+        if elapsed < RATE_WINDOW {
+            return;
+        }
+
+        let seconds = elapsed.as_secs_f64();
+        self.throughput.bytes_per_second = self.window_bytes as f64 / seconds;
+        self.throughput.frames_per_second = self.window_frames as f64 / seconds;
+
+        self.window_start = now;
+        self.window_bytes = 0;
+        self.window_frames = 0;
+    }
+
+    fn add(&mut self, now: Instant, bytes: usize, frames: usize) {
+        self.roll(now);
+
+        self.throughput.bytes += bytes as u64;
+        self.throughput.frames += frames as u64;
+        self.window_bytes += bytes as u64;
+        self.window_frames += frames as u64;
+    }
+}
+
+#[derive(Debug)]
+struct Counters {
+    rtt: Option<Duration>,
+    smoothed_rtt: Option<Duration>,
+    ack_latency: Option<Duration>,
+    retransmits: u64,
+    sent: Counter,
+    received: Counter,
+}
+
+#[derive(Debug)]
+pub(crate) struct LinkStats {
+    /// The PING payloads are relative to it
+    started: Instant,
+    counters: Mutex<Counters>,
+}
+
+impl Default for LinkStats {
+    fn default() -> Self {
+        let now = Instant::now();
+
+        Self {
+            started: now,
+            counters: Mutex::new(Counters {
+                rtt: None,
+                smoothed_rtt: None,
+                ack_latency: None,
+                retransmits: 0,
+                sent: Counter::new(now),
+                received: Counter::new(now),
+            }),
+        }
+    }
+}
+
+impl LinkStats {
+    fn lock_counters(&self) -> MutexGuard<'_, Counters> {
+        self.counters.lock().expect("Stats lock poisoned")
+    }
+
+    /// The payload of our next PING: the seconds & nanoseconds since `started`, like a `timespec`
+    pub(crate) fn ping_feature(&self) -> Feature {
+        let elapsed = self.started.elapsed();
+
+        let mut payload = Vec::with_capacity(16);
+        payload.extend_from_slice(&elapsed.as_secs().to_le_bytes());
+        payload.extend_from_slice(&u64::from(elapsed.subsec_nanos()).to_le_bytes());
+
+        Feature::Unknown {
+            feature: payload[0],
+            data: payload[1..].to_vec(),
+        }
+    }
+
+    pub(crate) fn sent(&self, bytes: usize) {
+        self.lock_counters().sent.add(Instant::now(), bytes, 1)
+    }
+
+    /// Counts the received datagram of `bytes` with its `frames`
+    pub(crate) fn received(&self, bytes: usize, frames: usize) {
+        self.lock_counters()
+            .received
+            .add(Instant::now(), bytes, frames)
+    }
+
+    /// Measures the round-trip time of a PONG with the `feature` of our PING
+    pub(crate) fn ponged(&self, feature: &FeatureRef<'_>) {
+        let now = Instant::now();
+
+        let sent_at = match ping_sent_at(feature) {
+            Some(sent_at) => sent_at,
+            None => return,
+        };
+
+        let rtt = match now
+            .saturating_duration_since(self.started)
+            .checked_sub(sent_at)
+        {
+            Some(rtt) => rtt,
+            // not one of our PINGs
+            None => return,
+        };
+
+        let mut counters = self.lock_counters();
+        counters.rtt = Some(rtt);
+        counters.smoothed_rtt = Some(smooth(counters.smoothed_rtt, rtt));
+    }
+
+    /// The `latency` since we first sent the acknowledged frame
+    pub(crate) fn acknowledged(&self, latency: Duration) {
+        let mut counters = self.lock_counters();
+
+        counters.ack_latency = Some(smooth(counters.ack_latency, latency));
+    }
+
+    pub(crate) fn retransmitted(&self, frames: usize) {
+        self.lock_counters().retransmits += frames as u64;
+    }
+
+    pub(crate) fn snapshot(&self, buffers: HashMap<BufferID, ReceiveStats>) -> Stats {
+        let now = Instant::now();
+        let mut counters = self.lock_counters();
+
+        counters.sent.roll(now);
+        counters.received.roll(now);
+
+        Stats {
+            rtt: counters.rtt,
+            smoothed_rtt: counters.smoothed_rtt,
+            ack_latency: counters.ack_latency,
+            retransmits: counters.retransmits,
+            sent: counters.sent.throughput,
+            received: counters.received.throughput,
+            buffers,
+        }
+    }
+}
+
+/// Reads back the payload of `LinkStats::ping_feature` from the PONG
+fn ping_sent_at(feature: &FeatureRef<'_>) -> Option<Duration> {
+    let payload = feature.as_bytes();
+
+    if payload.len() != 16 {
+        return None;
+    }
+
+    let secs = u64::from_le_bytes(payload[..8].try_into().ok()?);
+    let nanos = u64::from_le_bytes(payload[8..].try_into().ok()?);
+
+    if nanos >= 1_000_000_000 {
+        return None;
+    }
+
+    Some(Duration::new(secs, nanos as u32))
+}
+
+/// RFC 6298: `SRTT <- 7/8 * SRTT + 1/8 * R'`
+fn smooth(smoothed: Option<Duration>, sample: Duration) -> Duration {
+    match smoothed {
+        Some(smoothed) => (smoothed * 7 + sample) / 8,
+        None => sample,
+    }
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+    use crate::frame::{Frame, FrameRef, Type};
+    use scroll::{Pread, Pwrite, LE};
+
+    #[test]
+    fn test_rtt_from_pong() {
+        let stats = LinkStats::default();
+        let ping = stats.ping_feature();
+
+        std::thread::sleep(Duration::from_millis(20));
+
+        let mut pong = [0_u8; 23];
+        pong.pwrite_with(Frame::new(Type::Data, BufferID::PONG, 0, Some(ping)), 0, LE)
+            .expect("Should write");
+        let pong = pong.pread_with::<FrameRef>(0, LE).expect("Should parse");
+
+        stats.ponged(&pong.feature.expect("Should have the PING payload"));
+        // not the payload of a PING
+        stats.ponged(&FeatureRef::new(&[1, 2, 3]).expect("Should have bytes"));
+        stats.received(30, 2);
+
+        let snapshot = stats.snapshot(HashMap::new());
+        let rtt = snapshot.rtt.expect("Should measure the round-trip time");
+
+        assert!(rtt >= Duration::from_millis(20));
+        assert_eq!(Some(rtt), snapshot.smoothed_rtt);
+        assert_eq!(30, snapshot.received.bytes);
+        assert_eq!(2, snapshot.received.frames);
+    }
+
+    #[test]
+    fn test_smoothing_and_rates() {
+        assert_eq!(
+            Duration::from_millis(100),
+            smooth(None, Duration::from_millis(100))
+        );
+        assert_eq!(
+            Duration::from_millis(120),
+            smooth(Some(Duration::from_millis(100)), Duration::from_millis(260))
+        );
+
+        let start = Instant::now();
+        let mut counter = Counter::new(start);
+        counter.add(start, 100, 2);
+        counter.add(start + Duration::from_millis(500), 100, 2);
+        // the window isn't over yet
+        assert_eq!(0.0, counter.throughput.frames_per_second);
+
+        counter.roll(start + Duration::from_secs(2));
+        assert_eq!(100.0, counter.throughput.bytes_per_second);
+        assert_eq!(2.0, counter.throughput.frames_per_second);
+        assert_eq!(200, counter.throughput.bytes);
+    }
+}
@@ -0,0 +1,636 @@
+//! The transport of the ARNetwork datagrams between us and the Drone
+//!
+//! * [`UdpTransport`] - the d2c & c2d UDP sockets, set up by the TCP handshake
+//! * [`MemoryTransport`] - an in-memory channel pair, e.g. for testing without any network
+use crate::{
+    bind_d2c,
+    handshake::{perform_handshake, Request, Response},
+    local_ip, Config, ConnectionError, SHUTDOWN_POLL_INTERVAL,
+};
+use log::{debug, info, warn};
+use std::{
+    fmt, io,
+    net::{SocketAddr, UdpSocket},
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Mutex,
+    },
+};
+
+/// Sends and receives the datagrams of a [`Drone`](crate::Drone)
+///
+/// A datagram contains one or more frames.
+pub trait Transport: fmt::Debug + Send + Sync + 'static {
+    fn send(&self, datagram: &[u8]) -> io::Result<()>;
+
+    /// Receives a datagram from the Drone into `buf`, returning its length.
+    ///
+    /// The Listener's `buf` is [`MAX_DATAGRAM_SIZE`](crate::frame::MAX_DATAGRAM_SIZE) long,
+    /// so it can hold any datagram.
+    ///
+    /// It shouldn't block for longer than `SHUTDOWN_POLL_INTERVAL` (100ms), so the Listener
+    /// can notice the disconnection. Returns `Ok(None)` if nothing was received in the meantime.
+    fn recv(&self, buf: &mut [u8]) -> io::Result<Option<usize>>;
+
+    /// Sets up the session again once the connection is `ConnectionState::Lost`,
+    /// see [`Config::reconnect`].
+    fn reconnect(&self) -> Result<(), ConnectionError> {
+        Ok(())
+    }
+
+    /// The latest handshake response, `None` for transports without a handshake
+    fn handshake_response(&self) -> Option<Response> {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub struct UdpTransport {
+    d2c: UdpSocket,
+    c2d: UdpSocket,
+    init_addr: SocketAddr,
+    handshake_request: Request,
+    handshake_response: Mutex<Response>,
+    /// The port can change when reconnecting
+    c2d_addr: Mutex<SocketAddr>,
+}
+
+impl UdpTransport {
+    /// * Binds the d2c socket at `Config::d2c_port`
+    /// * Performs Handshake at `Config::init_port`
+    /// * Binds the c2d socket, which sends to the `c2d_port` of the handshake response
+    /// * Applies the `qos_mode` of the handshake response to both sockets
+    pub fn connect(config: &Config) -> Result<Self, ConnectionError> {
+        let local_ip = match config.local_addr {
+            Some(local_addr) => local_addr,
+            None => {
+                local_ip(config.drone_addr).ok_or(ConnectionError::DroneAddr(config.drone_addr))?
+            }
+        };
+
+        let d2c_addr = SocketAddr::new(local_ip, config.d2c_port);
+        let io_error = |addr| move |error| ConnectionError::Io { error, addr };
+
+        let d2c = bind_d2c(d2c_addr)?;
+        // so the Listener can notice the disconnection
+        d2c.set_read_timeout(Some(SHUTDOWN_POLL_INTERVAL))
+            .map_err(io_error(d2c_addr))?;
+        // the actual port when it's ephemeral
+        let d2c_port = d2c.local_addr().map_err(io_error(d2c_addr))?.port();
+        info!("{}: Listening", SocketAddr::new(local_ip, d2c_port));
+
+        let init_addr = SocketAddr::new(config.drone_addr, config.init_port);
+        info!("Init address {}", &init_addr);
+
+        let handshake_request = Request::new(config, d2c_port);
+        let handshake_response = perform_handshake(init_addr, &handshake_request)?;
+        let c2d_addr = SocketAddr::new(config.drone_addr, handshake_response.c2d_port);
+
+        // an ephemeral port, so every Drone gets its own
+        let local_c2d = SocketAddr::new(local_ip, 0);
+        let c2d = UdpSocket::bind(local_c2d).map_err(io_error(local_c2d))?;
+        info!("{}: Sending commands", c2d_addr);
+
+        let transport = Self {
+            d2c,
+            c2d,
+            init_addr,
+            handshake_request,
+            handshake_response: Mutex::new(handshake_response.clone()),
+            c2d_addr: Mutex::new(c2d_addr),
+        };
+        transport
+            .apply_qos(&handshake_response)
+            .map_err(io_error(local_c2d))?;
+
+        Ok(transport)
+    }
+
+    fn apply_qos(&self, handshake_response: &Response) -> io::Result<()> {
+        handshake_response.apply_qos(&self.d2c)?;
+        handshake_response.apply_qos(&self.c2d)
+    }
+
+    fn lock_handshake_response(&self) -> std::sync::MutexGuard<'_, Response> {
+        self.handshake_response
+            .lock()
+            .expect("Handshake response lock poisoned")
+    }
+
+    fn lock_c2d_addr(&self) -> std::sync::MutexGuard<'_, SocketAddr> {
+        self.c2d_addr.lock().expect("c2d address lock poisoned")
+    }
+}
+
+impl Transport for UdpTransport {
+    fn send(&self, datagram: &[u8]) -> io::Result<()> {
+        let c2d_addr = *self.lock_c2d_addr();
+        let size = self.c2d.send_to(datagram, c2d_addr)?;
+
+        if size == datagram.len() {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                format!("Sent {} bytes out of {}", size, datagram.len()),
+            ))
+        }
+    }
+
+    fn recv(&self, buf: &mut [u8]) -> io::Result<Option<usize>> {
+        match self.d2c.recv_from(buf) {
+            Ok((bytes_read, origin)) if origin.ip() == self.init_addr.ip() => {
+                debug!("Received: {} bytes from {}", bytes_read, origin);
+
+                Ok(Some(bytes_read))
+            }
+            Ok((bytes_read, origin)) => {
+                debug!("Ignoring {} bytes from {}", bytes_read, origin);
+
+                Ok(None)
+            }
+            Err(err)
+                if err.kind() == io::ErrorKind::WouldBlock
+                    || err.kind() == io::ErrorKind::TimedOut =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Performs the handshake again, the Drone might give us a new `c2d_port`
+    fn reconnect(&self) -> Result<(), ConnectionError> {
+        let handshake_response = perform_handshake(self.init_addr, &self.handshake_request)?;
+
+        *self.lock_c2d_addr() = SocketAddr::new(self.init_addr.ip(), handshake_response.c2d_port);
+
+        if let Err(error) = self.apply_qos(&handshake_response) {
+            warn!("Applying the QoS of the new session failed: {}", error);
+        }
+        *self.lock_handshake_response() = handshake_response;
+
+        Ok(())
+    }
+
+    fn handshake_response(&self) -> Option<Response> {
+        Some(self.lock_handshake_response().clone())
+    }
+}
+
+/// One end of an in-memory datagram channel pair
+///
+/// ```
+/// use arsdk_rs::transport::{MemoryTransport, Transport};
+///
+/// let (controller, drone) = MemoryTransport::pair();
+///
+/// drone.send(&[2, 0, 0, 8, 0, 0, 0, 1]).expect("Should send");
+///
+/// let mut buf = [0_u8; 8];
+/// assert_eq!(Some(8), controller.recv(&mut buf).expect("Should receive"));
+/// ```
+#[derive(Debug)]
+pub struct MemoryTransport {
+    sender: Mutex<Sender<Vec<u8>>>,
+    receiver: Mutex<Receiver<Vec<u8>>>,
+}
+
+impl MemoryTransport {
+    /// Whatever is sent on one end is received on the other
+    pub fn pair() -> (Self, Self) {
+        let (left_tx, left_rx) = channel();
+        let (right_tx, right_rx) = channel();
+
+        let left = Self {
+            sender: Mutex::new(left_tx),
+            receiver: Mutex::new(right_rx),
+        };
+        let right = Self {
+            sender: Mutex::new(right_tx),
+            receiver: Mutex::new(left_rx),
+        };
+
+        (left, right)
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, datagram: &[u8]) -> io::Result<()> {
+        self.sender
+            .lock()
+            .expect("Sender lock poisoned")
+            .send(datagram.to_vec())
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+
+    fn recv(&self, buf: &mut [u8]) -> io::Result<Option<usize>> {
+        let receiver = self.receiver.lock().expect("Receiver lock poisoned");
+
+        match receiver.recv_timeout(SHUTDOWN_POLL_INTERVAL) {
+            Ok(datagram) => {
+                // like UDP, the rest of a datagram which doesn't fit in `buf` is lost
+                let bytes_read = datagram.len().min(buf.len());
+                buf[..bytes_read].copy_from_slice(&datagram[..bytes_read]);
+
+                Ok(Some(bytes_read))
+            }
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(io::ErrorKind::BrokenPipe.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod transport_tests {
+    use super::*;
+    use crate::{
+        command::Feature,
+        event::{Event, Filter},
+        frame::{BufferID, Frame, Type, MAX_DATAGRAM_SIZE},
+        DeliveryStatus, Drone, Error, ReceiveStats, ReconnectPolicy, SEND_RETRIES,
+    };
+    use scroll::{Pread, LE};
+    use std::{
+        net::Ipv4Addr,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::{Duration, Instant},
+    };
+
+    /// Fails to send the next `failures` datagrams on `BufferID::CDAck`
+    #[derive(Debug)]
+    struct FlakyTransport {
+        failures: Arc<AtomicUsize>,
+        transport: MemoryTransport,
+    }
+
+    impl Transport for FlakyTransport {
+        fn send(&self, datagram: &[u8]) -> io::Result<()> {
+            let cd_ack = datagram.get(1) == Some(&BufferID::CDAck.into());
+            let failing = cd_ack
+                && self
+                    .failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |failures| {
+                        failures.checked_sub(1)
+                    })
+                    .is_ok();
+
+            if failing {
+                Err(io::ErrorKind::ConnectionRefused.into())
+            } else {
+                self.transport.send(datagram)
+            }
+        }
+
+        fn recv(&self, buf: &mut [u8]) -> io::Result<Option<usize>> {
+            self.transport.recv(buf)
+        }
+    }
+
+    /// Its handshake takes a while, like `UdpTransport` retrying an unreachable Drone
+    #[derive(Debug)]
+    struct SlowReconnectTransport {
+        handshake: Duration,
+        transport: MemoryTransport,
+    }
+
+    impl Transport for SlowReconnectTransport {
+        fn send(&self, datagram: &[u8]) -> io::Result<()> {
+            self.transport.send(datagram)
+        }
+
+        fn recv(&self, buf: &mut [u8]) -> io::Result<Option<usize>> {
+            self.transport.recv(buf)
+        }
+
+        fn reconnect(&self) -> Result<(), ConnectionError> {
+            std::thread::sleep(self.handshake);
+
+            Ok(())
+        }
+    }
+
+    fn recv_frame(transport: &MemoryTransport) -> Frame {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut buf = vec![0_u8; MAX_DATAGRAM_SIZE];
+
+        while Instant::now() < deadline {
+            if let Some(bytes_read) = transport.recv(&mut buf).expect("Should receive") {
+                return buf[..bytes_read]
+                    .pread_with(0, LE)
+                    .expect("Should deserialize Frame");
+            }
+        }
+
+        panic!("No Frame received");
+    }
+
+    fn config() -> Config {
+        Config::builder(Ipv4Addr::LOCALHOST)
+            .send_disconnection(false)
+            .build()
+    }
+
+    /// The connection is `Lost` after 100ms of silence & reconnected right away
+    fn reconnecting_config() -> Config {
+        Config::builder(Ipv4Addr::LOCALHOST)
+            .send_disconnection(false)
+            .degraded_timeout(Duration::from_millis(50))
+            .connection_timeout(Duration::from_millis(100))
+            .reconnect(ReconnectPolicy {
+                initial_backoff: Duration::from_millis(10),
+                max_backoff: Duration::from_millis(10),
+                max_attempts: None,
+            })
+            .build()
+    }
+
+    fn connect() -> (Drone, MemoryTransport) {
+        let (controller, drone) = MemoryTransport::pair();
+
+        let connected =
+            Drone::with_transport(config(), controller).expect("Should connect over the transport");
+
+        (connected, drone)
+    }
+
+    /// Acknowledges the next Frame the `drone` receives, which has to be a `Type::DataWithAck` one
+    fn acknowledge_next(drone: MemoryTransport) -> std::thread::JoinHandle<MemoryTransport> {
+        std::thread::spawn(move || {
+            let frame = recv_frame(&drone);
+            assert_eq!(Type::DataWithAck, frame.frame_type);
+            assert_eq!(BufferID::CDAck, frame.buffer_id);
+
+            // Ack on buffer 139 (128 + CDAck) carrying the acknowledged sequence id
+            let ack = [1, 139, 0, 8, 0, 0, 0, frame.sequence_id];
+            drone.send(&ack).expect("Should send the Ack");
+
+            drone
+        })
+    }
+
+    // PING on buffer 0, sequence 4, 23 bytes
+    const PING: [u8; 23] = [
+        2, 0, 4, 23, 0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 57, 252, 225, 47, 0, 0, 0, 0,
+    ];
+
+    fn assert_pong(pong: Frame) {
+        assert_eq!(Type::Data, pong.frame_type);
+        assert_eq!(BufferID::PONG, pong.buffer_id);
+        assert_eq!(
+            Some(Feature::Unknown {
+                feature: 9,
+                data: PING[8..].to_vec(),
+            }),
+            pong.feature
+        );
+    }
+
+    #[test]
+    fn test_ping_pong() {
+        let (_connected, drone) = connect();
+
+        drone.send(&PING).expect("Should send PING");
+
+        assert_pong(recv_frame(&drone));
+    }
+
+    #[test]
+    fn test_acknowledged_frame() {
+        let (connected, drone) = connect();
+        let acknowledging = acknowledge_next(drone);
+
+        let frame = Frame::for_drone(
+            &connected,
+            Type::DataWithAck,
+            BufferID::CDAck,
+            Some(Feature::ArDrone3(None)),
+        );
+
+        assert_eq!(
+            DeliveryStatus::Acked,
+            connected
+                .send_frame_with_ack(frame)
+                .expect("Should send the Frame")
+        );
+
+        acknowledging.join().expect("Drone thread panicked");
+    }
+
+    #[test]
+    fn test_duplicates_are_acknowledged_but_not_published() {
+        let (connected, drone) = connect();
+        let events = connected.subscribe_with(Filter::feature(1));
+
+        // DataWithAck on DCNavdata, sequence 5
+        // ArDrone3 - PilotingState - FlyingStateChanged - 2
+        let flying_state = [4, 127, 5, 15, 0, 0, 0, 1, 4, 1, 0, 2, 0, 0, 0];
+        drone.send(&flying_state).expect("Should send the Frame");
+        // retransmitted, as if our Ack was lost
+        drone.send(&flying_state).expect("Should send the Frame");
+
+        for _ in 0..2 {
+            let ack = recv_frame(&drone);
+
+            assert_eq!(Type::Ack, ack.frame_type);
+            assert_eq!(BufferID::Ack(127), ack.buffer_id);
+            // the acknowledged sequence id
+            let acknowledged: Option<u8> = ack.feature.as_ref().map(Into::into);
+            assert_eq!(Some(5), acknowledged);
+        }
+
+        match events.recv_timeout(Duration::from_secs(2)) {
+            Ok(Event::Frame(_)) => {}
+            other => panic!("Expected the FlyingStateChanged Frame, got: {:?}", other),
+        }
+        assert!(events.recv_timeout(Duration::from_millis(200)).is_err());
+
+        assert_eq!(
+            ReceiveStats {
+                received: 1,
+                duplicates: 1,
+                missed: 0,
+            },
+            connected.receive_stats(BufferID::DCNavdata)
+        );
+    }
+
+    #[test]
+    fn test_send_failures() {
+        let (controller, drone) = MemoryTransport::pair();
+        let failures = Arc::new(AtomicUsize::new(0));
+        let transport = FlakyTransport {
+            failures: failures.clone(),
+            transport: controller,
+        };
+        let connected =
+            Drone::with_transport(config(), transport).expect("Should connect over the transport");
+        let events = connected.subscribe();
+
+        let take_off = || {
+            Frame::for_drone(
+                &connected,
+                Type::DataWithAck,
+                BufferID::CDAck,
+                Some(Feature::ArDrone3(None)),
+            )
+        };
+
+        // the first send & all the retries fail
+        failures.store(SEND_RETRIES + 1, Ordering::SeqCst);
+        match connected.send_frame_with_ack(take_off()) {
+            Err(Error::Send { buffer_id: 11, .. }) => {}
+            other => panic!("Expected a send failure, got: {:?}", other),
+        }
+        let send_failed = events
+            .iter()
+            .find(|event| matches!(event, Event::SendFailed { .. }));
+        assert_eq!(
+            Some(Event::SendFailed {
+                buffer_id: 11,
+                kind: io::ErrorKind::ConnectionRefused,
+            }),
+            send_failed
+        );
+
+        // transient failures are retried & the Command sender is still running
+        failures.store(SEND_RETRIES, Ordering::SeqCst);
+        connected
+            .send_frame(take_off())
+            .expect("Should queue the Frame");
+
+        let frame = recv_frame(&drone);
+        assert_eq!(BufferID::CDAck, frame.buffer_id);
+    }
+
+    #[test]
+    fn test_reconnection() {
+        let (controller, _drone) = MemoryTransport::pair();
+        let transport = SlowReconnectTransport {
+            handshake: Duration::from_millis(0),
+            transport: controller,
+        };
+        let connected = Drone::with_transport(reconnecting_config(), transport)
+            .expect("Should connect over the transport");
+        let events = connected.subscribe();
+
+        let reconnection = events
+            .iter()
+            .filter(|event| matches!(event, Event::Reconnecting { .. } | Event::Reconnected))
+            .take(2)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![Event::Reconnecting { attempt: 1 }, Event::Reconnected],
+            reconnection
+        );
+    }
+
+    #[test]
+    fn test_disconnect_during_reconnection() {
+        let (controller, _drone) = MemoryTransport::pair();
+        let transport = SlowReconnectTransport {
+            handshake: Duration::from_secs(5),
+            transport: controller,
+        };
+        let connected = Drone::with_transport(reconnecting_config(), transport)
+            .expect("Should connect over the transport");
+        let events = connected.subscribe();
+
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, Event::Reconnecting { .. })));
+
+        let disconnecting = Instant::now();
+        connected.disconnect();
+
+        assert!(
+            disconnecting.elapsed() < Duration::from_secs(1),
+            "Disconnecting waited for the handshake"
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    mod asynchronous_tests {
+        use super::*;
+        use crate::asynchronous;
+        use tokio::time::timeout;
+
+        async fn connect(config: Config, transport: impl Transport) -> asynchronous::Drone {
+            asynchronous::Drone::with_transport(config, transport)
+                .await
+                .expect("Should connect over the transport")
+        }
+
+        #[tokio::test]
+        async fn test_ping_pong() {
+            let (controller, drone) = MemoryTransport::pair();
+            let connected = connect(config(), controller).await;
+
+            drone.send(&PING).expect("Should send PING");
+
+            // the Listener & the Command sender don't need this runtime
+            assert_pong(recv_frame(&drone));
+
+            connected.disconnect().await;
+            assert!(!connected.is_connected());
+        }
+
+        #[tokio::test]
+        async fn test_acknowledged_frame() {
+            let (controller, drone) = MemoryTransport::pair();
+            let connected = connect(config(), controller).await;
+            let acknowledging = acknowledge_next(drone);
+
+            let frame = Frame::for_async_drone(
+                &connected,
+                Type::DataWithAck,
+                BufferID::CDAck,
+                Some(Feature::ArDrone3(None)),
+            );
+
+            assert_eq!(
+                DeliveryStatus::Acked,
+                connected
+                    .send_frame_with_ack(frame)
+                    .await
+                    .expect("Should send the Frame")
+            );
+
+            acknowledging.join().expect("Drone thread panicked");
+        }
+
+        #[tokio::test]
+        async fn test_disconnect_during_reconnection() {
+            let (controller, _drone) = MemoryTransport::pair();
+            let transport = SlowReconnectTransport {
+                handshake: Duration::from_secs(5),
+                transport: controller,
+            };
+            let connected = connect(reconnecting_config(), transport).await;
+            let mut events = connected.subscribe();
+
+            let reconnecting = async {
+                while let Some(event) = events.recv().await {
+                    if let Event::Reconnecting { attempt } = event {
+                        return attempt;
+                    }
+                }
+
+                panic!("No Reconnecting event");
+            };
+            assert_eq!(Ok(1), timeout(Duration::from_secs(2), reconnecting).await);
+
+            let disconnecting = Instant::now();
+            connected.disconnect().await;
+
+            assert!(
+                disconnecting.elapsed() < Duration::from_secs(1),
+                "Disconnecting waited for the handshake"
+            );
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Connection-loss detection
+//!
+//! The Drone PINGs us regularly, so even an idle connection receives frames.
+//! When no frame (PINGs included) arrives for a while the connection is first
+//! `Degraded` and then `Lost`.
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+pub const DEFAULT_DEGRADED_TIMEOUT: Duration = Duration::from_secs(1);
+pub const DEFAULT_CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often the watchdog checks the connection
+pub(crate) const WATCHDOG_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Frames are being received
+    Connected,
+    /// No frame has been received for `Config::degraded_timeout`
+    Degraded,
+    /// No frame has been received for `Config::connection_timeout`
+    Lost,
+}
+
+#[derive(Debug)]
+struct Heartbeat {
+    last_received: Instant,
+    state: ConnectionState,
+}
+
+#[derive(Debug)]
+pub(crate) struct Watchdog {
+    degraded_timeout: Duration,
+    connection_timeout: Duration,
+    heartbeat: Mutex<Heartbeat>,
+}
+
+impl Watchdog {
+    pub(crate) fn new(degraded_timeout: Duration, connection_timeout: Duration) -> Self {
+        Self {
+            degraded_timeout,
+            connection_timeout,
+            heartbeat: Mutex::new(Heartbeat {
+                last_received: Instant::now(),
+                state: ConnectionState::Connected,
+            }),
+        }
+    }
+
+    pub(crate) fn state(&self) -> ConnectionState {
+        self.lock().state
+    }
+
+    pub(crate) fn last_received(&self) -> Instant {
+        self.lock().last_received
+    }
+
+    /// Records a received frame.
+    ///
+    /// Returns the new `ConnectionState` if it changed.
+    pub(crate) fn received(&self, now: Instant) -> Option<ConnectionState> {
+        let mut heartbeat = self.lock();
+        heartbeat.last_received = now;
+
+        Self::transition(&mut heartbeat, ConnectionState::Connected)
+    }
+
+    /// Checks for how long we haven't received any frame.
+    ///
+    /// Returns the new `ConnectionState` if it changed.
+    pub(crate) fn check(&self, now: Instant) -> Option<ConnectionState> {
+        let mut heartbeat = self.lock();
+        let silence = now.saturating_duration_since(heartbeat.last_received);
+
+        let state = if silence >= self.connection_timeout {
+            ConnectionState::Lost
+        } else if silence >= self.degraded_timeout {
+            ConnectionState::Degraded
+        } else {
+            ConnectionState::Connected
+        };
+
+        Self::transition(&mut heartbeat, state)
+    }
+
+    fn transition(heartbeat: &mut Heartbeat, state: ConnectionState) -> Option<ConnectionState> {
+        if heartbeat.state == state {
+            None
+        } else {
+            heartbeat.state = state;
+            Some(state)
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Heartbeat> {
+        self.heartbeat.lock().expect("Heartbeat lock poisoned")
+    }
+}
+
+#[cfg(test)]
+mod watchdog_tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_states() {
+        let watchdog = Watchdog::new(Duration::from_secs(1), Duration::from_secs(5));
+        let start = watchdog.last_received();
+
+        assert_eq!(ConnectionState::Connected, watchdog.state());
+        assert_eq!(None, watchdog.check(start + Duration::from_millis(500)));
+
+        assert_eq!(
+            Some(ConnectionState::Degraded),
+            watchdog.check(start + Duration::from_secs(1))
+        );
+        assert_eq!(None, watchdog.check(start + Duration::from_secs(2)));
+
+        assert_eq!(
+            Some(ConnectionState::Lost),
+            watchdog.check(start + Duration::from_secs(5))
+        );
+        assert_eq!(ConnectionState::Lost, watchdog.state());
+
+        let reconnected = start + Duration::from_secs(6);
+        assert_eq!(
+            Some(ConnectionState::Connected),
+            watchdog.received(reconnected)
+        );
+        assert_eq!(None, watchdog.received(reconnected));
+        assert_eq!(reconnected, watchdog.last_received());
+
+        // lost again when nothing follows the reconnection
+        assert_eq!(
+            Some(ConnectionState::Lost),
+            watchdog.check(reconnected + Duration::from_secs(5))
+        );
+    }
+}
@@ -22,30 +22,26 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let drone = Bebop2::connect(PARROT_SPHINX_CONFIG)?;
 
     info!("Takeoff!");
-    drone.take_off()?;
+    let status = drone.take_off()?;
+    info!("Takeoff: {:?}", status);
 
     info!("Wait 5 seconds and fly UP");
     delay_for(Duration::from_secs(5)).await;
-    for i in 0..254 {
-        drone.up(i)?;
-        delay_for(Duration::from_millis(25)).await;
-    }
+    drone.up();
+    delay_for(Duration::from_secs(6)).await;
+    drone.hover();
 
     info!("Wait 5 seconds and fly DOWN");
     delay_for(Duration::from_secs(5)).await;
-
-    for i in 0..220 {
-        drone.down(i)?;
-        delay_for(Duration::from_millis(25)).await;
-    }
+    drone.down();
+    delay_for(Duration::from_secs(5)).await;
+    drone.hover();
 
     info!("Hover for 4 seconds before landing");
     delay_for(Duration::from_secs(4)).await;
 
-    for _ in 0..50 {
-        drone.landing()?;
-        delay_for(Duration::from_millis(25)).await;
-    }
+    let status = drone.landing()?;
+    info!("Landing: {:?}", status);
 
     loop {}
     // })
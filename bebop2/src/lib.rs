@@ -1,18 +1,21 @@
 use arsdk_rs::{
     command::Feature,
     frame::{BufferID, Frame, Type},
+    PilotingCommand,
 };
 
 pub use arsdk_rs::{
-    ardrone3::{ArDrone3, MediaStreaming, Piloting, PCMD},
+    ardrone3::{media_streaming, piloting, ArDrone3},
     prelude::*,
+    Setpoint,
 };
 
 pub mod prelude {
     pub use crate::Bebop2;
     pub use arsdk_rs::{
-        ardrone3::{ArDrone3, Piloting, PCMD},
+        ardrone3::{piloting, ArDrone3},
         prelude::*,
+        Setpoint,
     };
 }
 
@@ -21,15 +24,21 @@ pub struct Bebop2 {
 }
 
 impl Bebop2 {
+    /// Connects and starts sending `PCMD`s, see [`Drone::start_piloting`]
+    ///
+    /// The `Product::Bebop2` profile is used, unless the `config` already has one.
     pub fn connect(config: Config) -> Result<Self, ConnectionError> {
-        let drone = Drone::connect(config)?;
+        let drone = Drone::connect(config.or_product(Product::Bebop2))?;
+        drone.start_piloting(PilotingCommand::PCMD);
 
         Ok(Self { drone })
     }
 
     /// - Captain #Ferris 🦀 :Take off... 🛫
-    pub fn take_off(&self) -> Result<(), Error> {
-        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(Piloting::TakeOff)));
+    ///
+    /// Blocks until the drone acknowledges the command.
+    pub fn take_off(&self) -> Result<DeliveryStatus, Error> {
+        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::TakeOff)));
 
         let frame = Frame::for_drone(
             &self.drone,
@@ -38,43 +47,32 @@ impl Bebop2 {
             Some(feature),
         );
 
-        self.drone.send_frame(frame)
+        self.drone.send_frame_with_ack(frame)
     }
 
-    pub fn up(&self, sequence_id: u8) -> Result<(), Error> {
-        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(Piloting::PCMD(PCMD {
-            flag: true,
-            roll: 0,
-            pitch: 0,
-            yaw: 0,
-            gaz: 100,
-            timestamp: Utc::now(),
-            sequence_id,
-        }))));
-
-        let frame = Frame::for_drone(&self.drone, Type::Data, BufferID::CDNonAck, Some(feature));
-
-        self.drone.send_frame(frame)
+    /// Climbs until [`hover`](Self::hover) is called
+    pub fn up(&self) {
+        self.drone.set_gaz(100)
     }
 
-    pub fn down(&self, sequence_id: u8) -> Result<(), Error> {
-        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(Piloting::PCMD(PCMD {
-            flag: true,
-            roll: 0,
-            pitch: 0,
-            yaw: 0,
-            gaz: -100,
-            timestamp: Utc::now(),
-            sequence_id,
-        }))));
+    /// Descends until [`hover`](Self::hover) is called
+    pub fn down(&self) {
+        self.drone.set_gaz(-100)
+    }
 
-        let frame = Frame::for_drone(&self.drone, Type::Data, BufferID::CDNonAck, Some(feature));
+    /// Stays in place
+    pub fn hover(&self) {
+        self.drone.set_setpoint(Setpoint::default())
+    }
 
-        self.drone.send_frame(frame)
+    /// The `PCMD` values that are sent every `PILOTING_INTERVAL`
+    pub fn set_setpoint(&self, setpoint: Setpoint) {
+        self.drone.set_setpoint(setpoint)
     }
 
-    pub fn landing(&self) -> Result<(), Error> {
-        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(Piloting::Landing)));
+    /// Blocks until the drone acknowledges the command.
+    pub fn landing(&self) -> Result<DeliveryStatus, Error> {
+        let feature = Feature::ArDrone3(Some(ArDrone3::Piloting(piloting::Command::Landing)));
 
         let frame = Frame::for_drone(
             &self.drone,
@@ -83,6 +81,6 @@ impl Bebop2 {
             Some(feature),
         );
 
-        self.drone.send_frame(frame)
+        self.drone.send_frame_with_ack(frame)
     }
 }
@@ -0,0 +1,57 @@
+use arsdk_emulator::{Emulator, FlyingState};
+use bebop2::prelude::*;
+use std::{
+    net::Ipv4Addr,
+    thread,
+    time::{Duration, Instant},
+};
+
+fn connect(emulator: &Emulator) -> Bebop2 {
+    let config = Config::builder(Ipv4Addr::LOCALHOST)
+        .init_port(emulator.init_addr().port())
+        .d2c_port(0)
+        .local_addr(Ipv4Addr::LOCALHOST)
+        .build();
+
+    Bebop2::connect(config).expect("Should connect to the emulator")
+}
+
+fn wait_for_flying_state(emulator: &Emulator, expected: FlyingState) {
+    let deadline = Instant::now() + Duration::from_secs(2);
+
+    while emulator.flying_state() != expected {
+        assert!(
+            Instant::now() < deadline,
+            "Expected {:?}, got {:?}",
+            expected,
+            emulator.flying_state()
+        );
+
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_take_off_up_and_landing() {
+    let emulator =
+        Emulator::start(arsdk_emulator::Config::localhost()).expect("Should start the emulator");
+    let bebop2 = connect(&emulator);
+
+    assert_eq!(
+        DeliveryStatus::Acked,
+        bebop2.take_off().expect("Should send TakeOff")
+    );
+    wait_for_flying_state(&emulator, FlyingState::Hovering);
+
+    bebop2.up();
+    wait_for_flying_state(&emulator, FlyingState::Flying);
+
+    bebop2.hover();
+    wait_for_flying_state(&emulator, FlyingState::Hovering);
+
+    assert_eq!(
+        DeliveryStatus::Acked,
+        bebop2.landing().expect("Should send Landing")
+    );
+    wait_for_flying_state(&emulator, FlyingState::Landed);
+}
@@ -9,8 +9,8 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     loop {
         std::thread::sleep(std::time::Duration::from_secs(2));
-        let turn_right = PilotState {
-            flag: true,
+        let turn_right = Pcmd {
+            flag: 1,
             speed: 0,
             turn: i8::MAX,
         };
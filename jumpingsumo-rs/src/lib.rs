@@ -1,14 +1,20 @@
 use arsdk_rs::{
     command::Feature::JumpingSumo as JumpingSumoFeature,
     frame::{BufferID, Frame, Type as FrameType},
-    jumping_sumo::PilotState,
-    jumping_sumo::{Anim, Class::*, PilotingID::*},
-    Config, ConnectionError, Drone, Error,
+    jumping_sumo::{
+        animations::{self, JumpType},
+        piloting::Pcmd,
+        Class::*,
+    },
+    Config, ConnectionError, Drone, Error, PilotingCommand, Product, Setpoint,
 };
 
 pub mod prelude {
     pub use crate::JumpingSumo;
-    pub use arsdk_rs::{jumping_sumo::PilotState, prelude::*};
+    pub use arsdk_rs::{
+        jumping_sumo::{animations::JumpType, piloting::Pcmd},
+        prelude::*,
+    };
 }
 
 pub struct JumpingSumo {
@@ -19,66 +25,81 @@ const TURN_ANGLE: i8 = 30;
 const FORWARD_SPEED: i8 = 100;
 
 impl JumpingSumo {
+    /// Connects and starts sending the `Pcmd`, see [`Drone::start_piloting`]
+    ///
+    /// The `Product::JumpingSumo` profile is used, unless the `config` already has one.
     pub fn connect(config: Config) -> Result<Self, ConnectionError> {
-        Ok(Self {
-            drone: Drone::connect(config)?,
-        })
+        let drone = Drone::connect(config.or_product(Product::JumpingSumo))?;
+        drone.start_piloting(PilotingCommand::PilotState);
+
+        Ok(Self { drone })
     }
 
     pub fn forward(&self) -> Result<(), Error> {
-        self.drive(PilotState {
-            flag: true,
+        self.drive(Pcmd {
+            flag: 1,
             speed: FORWARD_SPEED,
             turn: 0,
         })
     }
 
     pub fn backwards(&self) -> Result<(), Error> {
-        self.drive(PilotState {
-            flag: true,
+        self.drive(Pcmd {
+            flag: 1,
             speed: -FORWARD_SPEED,
             turn: 0,
         })
     }
 
     pub fn turn_left(&self) -> Result<(), Error> {
-        self.drive(PilotState {
-            flag: true,
+        self.drive(Pcmd {
+            flag: 1,
             speed: 0,
             turn: -TURN_ANGLE,
         })
     }
 
     pub fn turn_right(&self) -> Result<(), Error> {
-        self.drive(PilotState {
-            flag: true,
+        self.drive(Pcmd {
+            flag: 1,
             speed: 0,
             turn: TURN_ANGLE,
         })
     }
 
     pub fn stop(&self) -> Result<(), Error> {
-        self.drive(PilotState {
-            flag: false,
+        self.drive(Pcmd {
+            flag: 0,
             speed: 0,
             turn: 0,
         })
     }
 
-    pub fn drive(&self, state: PilotState) -> Result<(), Error> {
-        let feature = JumpingSumoFeature(Piloting(Pilot(state)));
-        let frame = Frame::for_drone(
-            &self.drone,
-            FrameType::Data,
-            BufferID::CDNonAck,
-            Some(feature),
-        );
+    /// The `state` is sent every `PILOTING_INTERVAL` until the next call
+    pub fn drive(&self, state: Pcmd) -> Result<(), Error> {
+        let setpoint = if state.flag != 0 {
+            Setpoint {
+                pitch: state.speed,
+                yaw: state.turn,
+                ..Setpoint::default()
+            }
+        } else {
+            Setpoint::default()
+        };
 
-        self.drone.send_frame(frame)
+        self.drone.set_setpoint(setpoint);
+
+        Ok(())
     }
 
+    /// A long jump, see [`JumpingSumo::jump_with`]
     pub fn jump(&self) -> Result<(), Error> {
-        let feature = JumpingSumoFeature(Animations(Anim::Jump));
+        self.jump_with(JumpType::Long)
+    }
+
+    pub fn jump_with(&self, jump_type: JumpType) -> Result<(), Error> {
+        let jump = animations::Jump { r#type: jump_type };
+        let feature = JumpingSumoFeature(Animations(animations::Command::Jump(jump)));
         let frame = Frame::for_drone(
             &self.drone,
             FrameType::DataWithAck,
@@ -0,0 +1,64 @@
+use arsdk_emulator::Emulator;
+use arsdk_rs::{
+    command::Feature,
+    jumping_sumo::{animations, piloting, Class},
+};
+use jumpingsumo_rs::prelude::*;
+use std::{
+    net::Ipv4Addr,
+    thread,
+    time::{Duration, Instant},
+};
+
+fn connect(emulator: &Emulator) -> JumpingSumo {
+    let config = Config::builder(Ipv4Addr::LOCALHOST)
+        .init_port(emulator.init_addr().port())
+        .d2c_port(0)
+        .local_addr(Ipv4Addr::LOCALHOST)
+        .arstream2(None)
+        .build();
+
+    JumpingSumo::connect(config).expect("Should connect to the emulator")
+}
+
+fn wait_for_command(emulator: &Emulator, expected: Feature) {
+    let deadline = Instant::now() + Duration::from_secs(2);
+
+    while !emulator.commands().contains(&expected) {
+        assert!(
+            Instant::now() < deadline,
+            "Expected {:?}, got {:?}",
+            expected,
+            emulator.commands()
+        );
+
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_forward_and_jump() {
+    let emulator =
+        Emulator::start(arsdk_emulator::Config::localhost()).expect("Should start the emulator");
+    let jumping_sumo = connect(&emulator);
+
+    jumping_sumo.forward().expect("Should send Pilot");
+    wait_for_command(
+        &emulator,
+        Feature::JumpingSumo(Class::Piloting(piloting::Command::Pcmd(piloting::Pcmd {
+            flag: 1,
+            speed: 100,
+            turn: 0,
+        }))),
+    );
+
+    jumping_sumo.jump().expect("Should send Jump");
+    wait_for_command(
+        &emulator,
+        Feature::JumpingSumo(Class::Animations(animations::Command::Jump(
+            animations::Jump {
+                r#type: animations::JumpType::Long,
+            },
+        ))),
+    );
+}